@@ -0,0 +1,243 @@
+use std::fs::{self, DirBuilder, Permissions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::os::unix::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::{Command as Process, Stdio};
+use std::time::Instant;
+use std::{env, thread};
+use directories::ProjectDirs;
+use zeroize::Zeroize;
+use crate::cli::commands::copy_with_auto_clear;
+use crate::domain::app::agent_protocol::{AgentRequest, AgentResponse};
+use crate::domain::app::error::AppError;
+use crate::domain::cli::commands::{Command, VaultCommand};
+use crate::repository::vault::vault_router::VaultRouter;
+use crate::services::agent_service::AgentService;
+use crate::utils::constants::{AGENT_IO_TIMEOUT, AGENT_POLL_INTERVAL, AGENT_SOCKET_ENV, AGENT_SOCKET_NAME, AGENT_START_TIMEOUT};
+
+pub fn socket_path() -> PathBuf {
+    if let Some(path) = env::var_os(AGENT_SOCKET_ENV) {
+        return PathBuf::from(path);
+    }
+    let dir = ProjectDirs::from("com", "passman", "Passman")
+        .and_then(|dirs| dirs.runtime_dir().map(Path::to_path_buf))
+        .unwrap_or_else(|| env::temp_dir().join(format!("passman-{}", euid())));
+    dir.join(AGENT_SOCKET_NAME)
+}
+
+// runs the agent in the foreground until it is asked to stop
+pub fn run() -> Result<(), AppError> {
+    if request(&AgentRequest::List).is_ok() {
+        return Err(AppError::Other("The agent is already running".to_string()));
+    }
+    let path = socket_path();
+    let dir = path.parent().ok_or(AppError::Other("Invalid agent socket path".to_string()))?;
+    DirBuilder::new().recursive(true).mode(0o700).create(dir)
+        .map_err(|e| AppError::Other(format!("Failed to create agent directory: {}", e)))?;
+    check_dir(dir)?;
+    // a socket left behind by an agent that did not shut down cleanly
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path)
+        .map_err(|e| AppError::Other(format!("Failed to bind agent socket: {}", e)))?;
+    fs::set_permissions(&path, Permissions::from_mode(0o600))
+        .and_then(|_| listener.set_nonblocking(true))
+        .map_err(|e| AppError::Other(format!("Failed to set up agent socket: {}", e)))?;
+
+    let mut agent = AgentService::new(VaultRouter);
+    loop {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                if serve(&mut agent, stream) {
+                    break;
+                }
+            }
+            // polling lets expired sessions be locked even when nobody asks
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                agent.sweep();
+                thread::sleep(AGENT_POLL_INTERVAL.to_std().unwrap());
+            }
+            Err(e) => {
+                agent.lock(None);
+                let _ = fs::remove_file(&path);
+                return Err(AppError::Other(format!("Agent socket failed: {}", e)));
+            }
+        }
+    }
+    agent.lock(None);
+    let _ = fs::remove_file(&path);
+    Ok(())
+}
+
+// spawns the agent in its own process group so it outlives the shell command
+pub fn start() -> Result<String, AppError> {
+    if request(&AgentRequest::List).is_ok() {
+        return Ok("The agent is already running".to_string());
+    }
+    let exe = env::current_exe()
+        .map_err(|e| AppError::Other(format!("Failed to locate passman: {}", e)))?;
+    Process::new(exe)
+        .args(["agent", "run"])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .process_group(0)
+        .spawn()
+        .map_err(|e| AppError::Other(format!("Failed to start agent: {}", e)))?;
+    let deadline = Instant::now() + AGENT_START_TIMEOUT.to_std().unwrap();
+    while Instant::now() < deadline {
+        if request(&AgentRequest::List).is_ok() {
+            return Ok(format!("Agent listening on {}", socket_path().display()));
+        }
+        thread::sleep(AGENT_POLL_INTERVAL.to_std().unwrap());
+    }
+    Err(AppError::Other("The agent did not start".to_string()))
+}
+
+pub fn request(request: &AgentRequest) -> Result<AgentResponse, AppError> {
+    let path = socket_path();
+    let not_running = || AppError::Other("The agent is not running".to_string());
+    // never hand a master password to a socket someone else could have planted
+    check_dir(path.parent().ok_or_else(not_running)?).map_err(|_| not_running())?;
+    let mut stream = UnixStream::connect(&path).map_err(|_| not_running())?;
+    set_timeouts(&stream).map_err(|_| not_running())?;
+    let mut line = serde_json::to_string(request)
+        .map_err(|e| AppError::Other(format!("Serialization failed: {}", e)))?;
+    line.push('\n');
+    let sent = stream.write_all(line.as_bytes());
+    line.zeroize();
+    sent.map_err(|e| AppError::Other(format!("Failed to reach the agent: {}", e)))?;
+    let mut response = String::new();
+    BufReader::new(stream).read_line(&mut response)
+        .map_err(|e| AppError::Other(format!("Failed to read agent response: {}", e)))?;
+    let parsed = serde_json::from_str(&response)
+        .map_err(|_| AppError::Other("Invalid agent response".to_string()));
+    response.zeroize();
+    parsed
+}
+
+// answers get and copy from the agent, none when it is not running or does not hold the vault
+pub fn forward(vault: &str, cmd: &Command) -> Option<Result<Option<String>, AppError>> {
+    let request = match cmd {
+        Command::Vault(VaultCommand::Get(entry, field)) => AgentRequest::Get {
+            vault: vault.to_string(),
+            entry: entry.clone(),
+            field: field.clone(),
+        },
+        Command::Vault(VaultCommand::Copy(entry, field)) => AgentRequest::Copy {
+            vault: vault.to_string(),
+            entry: entry.clone(),
+            field: field.clone(),
+        },
+        _ => return None,
+    };
+    match self::request(&request) {
+        Err(_) | Ok(AgentResponse::Locked) => None,
+        Ok(AgentResponse::Ok(value)) => Some(Ok(value)),
+        Ok(AgentResponse::Error(message)) => Some(Err(AppError::Other(message))),
+    }
+}
+
+// returns whether the agent should stop
+fn serve(agent: &mut AgentService<VaultRouter>, mut stream: UnixStream) -> bool {
+    if stream.set_nonblocking(false).and_then(|_| set_timeouts(&stream)).is_err() {
+        return false;
+    }
+    let (response, stop) = match peer_uid(&stream) {
+        Ok(uid) if uid == euid() => {
+            let mut line = String::new();
+            let read = BufReader::new(&stream).read_line(&mut line);
+            let request = read.ok().and_then(|_| serde_json::from_str::<AgentRequest>(&line).ok());
+            line.zeroize();
+            match request {
+                Some(AgentRequest::Stop) => (AgentResponse::Ok(Some("Agent stopped".to_string())), true),
+                Some(request) => (handle(agent, request), false),
+                None => (AgentResponse::Error("Invalid request".to_string()), false),
+            }
+        }
+        _ => (AgentResponse::Error("Permission denied".to_string()), false),
+    };
+    if let Ok(mut json) = serde_json::to_string(&response) {
+        json.push('\n');
+        let _ = stream.write_all(json.as_bytes());
+        json.zeroize();
+    }
+    stop
+}
+
+fn handle(agent: &mut AgentService<VaultRouter>, request: AgentRequest) -> AgentResponse {
+    let result = match request {
+        AgentRequest::Unlock { vault, password } => agent.unlock(&vault, &password)
+            .map(|_| Some(format!("Vault {} added to the agent", vault))),
+        AgentRequest::Get { vault, .. } | AgentRequest::Copy { vault, .. } if !agent.is_unlocked(&vault) => {
+            return AgentResponse::Locked;
+        }
+        AgentRequest::Get { vault, entry, field } => agent.field(&vault, &entry, &field, false).map(Some),
+        // the agent outlives the caller, so it owns the clipboard until it is cleared,
+        // and a missing clipboard is reported back instead of taking the agent down
+        AgentRequest::Copy { vault, entry, field } => agent.field(&vault, &entry, &field, true)
+            .and_then(copy_with_auto_clear)
+            .map(|_| Some(format!("Copied {} to clipboard", field.to_string().to_lowercase()))),
+        AgentRequest::List => Ok(Some(agent.list())),
+        AgentRequest::Lock { vault } => {
+            let locked = agent.lock(vault.as_deref());
+            Ok(Some(format!("Locked {} vault{}", locked, if locked == 1 { "" } else { "s" })))
+        }
+        AgentRequest::Stop => Ok(None),
+    };
+    match result {
+        Ok(value) => AgentResponse::Ok(value),
+        Err(AppError::Other(message)) => AgentResponse::Error(message),
+        Err(err) => AgentResponse::Error(err.to_string()),
+    }
+}
+
+fn check_dir(dir: &Path) -> Result<(), AppError> {
+    let meta = fs::metadata(dir)
+        .map_err(|e| AppError::Other(format!("Failed to inspect agent directory: {}", e)))?;
+    if meta.uid() != euid() || meta.mode() & 0o077 != 0 {
+        return Err(AppError::Other(format!("{} must be private to the current user", dir.display())));
+    }
+    Ok(())
+}
+
+fn set_timeouts(stream: &UnixStream) -> io::Result<()> {
+    let timeout = Some(AGENT_IO_TIMEOUT.to_std().unwrap());
+    stream.set_read_timeout(timeout)?;
+    stream.set_write_timeout(timeout)
+}
+
+fn euid() -> u32 {
+    // geteuid cannot fail
+    unsafe { libc::geteuid() }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let mut cred = libc::ucred { pid: 0, uid: 0, gid: 0 };
+    let mut len = std::mem::size_of::<libc::ucred>() as libc::socklen_t;
+    let result = unsafe {
+        libc::getsockopt(
+            stream.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PEERCRED,
+            &mut cred as *mut libc::ucred as *mut libc::c_void,
+            &mut len,
+        )
+    };
+    match result {
+        0 => Ok(cred.uid),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "android")))]
+fn peer_uid(stream: &UnixStream) -> io::Result<u32> {
+    let (mut uid, mut gid) = (0, 0);
+    match unsafe { libc::getpeereid(stream.as_raw_fd(), &mut uid, &mut gid) } {
+        0 => Ok(uid),
+        _ => Err(io::Error::last_os_error()),
+    }
+}
@@ -1,33 +1,114 @@
 use std::{fs, thread};
+use std::path::Path;
+use colored::Colorize;
 use secrecy::{ExposeSecret, SecretBox};
-use crate::cli::io::{read_line_hidden_with, read_line_with, clear_clipboard, clear_console, copy_to_clipboard, confirmation_prompt, confirmation_prompt_with};
+use crate::cli::io::{read_line_hidden_with, read_line_with, read_multiline_with, clear_clipboard, clear_console, copy_to_clipboard, confirmation_prompt, confirmation_prompt_with, choice_prompt};
 use crate::domain::app::error::AppError;
 use crate::domain::app::state::AppState;
-use crate::domain::cli::commands::{Command, VaultCommand};
+use crate::cli::parser::parse_password_params;
+use crate::domain::app::config::CONFIG_KEYS;
+#[cfg(unix)]
+use crate::cli::agent;
+#[cfg(unix)]
+use crate::domain::app::agent_protocol::{AgentRequest, AgentResponse};
+use crate::domain::cli::commands::{AgentCommand, Command, ConfigCommand, VaultCommand};
 use crate::domain::cli::field::Field;
+use crate::domain::cli::import_params::ImportFormat;
+use crate::domain::cli::passphrase_params::PassphraseParams;
 use crate::domain::cli::password_params::PasswordParams;
-use crate::repository::vault::vault_manager::VaultManager;
+use crate::domain::vault::password_entry::{PasswordEntry, PasswordEntryDebug};
+use crate::domain::vault::sync_report::{MergeConflict, Resolution};
+use crate::domain::vault::vault_locator::VaultLocator;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::services::import_service::ImportService;
+use crate::services::breach_service::BreachService;
+use crate::services::config_service::ConfigService;
 use crate::services::password_service::PasswordService;
 use crate::services::vault_service::VaultService;
-use crate::utils::constants::{CLIPBOARD_TTL};
-use crate::utils::validation::{validate_arg, validate_password, validate_password_strength};
+use crate::utils::validation::{validate_arg, validate_password, validate_password_strength, validate_text};
 
 const HELP_FILE_PATH: &str = "HELP.txt";
 type CommandResult = Result<Option<String>, AppError>;
 
-pub fn execute_cmd(
+pub fn execute_cmd<V: VaultManagerTrait>(
     cmd: Command,
-    vault_service: &VaultService<VaultManager>,
+    vault_service: &VaultService<V>,
     state: &mut AppState
 ) -> CommandResult {
     match cmd {
         Command::Exit => exit(),
         Command::Help(cmd) => help(cmd),
         Command::Clear => clear(),
-        Command::Analyze(pwd) => analyze_password(pwd),
+        Command::Analyze(pwd, breaches) => analyze_password(pwd, breaches),
         Command::Generate(params, copy) => generate_password(params, copy),
+        Command::Passphrase(params, copy) => generate_passphrase(params, copy),
         Command::Vault(cmd) => vault_cmd(cmd, vault_service, state),
         Command::Panic => panic(vault_service, state),
+        Command::Config(cmd) => config_cmd(cmd),
+        Command::Agent(cmd) => agent_cmd(cmd, vault_service, state),
+    }
+}
+
+#[cfg(unix)]
+fn agent_cmd<V: VaultManagerTrait>(cmd: AgentCommand, vault: &VaultService<V>, state: &mut AppState) -> CommandResult {
+    let request = match cmd {
+        AgentCommand::Start => return agent::start().map(Some),
+        AgentCommand::Run => return agent::run().map(|_| None),
+        AgentCommand::Stop => AgentRequest::Stop,
+        AgentCommand::List => AgentRequest::List,
+        AgentCommand::Add(None) => {
+            vault.is_accessible(state)?;
+            let session = state.session.as_ref().unwrap();
+            let password = SecretBox::new(Box::new(session.secret.expose_secret().clone()));
+            AgentRequest::Unlock { vault: session.name.clone(), password }
+        }
+        AgentCommand::Add(Some(name)) => {
+            vault.exists(&name)?;
+            let password = read_line_hidden_with("Enter master password for vault: ");
+            validate_password(&password)?;
+            AgentRequest::Unlock { vault: name, password: SecretBox::new(Box::from(password)) }
+        }
+        AgentCommand::Lock(name) => AgentRequest::Lock { vault: name },
+    };
+    match agent::request(&request)? {
+        AgentResponse::Ok(message) => Ok(message),
+        AgentResponse::Locked => Err(AppError::Other("Vault is not unlocked in the agent".to_string())),
+        AgentResponse::Error(message) => Err(AppError::Other(message)),
+    }
+}
+
+#[cfg(not(unix))]
+fn agent_cmd<V: VaultManagerTrait>(_: AgentCommand, _: &VaultService<V>, _: &mut AppState) -> CommandResult {
+    Err(AppError::Other("The agent is only available on unix".to_string()))
+}
+
+fn config_cmd(cmd: ConfigCommand) -> CommandResult {
+    match cmd {
+        ConfigCommand::Show => {
+            let overrides: Vec<String> = CONFIG_KEYS.iter()
+                .map(|key| ConfigService::env_var(key))
+                .filter(|var| std::env::var_os(var).is_some())
+                .collect();
+            let mut result = format!("# {}\n{}", ConfigService::path().display(), ConfigService::current());
+            if !overrides.is_empty() {
+                result.push_str(&format!("\n# overridden by {}", overrides.join(", ")));
+            }
+            Ok(Some(result))
+        }
+        ConfigCommand::Get(key) => ConfigService::get(&key).map(Some),
+        ConfigCommand::Set(key, value) => {
+            if key == "generator_policy" {
+                let flags: Vec<&str> = value.split_whitespace().collect();
+                parse_password_params(ConfigService::current().generator_length, &flags)?;
+            }
+            ConfigService::set(&key, &value)?;
+            let var = ConfigService::env_var(&key);
+            match std::env::var_os(&var) {
+                Some(_) => Ok(Some(format!("Saved, but {} is set and takes precedence", var))),
+                None => Ok(Some("Configuration updated".to_string())),
+            }
+        }
     }
 }
 
@@ -41,11 +122,12 @@ fn clear() -> CommandResult {
     Ok(None)
 }
 
-fn panic(vault: &VaultService<VaultManager>, state: &mut AppState) -> CommandResult {
+fn panic<V: VaultManagerTrait>(vault: &VaultService<V>, state: &mut AppState) -> CommandResult {
     if state.session.is_some() { // if in vault
         vault.close(state);
     }
-    clear_clipboard();
+    // nothing to clear without a clipboard
+    let _ = clear_clipboard();
     clear_console();
     exit()
 }
@@ -53,16 +135,35 @@ fn panic(vault: &VaultService<VaultManager>, state: &mut AppState) -> CommandRes
 fn generate_password(params: PasswordParams, copy: bool) -> CommandResult {
     let result = PasswordService::generate(params)?;
     if copy {
-        copy_to_clipboard(result);
+        copy_to_clipboard(result)?;
         Ok(Some("Generated password copied to clipboard".to_string()))
     } else {
         Ok(Some(result))
     }
 }
 
-fn analyze_password(password: String) -> CommandResult {
-    let (score, classification) = PasswordService::analyze(password);
-    Ok(Option::from(format!("Password score: {:.2} ({})", score, classification)))
+fn generate_passphrase(params: PassphraseParams, copy: bool) -> CommandResult {
+    let (passphrase, entropy) = PasswordService::passphrase(&params)?;
+    if copy {
+        copy_to_clipboard(passphrase)?;
+        Ok(Some(format!("Generated passphrase copied to clipboard ({:.1} bits of entropy)", entropy)))
+    } else {
+        Ok(Some(format!("{} ({:.1} bits of entropy)", passphrase, entropy)))
+    }
+}
+
+fn analyze_password(password: String, breaches: Option<String>) -> CommandResult {
+    let occurrences = match breaches {
+        Some(path) => Some(BreachService::occurrences(&mut BreachService::open(&path)?, &password)?),
+        None => None,
+    };
+    let mut result = PasswordService::analyze(&password, &[]).to_string();
+    match occurrences {
+        Some(0) => result.push_str("\nNot found in the breach list"),
+        Some(count) => result.push_str(&format!("\nFound {} times in the breach list", count).bright_red().to_string()),
+        None => {}
+    }
+    Ok(Some(result))
 }
 
 fn help(cmd: Option<String>) -> CommandResult {
@@ -93,16 +194,19 @@ fn help(cmd: Option<String>) -> CommandResult {
     }
 }
 
-fn vault_cmd(
+fn vault_cmd<V: VaultManagerTrait>(
     command: VaultCommand,
-    vault: &VaultService<VaultManager>,
+    vault: &VaultService<V>,
     state: &mut AppState
 ) -> CommandResult {
     match command {
-        VaultCommand::New(name) => {
-            if let Ok(_) = vault.exists(&name) {
+        VaultCommand::New(name, path) => {
+            if vault.exists(&name).is_ok() {
                 return Err(AppError::Other("Vault already exists".to_string()));
             }
+            if path.as_ref().is_some_and(|path| !VaultLocator::is_remote(path) && Path::new(path).exists()) {
+                return Err(AppError::Other("File already exists".to_string()));
+            }
             let password = read_line_hidden_with("Choose master password for vault: ");
             validate_password(&password)?;
             let confirm_password = read_line_hidden_with("Confirm master password: ");
@@ -112,10 +216,25 @@ fn vault_cmd(
             }
             validate_password_strength(&password)?;
             let secret = SecretBox::new(Box::from(password));
-            vault.create(&name, &secret);
+            if let Some(path) = &path {
+                vault.register(&name, path)?;
+            }
+            // a location is only kept for a vault that was actually created there
+            if let Err(e) = vault.create(&name, &secret) {
+                if path.is_some() {
+                    let _ = vault.unregister(&name);
+                }
+                return Err(e);
+            }
             Ok(None)
         }
-        VaultCommand::Open(name) => {
+        VaultCommand::Open(name, path) => {
+            if let Some(path) = &path {
+                if !VaultLocator::is_remote(path) && !Path::new(path).is_file() {
+                    return Err(AppError::Other("Vault not found".to_string()));
+                }
+                vault.register(&name, path)?;
+            }
             vault.exists(&name)?;
             let password = read_line_hidden_with("Enter master password for vault: ");
             validate_password(&password)?;
@@ -128,41 +247,74 @@ fn vault_cmd(
             Ok(None)
         },
         VaultCommand::List => {
-            Ok(Some(vault.list()))
+            Ok(Some(vault.list()?))
+        },
+        VaultCommand::Move(name, path) => {
+            let location = vault.relocate(&name, &path)?;
+            Ok(Some(format!("Vault moved to {}", location)))
+        },
+        VaultCommand::Rename(name, new_name) => {
+            vault.rename(&name, &new_name, state)?;
+            Ok(Some(format!("Vault renamed to {}", new_name)))
         },
         VaultCommand::Show(entry, expose) => {
             vault.is_accessible(state)?;
-            if entry.is_none() && expose {
-                if !confirmation_prompt_with("This will expose all credentials in the vault. Do you want to continue?")? {
-                    return Ok(None);
-                }
+            if entry.is_none() && expose && !confirmation_prompt_with("This will expose all credentials in the vault. Do you want to continue?")? {
+                return Ok(None);
             }
+            let entry = match entry {
+                Some(query) => Some(resolve_entry(&query, vault, state)?),
+                None => None,
+            };
             Ok(Some(vault.show(entry, expose, state)?))
         }
-        VaultCommand::Add(entry) => {
+        VaultCommand::Add(entry, params, copy) => {
             vault.is_accessible(state)?;
-            let duplicate_entry = state.session.as_mut().unwrap().vault.entries.iter().find(|e| e.name == entry);
-            if duplicate_entry.is_some() {
-                if !confirmation_prompt_with("Entry already exists. Do you want to update it?")? {
-                    return Ok(None);
-                } else {
-                    vault.delete_entry(&entry, state)?;
-                }
+            let exists = state.session.as_ref().unwrap().vault.entries.iter().any(|e| e.name == entry);
+            if exists && !confirmation_prompt_with("Entry already exists. Do you want to update it?")? {
+                return Ok(None);
             }
+            // the new credentials are collected and checked before the vault is touched
             let username = read_line_with("Username: ");
             validate_arg(&username, "username")?;
-            let password = read_line_hidden_with("Password: ");
-            validate_password(&password)?;
-            vault.add_entry(&entry, &username, &password, state);
-            Ok(None)
+            let generated = params.is_some();
+            let password = match params {
+                Some(params) => PasswordService::generate(params)?,
+                None => {
+                    let password = read_line_hidden_with("Password: ");
+                    validate_password(&password)?;
+                    password
+                }
+            };
+            match exists {
+                true => vault.replace_entry(&entry, &username, &password, state)?,
+                false => vault.add_entry(&entry, &username, &password, state)?,
+            }
+            if !generated {
+                return Ok(None);
+            }
+            store_generated(&entry, password, copy, vault, state)
         }
         VaultCommand::Update(entry, field, value) => {
             vault.is_accessible(state)?;
+            let value = match value {
+                Some(value) => value,
+                None => read_field_value(&field)?,
+            };
             if confirmation_prompt()? {
                 vault.update_entry(&entry, &field, &value, state)?;
             }
             Ok(None)
         }
+        VaultCommand::Regenerate(entry, params, copy) => {
+            vault.is_accessible(state)?;
+            if !confirmation_prompt()? {
+                return Ok(None);
+            }
+            let password = PasswordService::generate(params)?;
+            vault.update_entry(&entry, &Field::Password, &password, state)?;
+            store_generated(&entry, password, copy, vault, state)
+        }
         VaultCommand::Delete(entry) => {
             vault.is_accessible(state)?;
             if confirmation_prompt()? {
@@ -172,31 +324,222 @@ fn vault_cmd(
         }
         VaultCommand::Copy(entry, field) => {
             vault.is_accessible(state)?;
-            let entry_opt = state.session.as_mut().unwrap().vault.entries.iter().find(|e| e.name == entry);
-            if entry_opt.is_none() {
-                return Err(AppError::Other("Entry not found".to_string()));
+            let entry = resolve_entry(&entry, vault, state)?;
+            let text = vault.field(&entry, &field, state)?;
+            copy_with_auto_clear(text)?;
+            vault.mark_copied(&entry, state)?;
+            Ok(Some(format!("Copied {} to clipboard", field.to_string().to_lowercase())))
+        }
+        VaultCommand::Get(entry, field) => {
+            vault.is_accessible(state)?;
+            let entry = resolve_entry(&entry, vault, state)?;
+            Ok(Some(vault.field(&entry, &field, state)?))
+        }
+        VaultCommand::Otp(entry, copy) => {
+            vault.is_accessible(state)?;
+            let entry = resolve_entry(&entry, vault, state)?;
+            let (code, remaining) = vault.otp(&entry, state)?;
+            if copy {
+                copy_with_auto_clear(code)?;
+                vault.mark_copied(&entry, state)?;
+                Ok(Some(format!("Copied OTP code to clipboard ({}s left)", remaining)))
+            } else {
+                Ok(Some(format!("{} ({}s left)", code, remaining)))
+            }
+        }
+        VaultCommand::History(entry, expose) => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.history(&entry, expose, state)?))
+        }
+        VaultCommand::Revert(entry, version) => {
+            vault.is_accessible(state)?;
+            if confirmation_prompt()? {
+                vault.revert(&entry, version, state)?;
+                return Ok(Some(format!("Reverted {} to version {}", entry, version)));
             }
-            let entry = entry_opt.unwrap();
-            let text = match field {
-                Field::Username => entry.username.clone(),
-                Field::Password => entry.password.expose_secret().clone(),
+            Ok(None)
+        }
+        VaultCommand::Stale(days) => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.stale(days as i64, state)))
+        }
+        VaultCommand::Audit(params) => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.audit(&params, state)?.to_string()))
+        }
+        VaultCommand::BreachCheck(path) => {
+            vault.is_accessible(state)?;
+            let mut list = BreachService::open(&path)?;
+            let breached = vault.breach_check(&mut list, state)?;
+            if breached.is_empty() {
+                return Ok(Some("No stored passwords were found in the breach list".to_string()));
+            }
+            let lines: Vec<String> = breached.iter()
+                .map(|(name, count)| format!("{} (found {} times)", name, count))
+                .collect();
+            Ok(Some(format!("{}\n{} compromised passwords", lines.join("\n"), breached.len()).bright_red().to_string()))
+        }
+        VaultCommand::Search(query) => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.search(&query, state)))
+        }
+        VaultCommand::Import(params) => {
+            vault.is_accessible(state)?;
+            let data = fs::read_to_string(&params.path)
+                .map_err(|e| AppError::Other(format!("Failed to read {}: {}", params.path, e)))?;
+            let entries = match params.format {
+                ImportFormat::Encrypted => {
+                    let passphrase = read_line_hidden_with("Bundle passphrase: ");
+                    validate_password(&passphrase)?;
+                    ImportService::parse_bundle(&data, &SecretBox::new(Box::from(passphrase)))?
+                }
+                _ => ImportService::parse(&params.format, &data)?,
             };
-            copy_to_clipboard(text);
-
-            // launch auto-clear clipboard thread
-            thread::spawn(move || {
-                thread::sleep(CLIPBOARD_TTL.to_std().unwrap());
-                clear_clipboard();
-            });
-            Ok(Some(format!("Copied {} to clipboard", field.to_string().to_lowercase())))
+            let report = vault.import_entries(entries, &params.duplicates, state)?;
+            Ok(Some(format!("{} export: {}", params.format, report)))
+        }
+        VaultCommand::Export(params) => {
+            vault.is_accessible(state)?;
+            let passphrase = if params.format.is_plaintext() {
+                println!("{}", "A plaintext export contains every credential in the vault unencrypted.".bright_red());
+                let name = state.session.as_ref().unwrap().name.clone();
+                let confirmation = read_line_with(&format!("Type '{}' to export it as plaintext: ", name));
+                if confirmation != name {
+                    return Err(AppError::Other("Export cancelled".to_string()));
+                }
+                None
+            } else {
+                let passphrase = read_line_hidden_with("Choose export passphrase: ");
+                validate_password(&passphrase)?;
+                let confirm_passphrase = read_line_hidden_with("Confirm export passphrase: ");
+                if passphrase != confirm_passphrase {
+                    return Err(AppError::Other("Passphrases don't match".to_string()));
+                }
+                validate_password_strength(&passphrase)?;
+                Some(SecretBox::new(Box::from(passphrase)))
+            };
+            let path = Path::new(&params.path);
+            if path.exists() && !confirmation_prompt_with("File already exists. Do you want to overwrite it?")? {
+                return Ok(None);
+            }
+            let (data, omitted) = vault.export(&params.format, passphrase.as_ref(), state)?;
+            VaultStorage::write(path, data.as_bytes(), 0)?;
+            let exported = format!("Exported vault as {} to {}", params.format, params.path);
+            Ok(Some(match omitted {
+                Some(omitted) => format!("{}, {}", exported, omitted),
+                None => exported,
+            }))
+        }
+        VaultCommand::Sync => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.sync(state, resolve_conflict)?.to_string()))
         }
         VaultCommand::Destroy => {
             vault.is_accessible(state)?;
             if confirmation_prompt()? {
-                vault.delete(state);
+                vault.delete(state)?;
                 vault.close(state);
             }
             Ok(None)
         }
+        VaultCommand::ChangePassword => {
+            vault.is_accessible(state)?;
+            let current = read_line_hidden_with("Current master password: ");
+            validate_password(&current)?;
+            let password = read_line_hidden_with("New master password: ");
+            validate_password(&password)?;
+            let confirm_password = read_line_hidden_with("Confirm new master password: ");
+            validate_password(&confirm_password)?;
+            if password != confirm_password {
+                return Err(AppError::Other("Passwords don't match".to_string()));
+            }
+            validate_password_strength(&password)?;
+            let current = SecretBox::new(Box::from(current));
+            let secret = SecretBox::new(Box::from(password));
+            vault.change_password(&current, secret, state)?;
+            Ok(Some("Master password changed".to_string()))
+        }
+        VaultCommand::Backups => {
+            vault.is_accessible(state)?;
+            Ok(Some(vault.backups(state)?))
+        }
+        VaultCommand::Restore(backup) => {
+            vault.is_accessible(state)?;
+            if confirmation_prompt()? {
+                vault.restore(backup, state)?;
+                return Ok(Some(format!("Restored backup {}", backup)));
+            }
+            Ok(None)
+        }
     }
-}
\ No newline at end of file
+}
+
+fn read_field_value(field: &Field) -> Result<String, AppError> {
+    let value = match field {
+        Field::Notes => read_multiline_with("Notes (finish with an empty line):"),
+        Field::Custom { name, secret: true } => read_line_hidden_with(&format!("{}: ", name)),
+        Field::Otp => read_line_hidden_with("OTP secret or otpauth:// URI: "),
+        field => read_line_with(&format!("{}: ", field)),
+    };
+    validate_text(&value, &field.to_string())?;
+    Ok(value)
+}
+
+fn store_generated<V: VaultManagerTrait>(entry: &str, password: String, copy: bool, vault: &VaultService<V>, state: &mut AppState) -> CommandResult {
+    if !copy {
+        return Ok(Some("Stored generated password".to_string()));
+    }
+    copy_with_auto_clear(password)?;
+    vault.mark_copied(entry, state)?;
+    Ok(Some("Stored generated password and copied it to clipboard".to_string()))
+}
+
+pub fn copy_with_auto_clear(text: String) -> Result<(), AppError> {
+    copy_to_clipboard(text)?;
+
+    // launch auto-clear clipboard thread
+    thread::spawn(move || {
+        thread::sleep(ConfigService::current().clipboard_ttl().to_std().unwrap());
+        let _ = clear_clipboard();
+    });
+    Ok(())
+}
+
+fn resolve_entry<V: VaultManagerTrait>(query: &str, vault: &VaultService<V>, state: &mut AppState) -> Result<String, AppError> {
+    let mut matches = vault.find_entries(query, state);
+    match matches.len() {
+        0 => Err(AppError::Other("Entry not found".to_string())),
+        1 => Ok(matches.remove(0)),
+        _ => {
+            let choice = choice_prompt("Multiple entries match, select one", &matches)?;
+            Ok(matches.remove(choice))
+        }
+    }
+}
+
+fn resolve_conflict(conflict: &MergeConflict) -> Result<Resolution, AppError> {
+    println!("{} {}", "Conflicting changes to".bright_red(), conflict.name().bold());
+    let describe = |side: &str, entry: Option<&PasswordEntry>| match entry {
+        Some(entry) => format!("{}: {}", side, PasswordEntryDebug { entry, expose: false }),
+        None => format!("{}: deleted\n", side),
+    };
+    print!("{}", describe("Local", conflict.local));
+    print!("{}", describe("Remote", conflict.remote));
+    if let (Some(local), Some(remote)) = (conflict.local, conflict.remote) {
+        if local.password.expose_secret() != remote.password.expose_secret() {
+            println!("The passwords differ");
+        }
+    }
+    let newer = conflict.newer();
+    let label = |text: &str, resolution: Resolution| match resolution == newer {
+        true => format!("{} (newer)", text),
+        false => text.to_string(),
+    };
+    let options = vec![
+        label("Keep local", Resolution::Local),
+        label("Keep remote", Resolution::Remote),
+        "Keep both".to_string(),
+    ];
+    let choice = choice_prompt("Resolve", &options)?;
+    Ok([Resolution::Local, Resolution::Remote, Resolution::Both][choice])
+}
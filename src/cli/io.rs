@@ -1,10 +1,13 @@
+use std::fs::File;
 use std::io;
-use std::io::Write;
+use std::io::{BufRead, BufReader, Write};
 use std::process::Command;
 use arboard::Clipboard;
 use colored::Colorize;
-use rpassword::read_password;
+use rpassword::{prompt_password, read_password};
 use crate::domain::app::error::AppError;
+use crate::domain::cli::oneshot_args::PasswordSource;
+use crate::services::config_service::ConfigService;
 
 pub fn read_line() -> String {
     let mut input = String::new();
@@ -24,6 +27,18 @@ pub fn read_line_with(content: &str) -> String {
     read_line()
 }
 
+pub fn read_multiline_with(content: &str) -> String {
+    println!("{}", content);
+    let mut lines = Vec::new();
+    loop {
+        let line = read_line();
+        if line.is_empty() {
+            return lines.join("\n");
+        }
+        lines.push(line);
+    }
+}
+
 pub fn read_line_with_prefix(vault: Option<&str>) -> String {
     loop {
         print_prefix(vault);
@@ -40,6 +55,28 @@ pub fn read_line_hidden_with(content: &str) -> String {
     read_line_hidden()
 }
 
+pub fn read_secret_from(source: &PasswordSource, prompt: &str) -> Result<String, AppError> {
+    let line = match source {
+        PasswordSource::Prompt => prompt_password(prompt)
+            .map_err(|e| AppError::Other(format!("Failed to read password: {}", e)))?,
+        PasswordSource::Stdin => read_first_line(io::stdin().lock())?,
+        PasswordSource::Fd(fd) => {
+            // /dev/fd exposes inherited descriptors as files on linux and macos
+            let file = File::open(format!("/dev/fd/{}", fd))
+                .map_err(|e| AppError::Other(format!("Failed to open file descriptor {}: {}", fd, e)))?;
+            read_first_line(BufReader::new(file))?
+        }
+    };
+    Ok(line)
+}
+
+fn read_first_line(mut reader: impl BufRead) -> Result<String, AppError> {
+    let mut line = String::new();
+    reader.read_line(&mut line)
+        .map_err(|e| AppError::Other(format!("Failed to read password: {}", e)))?;
+    Ok(line.trim_end_matches(['\n', '\r']).to_string())
+}
+
 pub fn confirmation_prompt() -> Result<bool, AppError> {
     confirmation_prompt_with("Are you sure?")
 }
@@ -54,11 +91,24 @@ pub fn confirmation_prompt_with(message: &str) -> Result<bool, AppError> {
     }
 }
 
+pub fn choice_prompt(message: &str, options: &[String]) -> Result<usize, AppError> {
+    for (i, option) in options.iter().enumerate() {
+        println!("  {}. {}", i + 1, option);
+    }
+    let input = read_line_with(&format!("{} (1-{}): ", message, options.len()));
+    match input.parse::<usize>() {
+        Ok(choice) if (1..=options.len()).contains(&choice) => Ok(choice - 1),
+        _ => Err(AppError::Other("Invalid choice".to_string()))
+    }
+}
+
 pub fn print_prefix(vault: Option<&str>) {
-    match vault {
-        Some(vault) => print!("{}", format!("{}@passman $ ", vault).bright_cyan()),
-        None => print!("{}", "passman $ ".bright_cyan()),
+    let config = ConfigService::current();
+    if let Some(vault) = vault {
+        print!("{}", vault.color(config.vault_color()));
+        print!("{}", "@".color(config.prompt_color()));
     }
+    print!("{}", "passman $ ".color(config.prompt_color()));
     io::stdout().flush().expect("Failed to flush stdout");
 }
 
@@ -75,16 +125,21 @@ pub fn clear_console() {
     }
 }
 
-pub fn copy_to_clipboard(text: String) {
-    let mut clipboard = Clipboard::new().expect("Failed to initialize clipboard");
-    clipboard.set_text(text).expect("Failed to copy to clipboard");
+// headless machines have no clipboard, which is an error rather than a crash
+pub fn copy_to_clipboard(text: String) -> Result<(), AppError> {
+    let mut clipboard = Clipboard::new()
+        .map_err(|e| AppError::Other(format!("Failed to initialize clipboard: {}", e)))?;
+    clipboard.set_text(text)
+        .map_err(|e| AppError::Other(format!("Failed to copy to clipboard: {}", e)))
 }
 
-pub fn get_clipboard() -> String {
-    let mut clipboard = Clipboard::new().expect("Failed to initialize clipboard");
-    clipboard.get_text().expect("Failed to get clipboard text")
+pub fn get_clipboard() -> Result<String, AppError> {
+    let mut clipboard = Clipboard::new()
+        .map_err(|e| AppError::Other(format!("Failed to initialize clipboard: {}", e)))?;
+    clipboard.get_text()
+        .map_err(|e| AppError::Other(format!("Failed to get clipboard text: {}", e)))
 }
 
-pub fn clear_clipboard() {
-    copy_to_clipboard(String::new());
+pub fn clear_clipboard() -> Result<(), AppError> {
+    copy_to_clipboard(String::new())
 }
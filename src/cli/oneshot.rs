@@ -0,0 +1,125 @@
+use std::thread;
+use secrecy::SecretBox;
+#[cfg(unix)]
+use crate::cli::agent;
+use crate::cli::commands::execute_cmd;
+use crate::cli::io::{clear_clipboard, read_secret_from};
+use crate::cli::parser::{parse_cmd, parse_oneshot_args};
+use crate::domain::app::error::AppError;
+use crate::domain::app::state::AppState;
+use crate::domain::cli::commands::{AgentCommand, Command, VaultCommand};
+use crate::domain::cli::oneshot_args::OneShotArgs;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::repository::vault::vault_router::VaultRouter;
+use crate::services::config_service::ConfigService;
+use crate::services::vault_service::VaultService;
+use crate::utils::constants::{EXIT_FAILURE, EXIT_SUCCESS, EXIT_USAGE, EXIT_VAULT_ACCESS};
+use crate::utils::validation::validate_password;
+
+pub fn run_oneshot(argv: &[String]) -> i32 {
+    let args = match parse_oneshot_args(argv) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+    let cmd = match parse_cmd(&args.command) {
+        Ok(cmd) => cmd,
+        Err(err) => {
+            eprintln!("{}", err);
+            return EXIT_USAGE;
+        }
+    };
+
+    // an agent holding the vault answers without asking for the master password
+    #[cfg(unix)]
+    if let Some(result) = args.vault.as_deref().and_then(|vault| agent::forward(vault, &cmd)) {
+        return match result {
+            Ok(msg) => {
+                if let Some(m) = msg.filter(|m| !m.is_empty()) {
+                    println!("{}", m);
+                }
+                EXIT_SUCCESS
+            }
+            Err(err) => {
+                eprintln!("{}", err);
+                EXIT_FAILURE
+            }
+        };
+    }
+
+    let vault_service = VaultService::new(VaultRouter);
+    let mut state = AppState { session: None };
+    if requires_session(&cmd) {
+        if args.vault.is_none() {
+            eprintln!("{}", AppError::MissingArgument("vault".to_string()));
+            return EXIT_USAGE;
+        }
+        if let Err(err) = open_vault(&args, &vault_service, &mut state) {
+            eprintln!("{}", err);
+            return EXIT_VAULT_ACCESS;
+        }
+    }
+
+    let copies = copies_to_clipboard(&cmd);
+    let result = execute_cmd(cmd, &vault_service, &mut state);
+    vault_service.close(&mut state);
+    match result {
+        Ok(msg) => {
+            if let Some(m) = msg.filter(|m| !m.is_empty()) {
+                println!("{}", m);
+            }
+            if copies {
+                // the clipboard is owned by this process, so hold it until the ttl runs out
+                thread::sleep(ConfigService::current().clipboard_ttl().to_std().unwrap());
+                let _ = clear_clipboard();
+            }
+            EXIT_SUCCESS
+        }
+        Err(err) => {
+            eprintln!("{}", err);
+            EXIT_FAILURE
+        }
+    }
+}
+
+fn open_vault<V: VaultManagerTrait>(
+    args: &OneShotArgs,
+    vault_service: &VaultService<V>,
+    state: &mut AppState
+) -> Result<(), AppError> {
+    let name = args.vault.as_deref().unwrap();
+    if let Some(path) = &args.vault_path {
+        vault_service.register(name, path)?;
+    }
+    vault_service.exists(name)?;
+    let password = read_secret_from(&args.password, "Enter master password for vault: ")?;
+    validate_password(&password)?;
+    let secret = SecretBox::new(Box::from(password));
+    vault_service.open(name, &secret, state)
+}
+
+fn requires_session(cmd: &Command) -> bool {
+    match cmd {
+        Command::Vault(sub_cmd) => !matches!(
+            sub_cmd,
+            VaultCommand::New(..) | VaultCommand::Open(..) | VaultCommand::Close | VaultCommand::List
+                | VaultCommand::Move(..) | VaultCommand::Rename(..)
+        ),
+        Command::Agent(AgentCommand::Add(None)) => true,
+        _ => false,
+    }
+}
+
+fn copies_to_clipboard(cmd: &Command) -> bool {
+    matches!(
+        cmd,
+        Command::Generate(_, true) | Command::Passphrase(_, true) | Command::Vault(
+            VaultCommand::Copy(..)
+            | VaultCommand::Otp(_, true)
+            | VaultCommand::Add(_, Some(_), true)
+            | VaultCommand::Regenerate(_, _, true)
+        )
+    )
+}
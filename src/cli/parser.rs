@@ -1,10 +1,18 @@
+use std::path::Path;
 use crate::cli::io::get_clipboard;
 use crate::domain::app::error::AppError;
-use crate::domain::cli::commands::{Command, VaultCommand};
+use crate::domain::cli::audit_params::{AuditCheck, AuditParams, AuditSort};
+use crate::domain::cli::commands::{AgentCommand, Command, ConfigCommand, VaultCommand};
 use crate::domain::cli::field::Field;
+use crate::domain::cli::export_params::{ExportFormat, ExportParams};
+use crate::domain::cli::import_params::{DuplicateStrategy, ImportFormat, ImportParams};
+use crate::domain::cli::oneshot_args::{OneShotArgs, PasswordSource};
+use crate::domain::cli::passphrase_params::PassphraseParams;
 use crate::domain::cli::password_params::PasswordParams;
-use crate::utils::constants::PASSWORD_MIN_LEN;
-use crate::utils::validation::validate_arg;
+use crate::domain::vault::vault_locator::VaultLocator;
+use crate::services::config_service::ConfigService;
+use crate::utils::constants::{AUDIT_MAX_AGE_DAYS, PASSPHRASE_DEFAULT_SEPARATOR, PASSPHRASE_DEFAULT_WORDS, PASSPHRASE_SEPARATOR_MAX_LEN, VAULT_EXTENSION};
+use crate::utils::validation::{validate_arg, validate_path, validate_text};
 
 pub fn parse_cmd(input: &str) -> Result<Command, AppError> {
     let trimmed = input.trim();
@@ -25,21 +33,51 @@ pub fn parse_cmd(input: &str) -> Result<Command, AppError> {
                     Some(joined)
                 }
             };
-            Ok(Command::Help(cmd_arg.into()))
+            Ok(Command::Help(cmd_arg))
         }
         Some("clear" | "cls") => Ok(Command::Clear),
         Some("exit" | "quit" | "q") => Ok(Command::Exit),
         Some("generate" | "gen") => {
-            let length = args.get(0).and_then(|s| s.parse::<usize>().ok()).unwrap_or(PASSWORD_MIN_LEN);
-            let params = PasswordParams {
-                length,
-                avoid_ambiguous: opts.contains(&"-avoid-ambiguous"),
-            };
             let copy = opts.contains(&"-copy") || opts.contains(&"-cp") || opts.contains(&"-c");
+            if opts.contains(&"-words") || opts.contains(&"-w") {
+                let words = match args.first() {
+                    Some(n) => n.parse::<usize>().map_err(|_| AppError::InvalidArgument("words".to_string()))?,
+                    None => PASSPHRASE_DEFAULT_WORDS,
+                };
+                let separator = opts.iter()
+                    .find_map(|opt| opt.strip_prefix("-sep:").or_else(|| opt.strip_prefix("-separator:")))
+                    .unwrap_or(PASSPHRASE_DEFAULT_SEPARATOR);
+                if separator.chars().count() > PASSPHRASE_SEPARATOR_MAX_LEN {
+                    return Err(AppError::InvalidArgument("separator".to_string()));
+                }
+                let params = PassphraseParams {
+                    words,
+                    separator: separator.to_string(),
+                    capitalize: opts.contains(&"-capitalize") || opts.contains(&"-cap"),
+                    digit: opts.contains(&"-digit"),
+                    symbol: opts.contains(&"-symbol"),
+                };
+                return Ok(Command::Passphrase(params, copy));
+            }
+            let length = args.first().and_then(|s| s.parse::<usize>().ok());
+            let params = parse_configured_params(length, &opts)?;
             Ok(Command::Generate(params, copy))
         },
         Some("analyze" | "score") => {
-            let arg = args.get(0).map(|s| s.to_string());
+            let mut args = args.clone();
+            // -breaches takes the hash file after the password, never in place of it
+            let breaches = match opts.contains(&"-breaches") {
+                true => {
+                    if args.len() != 2 {
+                        return Err(AppError::MissingArgument("breaches".to_string()));
+                    }
+                    let path = args.pop().unwrap();
+                    validate_path(path)?;
+                    Some(path.to_string())
+                }
+                false => None,
+            };
+            let arg = args.first().map(|s| s.to_string());
             let password: String = if let Some(pass) = arg {
                 pass
             } else {
@@ -47,36 +85,95 @@ pub fn parse_cmd(input: &str) -> Result<Command, AppError> {
                 if !paste {
                     return Err(AppError::MissingArgument("password".to_string()));
                 }
-                get_clipboard()
+                get_clipboard()?
             };
-            Ok(Command::Analyze(password.to_string()))
+            Ok(Command::Analyze(password.to_string(), breaches))
         },
         Some("panic") => Ok(Command::Panic),
-        Some("vault" | "vlt") => parse_vault_cmd(&args, opts),
+        Some("config" | "cfg") => parse_config_cmd(&collected),
+        Some("vault" | "vlt") => parse_vault_cmd(rest_of_input(trimmed, 1), &args, opts),
+        Some("agent") => parse_agent_cmd(&args),
         _ => Err(AppError::InvalidCommand),
     }
 }
 
-pub fn parse_vault_cmd(args: &[&str], opts: Vec<&str>) -> Result<Command, AppError> {
+fn parse_agent_cmd(args: &[&str]) -> Result<Command, AppError> {
+    let get_name = || args.get(1).map(|name| {
+        validate_arg(name, "name")?;
+        Ok::<String, AppError>(name.to_string())
+    }).transpose();
+    let sub_cmd = match args.first() {
+        Some(&"start") => AgentCommand::Start,
+        Some(&"stop") => AgentCommand::Stop,
+        Some(&"run") => AgentCommand::Run,
+        None | Some(&"list" | &"status") => AgentCommand::List,
+        Some(&"add") => AgentCommand::Add(get_name()?),
+        Some(&"lock") => AgentCommand::Lock(get_name()?),
+        _ => return Err(AppError::InvalidCommand),
+    };
+    Ok(Command::Agent(sub_cmd))
+}
+
+// values are taken verbatim since a generator policy is itself a list of flags
+fn parse_config_cmd(words: &[&str]) -> Result<Command, AppError> {
+    let get_key = || {
+        let key = words.get(1).copied().ok_or(AppError::MissingArgument("key".to_string()))?;
+        validate_arg(key, "key")?;
+        Ok::<String, AppError>(key.to_string())
+    };
+    let sub_cmd = match words.first() {
+        None | Some(&"show") => ConfigCommand::Show,
+        Some(&"get") => ConfigCommand::Get(get_key()?),
+        Some(&"set") => {
+            let key = get_key()?;
+            let value = words[2..].join(" ");
+            if value.is_empty() {
+                return Err(AppError::MissingArgument("value".to_string()));
+            }
+            validate_text(&value, "value")?;
+            ConfigCommand::Set(key, value)
+        }
+        Some(&"unset" | &"reset") => ConfigCommand::Set(get_key()?, String::new()),
+        _ => return Err(AppError::InvalidCommand),
+    };
+    Ok(Command::Config(sub_cmd))
+}
+
+pub fn parse_vault_cmd(input: &str, args: &[&str], opts: Vec<&str>) -> Result<Command, AppError> {
     let get_arg = |index: usize, name: &str| {
         let arg = args
             .get(index)
-            .map(|s| *s)
+            .copied()
             .ok_or(AppError::MissingArgument(name.to_string()))?;
         validate_arg(arg, name)?;
-        Ok(arg)
+        Ok::<&str, AppError>(arg)
     };
-    let sub_cmd = match args.first() {
+    let generate = opts.contains(&"-generate") || opts.contains(&"-gen");
+    let copy = opts.contains(&"-copy") || opts.contains(&"-cp") || opts.contains(&"-c");
+    let sub_cmd: Result<VaultCommand, AppError> = match args.first() {
         Some(&"new" | &"create") => {
-            let name = get_arg(1, "name")?;
-            Ok(VaultCommand::New(name.to_string()))
+            let location = args.get(1).ok_or(AppError::MissingArgument("name".to_string()))?;
+            let (name, path) = parse_vault_location(location)?;
+            Ok(VaultCommand::New(name, path))
         },
         Some(&"open" | &"enter" | &"unlock") => {
-            let name = get_arg(1, "name")?;
-            Ok(VaultCommand::Open(name.to_string()))
+            let location = args.get(1).ok_or(AppError::MissingArgument("name".to_string()))?;
+            let (name, path) = parse_vault_location(location)?;
+            Ok(VaultCommand::Open(name, path))
         },
         Some(&"close" | &"exit" | &"lock") => Ok(VaultCommand::Close),
         Some(&"list" | &"lst") => Ok(VaultCommand::List),
+        Some(&"move" | &"mv") => {
+            let name = get_arg(1, "name")?;
+            let path = args.get(2).ok_or(AppError::MissingArgument("path".to_string()))?;
+            validate_path(path)?;
+            Ok(VaultCommand::Move(name.to_string(), path.to_string()))
+        },
+        Some(&"rename" | &"ren") => {
+            let name = get_arg(1, "name")?;
+            let new_name = get_arg(2, "new-name")?;
+            Ok(VaultCommand::Rename(name.to_string(), new_name.to_string()))
+        },
         Some(&"show" | &"inspect") => {
             let entry = args.get(1).map(|s| s.to_string());
             let expose = ["-expose", "-unmask"].iter().any(|opt| opts.contains(opt));
@@ -84,13 +181,57 @@ pub fn parse_vault_cmd(args: &[&str], opts: Vec<&str>) -> Result<Command, AppErr
         },
         Some(&"add") => {
             let entry = get_arg(1, "entry")?.to_string();
-            Ok(VaultCommand::Add(entry))
+            let params = match generate {
+                true => Some(parse_generate_params(args.get(2), &opts)?),
+                false => None,
+            };
+            Ok(VaultCommand::Add(entry, params, copy))
         },
         Some(&"update" | &"up") => {
             let entry = get_arg(1, "entry")?.to_string();
-            let field = parse_vault_field(opts.get(0).unwrap_or(&""))?;
-            let value = get_arg(2, "value")?.to_string();
-            Ok(VaultCommand::Update(entry, field, value))
+            let field = parse_field_opts(&opts)?.ok_or(AppError::MissingArgument("field".to_string()))?;
+            // free text is taken as typed, so words starting with a dash and runs of spaces are kept,
+            // and only the words before it count as options
+            if !matches!(field, Field::Username | Field::Password | Field::Tags) {
+                let tokens: Vec<&str> = input.split_whitespace().skip(1).collect();
+                let start = free_text_start(&tokens);
+                let opts: Vec<&str> = tokens[..start].iter().filter(|t| t.starts_with('-')).cloned().collect();
+                if opts.contains(&"-generate") || opts.contains(&"-gen") {
+                    return Err(AppError::InvalidArgument("generate".to_string()));
+                }
+                let field = parse_field_opts(&opts)?.ok_or(AppError::MissingArgument("field".to_string()))?;
+                // longer values are prompted for when left out
+                let value = match rest_of_input(input, start + 1) {
+                    "" => None,
+                    value => {
+                        validate_text(value, "value")?;
+                        Some(value.to_string())
+                    }
+                };
+                return Ok(Command::Vault(VaultCommand::Update(entry, field, value)));
+            }
+            if generate {
+                if field != Field::Password {
+                    return Err(AppError::InvalidArgument("generate".to_string()));
+                }
+                let params = parse_generate_params(args.get(2), &opts)?;
+                return Ok(Command::Vault(VaultCommand::Regenerate(entry, params, copy)));
+            }
+            // single word values take no more words
+            if args.len() > 3 {
+                return Err(AppError::InvalidArgument("value".to_string()));
+            }
+            let value = match field {
+                Field::Tags => {
+                    let tags = args.get(2).ok_or(AppError::MissingArgument("tags".to_string()))?;
+                    for tag in tags.split(',') {
+                        validate_arg(tag, "tags")?;
+                    }
+                    tags.to_string()
+                }
+                _ => get_arg(2, "value")?.to_string(),
+            };
+            Ok(VaultCommand::Update(entry, field, Some(value)))
         },
         Some(&"delete" | &"del") => {
             let entry = get_arg(1, "entry")?;
@@ -98,14 +239,114 @@ pub fn parse_vault_cmd(args: &[&str], opts: Vec<&str>) -> Result<Command, AppErr
         },
         Some(&"copy" | &"cp") => {
             let entry = get_arg(1, "entry")?;
-            let field_opt = opts.get(1);
-            let field = match field_opt {
-                Some(f) => parse_vault_field(f)?,
-                None => Field::Password,
-            };
+            let field = parse_field_opts(&opts)?.unwrap_or(Field::Password);
             Ok(VaultCommand::Copy(entry.to_string(), field))
         },
+        Some(&"get") => {
+            let entry = get_arg(1, "entry")?;
+            let field = parse_field_opts(&opts)?.unwrap_or(Field::Password);
+            Ok(VaultCommand::Get(entry.to_string(), field))
+        },
+        Some(&"otp" | &"totp") => {
+            let entry = get_arg(1, "entry")?;
+            let copy = ["-copy", "-cp", "-c"].iter().any(|opt| opts.contains(opt));
+            Ok(VaultCommand::Otp(entry.to_string(), copy))
+        },
+        Some(&"history" | &"versions") => {
+            let entry = get_arg(1, "entry")?;
+            let expose = ["-expose", "-unmask"].iter().any(|opt| opts.contains(opt));
+            Ok(VaultCommand::History(entry.to_string(), expose))
+        },
+        Some(&"revert") => {
+            let entry = get_arg(1, "entry")?;
+            let version = get_arg(2, "version")?
+                .parse::<usize>()
+                .map_err(|_| AppError::InvalidArgument("version".to_string()))?;
+            Ok(VaultCommand::Revert(entry.to_string(), version))
+        },
+        Some(&"stale" | &"old") => {
+            let days = get_arg(1, "days")?
+                .parse::<u32>()
+                .map_err(|_| AppError::InvalidArgument("days".to_string()))?;
+            Ok(VaultCommand::Stale(days))
+        },
+        Some(&"search" | &"find") => {
+            let query = get_arg(1, "query")?;
+            Ok(VaultCommand::Search(query.to_string()))
+        },
+        Some(&"import") => {
+            let format = match get_arg(1, "format")?.to_lowercase().as_str() {
+                "bitwarden" | "bw" => ImportFormat::Bitwarden,
+                "keepass" | "keepassxc" => ImportFormat::KeePass,
+                "1password" | "onepassword" | "op" => ImportFormat::OnePassword,
+                "chrome" | "firefox" | "browser" => ImportFormat::Chrome,
+                "encrypted" | "bundle" | "passman" => ImportFormat::Encrypted,
+                _ => return Err(AppError::InvalidArgument("format".to_string())),
+            };
+            let path = args.get(2).ok_or(AppError::MissingArgument("path".to_string()))?;
+            validate_path(path)?;
+            let duplicates = if opts.contains(&"-overwrite") {
+                DuplicateStrategy::Overwrite
+            } else if opts.contains(&"-rename") {
+                DuplicateStrategy::Rename
+            } else {
+                DuplicateStrategy::Skip
+            };
+            Ok(VaultCommand::Import(ImportParams { format, path: path.to_string(), duplicates }))
+        },
+        Some(&"audit" | &"health") => {
+            let mut params = AuditParams { checks: Vec::new(), sort: AuditSort::Severity, max_age_days: AUDIT_MAX_AGE_DAYS };
+            for opt in &opts {
+                let (flag, value) = opt.split_once(':').unwrap_or((opt, ""));
+                match flag {
+                    "-weak" => params.checks.push(AuditCheck::Weak),
+                    "-reused" => params.checks.push(AuditCheck::Reused),
+                    "-username" => params.checks.push(AuditCheck::UsernameMatch),
+                    "-old" => params.checks.push(AuditCheck::Old),
+                    "-days" => {
+                        params.max_age_days = value.parse::<u32>().map_err(|_| AppError::InvalidArgument("days".to_string()))?;
+                    }
+                    "-sort" => {
+                        params.sort = match value {
+                            "severity" => AuditSort::Severity,
+                            "name" => AuditSort::Name,
+                            "score" => AuditSort::Score,
+                            "age" => AuditSort::Age,
+                            _ => return Err(AppError::InvalidArgument("sort".to_string())),
+                        };
+                    }
+                    _ => return Err(AppError::InvalidArgument(flag.trim_start_matches('-').to_string())),
+                }
+            }
+            Ok(VaultCommand::Audit(params))
+        },
+        Some(&"breach-check" | &"breaches") => {
+            let path = args.get(1).ok_or(AppError::MissingArgument("hashfile".to_string()))?;
+            validate_path(path)?;
+            Ok(VaultCommand::BreachCheck(path.to_string()))
+        },
+        Some(&"export") => {
+            let format = match get_arg(1, "format")?.to_lowercase().as_str() {
+                "csv" => ExportFormat::Csv,
+                "json" => ExportFormat::Json,
+                "bitwarden" | "bw" => ExportFormat::Bitwarden,
+                "encrypted" | "bundle" | "passman" => ExportFormat::Encrypted,
+                _ => return Err(AppError::InvalidArgument("format".to_string())),
+            };
+            let path = args.get(2).ok_or(AppError::MissingArgument("path".to_string()))?;
+            validate_path(path)?;
+            Ok(VaultCommand::Export(ExportParams { format, path: path.to_string() }))
+        },
+        Some(&"sync") => Ok(VaultCommand::Sync),
         Some(&"destroy" | &"wipe") => Ok(VaultCommand::Destroy),
+        Some(&"passwd" | &"rekey") => Ok(VaultCommand::ChangePassword),
+        Some(&"backups" | &"bak") => Ok(VaultCommand::Backups),
+        Some(&"restore") => {
+            let backup = get_arg(1, "backup")?
+                .parse::<usize>()
+                .map_err(|_| AppError::InvalidArgument("backup".to_string()))?;
+            Ok(VaultCommand::Restore(backup))
+        },
         _ => return Err(AppError::InvalidCommand),
     };
     Ok(Command::Vault(sub_cmd?))
@@ -115,6 +356,163 @@ pub fn parse_vault_field(input: &str) -> Result<Field, AppError> {
     match input.to_lowercase().as_str() {
         "-username" | "-name" | "-user" => Ok(Field::Username),
         "-password" | "-pass" | "-pwd" => Ok(Field::Password),
-        _ => Err(AppError::InvalidArgument(input.to_string()))
+        "-url" | "-website" => Ok(Field::Url),
+        "-notes" | "-note" => Ok(Field::Notes),
+        "-tags" | "-tag" => Ok(Field::Tags),
+        "-otp" | "-totp" => Ok(Field::Otp),
+        _ => {
+            let name = input.strip_prefix("-field:").ok_or(AppError::InvalidArgument(input.to_string()))?;
+            validate_arg(name, "field")?;
+            Ok(Field::Custom { name: name.to_string(), secret: false })
+        }
+    }
+}
+
+fn is_field_flag(opt: &str) -> bool {
+    ["-secret", "-generate", "-gen", "-copy", "-cp", "-c", "-avoid-ambiguous"].contains(&opt)
+        || ["-no-", "-min-", "-symbols:"].iter().any(|prefix| opt.starts_with(prefix))
+}
+
+// the field is whichever option is not -secret, a copy flag or a generator flag
+fn parse_field_opts(opts: &[&str]) -> Result<Option<Field>, AppError> {
+    let secret = opts.contains(&"-secret");
+    let field = opts.iter().find(|opt| !is_field_flag(opt)).map(|opt| parse_vault_field(opt)).transpose()?;
+    Ok(field.map(|field| match field {
+        Field::Custom { name, .. } => Field::Custom { name, secret },
+        field => field,
+    }))
+}
+
+// free text starts at the first word after the entry and the field that is not a flag
+fn free_text_start(tokens: &[&str]) -> usize {
+    let (mut entry, mut field) = (false, false);
+    for (i, token) in tokens.iter().enumerate() {
+        match token.starts_with('-') {
+            false if !entry => entry = true,
+            true if is_field_flag(token) => {}
+            true if !field => field = true,
+            _ => return i,
+        }
+    }
+    tokens.len()
+}
+
+// the input after its first words, as typed
+fn rest_of_input(input: &str, words: usize) -> &str {
+    let mut rest = input.trim();
+    for _ in 0..words {
+        rest = rest.trim_start_matches(|c: char| !c.is_whitespace()).trim_start();
     }
-}
\ No newline at end of file
+    rest
+}
+
+// optional length followed by the same policy flags as generate
+fn parse_generate_params(length: Option<&&str>, opts: &[&str]) -> Result<PasswordParams, AppError> {
+    let length = length
+        .map(|length| length.parse::<usize>().map_err(|_| AppError::InvalidArgument("length".to_string())))
+        .transpose()?;
+    parse_configured_params(length, opts)
+}
+
+// the configured generator policy goes first so flags given on the command line win
+fn parse_configured_params(length: Option<usize>, opts: &[&str]) -> Result<PasswordParams, AppError> {
+    let config = ConfigService::current();
+    let mut flags: Vec<&str> = config.generator_policy.split_whitespace().collect();
+    flags.extend_from_slice(opts);
+    parse_password_params(length.unwrap_or(config.generator_length), &flags)
+}
+
+// character class flags: -no-<class>, -min-<class>:<n> and -symbols:<chars>
+pub fn parse_password_params(length: usize, opts: &[&str]) -> Result<PasswordParams, AppError> {
+    let mut params = PasswordParams {
+        length,
+        avoid_ambiguous: opts.contains(&"-avoid-ambiguous"),
+        ..PasswordParams::default()
+    };
+    for opt in opts {
+        let (flag, value) = opt.split_once(':').unwrap_or((opt, ""));
+        if flag == "-symbols" {
+            if value.is_empty() || !value.chars().all(|c| c.is_ascii_punctuation()) {
+                return Err(AppError::InvalidArgument("symbols".to_string()));
+            }
+            params.symbol_set = Some(value.to_string());
+            continue;
+        }
+        let (exclude, class) = match (flag.strip_prefix("-no-"), flag.strip_prefix("-min-")) {
+            (Some(class), _) => (true, class),
+            (_, Some(class)) => (false, class),
+            _ => continue,
+        };
+        let policy = match class {
+            "lower" => &mut params.lowercase,
+            "upper" => &mut params.uppercase,
+            "digits" => &mut params.digits,
+            "symbols" => &mut params.symbols,
+            _ => return Err(AppError::InvalidArgument(flag[1..].to_string())),
+        };
+        if exclude {
+            policy.enabled = false;
+        } else {
+            policy.min = value.parse::<usize>().map_err(|_| AppError::InvalidArgument(flag[1..].to_string()))?;
+        }
+    }
+    Ok(params)
+}
+
+// a vault name, a path to a .vault file whose stem becomes the name,
+// or a store locator like sqlite:/path/vaults.db#work or https://host/dav/work.vault
+pub fn parse_vault_location(location: &str) -> Result<(String, Option<String>), AppError> {
+    if VaultLocator::is_remote(location) {
+        validate_path(location)?;
+        let name = VaultLocator::parse(location, "")?
+            .name()
+            .ok_or(AppError::InvalidArgument("path".to_string()))?;
+        validate_arg(&name, "name")?;
+        return Ok((name, Some(location.to_string())));
+    }
+    let is_path = location.contains(['/', '\\']) || location.ends_with(VAULT_EXTENSION);
+    if !is_path {
+        validate_arg(location, "name")?;
+        return Ok((location.to_string(), None));
+    }
+    validate_path(location)?;
+    let name = Path::new(location)
+        .file_name()
+        .and_then(|file| file.to_str())
+        .and_then(|file| file.strip_suffix(VAULT_EXTENSION))
+        .ok_or(AppError::InvalidArgument("path".to_string()))?;
+    validate_arg(name, "name")?;
+    Ok((name.to_string(), Some(location.to_string())))
+}
+
+pub fn parse_oneshot_args(argv: &[String]) -> Result<OneShotArgs, AppError> {
+    let mut words: Vec<&str> = Vec::new();
+    let mut vault = None;
+    let mut vault_path = None;
+    let mut password = PasswordSource::Prompt;
+    let mut iter = argv.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--vault" => {
+                let location = iter.next().ok_or(AppError::MissingArgument("vault".to_string()))?;
+                let (name, path) = parse_vault_location(location)
+                    .map_err(|_| AppError::InvalidArgument("vault".to_string()))?;
+                vault = Some(name);
+                vault_path = path;
+            }
+            "--password-stdin" => password = PasswordSource::Stdin,
+            "--password-fd" => {
+                let fd = iter.next()
+                    .and_then(|s| s.parse::<u32>().ok())
+                    .ok_or(AppError::InvalidArgument("password-fd".to_string()))?;
+                password = PasswordSource::Fd(fd);
+            }
+            flag if flag.starts_with("--") => return Err(AppError::InvalidArgument(flag.to_string())),
+            word => words.push(word),
+        }
+    }
+    if words.is_empty() {
+        return Err(AppError::MissingArgument("command".to_string()));
+    }
+    Ok(OneShotArgs { command: words.join(" "), vault, vault_path, password })
+}
@@ -0,0 +1,29 @@
+use secrecy::SecretBox;
+use serde::{Deserialize, Serialize};
+use crate::domain::cli::field::Field;
+use crate::domain::vault::password_entry::secret_serde;
+
+// one json object per line in each direction
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "request", rename_all = "snake_case")]
+pub enum AgentRequest {
+    Unlock {
+        vault: String,
+        #[serde(with = "secret_serde")]
+        password: SecretBox<String>,
+    },
+    Get { vault: String, entry: String, field: Field },
+    Copy { vault: String, entry: String, field: Field },
+    List,
+    Lock { vault: Option<String> },
+    Stop,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", content = "value", rename_all = "snake_case")]
+pub enum AgentResponse {
+    Ok(Option<String>),
+    // the vault is not unlocked in the agent, so the caller should unlock it itself
+    Locked,
+    Error(String),
+}
@@ -0,0 +1,222 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+use chrono::{Duration, TimeDelta};
+use colored::Color;
+use serde::{Deserialize, Serialize};
+use crate::domain::app::error::AppError;
+use crate::domain::vault::vault_locator::VaultLocator;
+use crate::utils::constants::{CLIPBOARD_TTL, CLIPBOARD_TTL_MAX_SECONDS, PASSWORD_MAX_LEN, PASSWORD_MIN_LEN, SESSION_LIFETIME, SESSION_LIFETIME_MAX_MINUTES, SESSION_TTL, SESSION_TTL_MAX_MINUTES, VAULT_BACKUP_COUNT, VAULT_BACKUP_COUNT_MAX, VAULT_LOCATOR_SQLITE};
+
+pub const CONFIG_KEYS: [&str; 12] = [
+    "session_ttl",
+    "session_lifetime",
+    "clipboard_ttl",
+    "vault_dir",
+    "vault_backend",
+    "store_user",
+    "store_password",
+    "backup_count",
+    "generator_length",
+    "generator_policy",
+    "prompt_color",
+    "vault_color",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    // minutes
+    pub session_ttl: i64,
+    // minutes, activity does not extend it
+    pub session_lifetime: i64,
+    // seconds
+    pub clipboard_ttl: i64,
+    pub vault_dir: Option<String>,
+    // sqlite:<path> or an http(s) collection url new vaults are kept in, files when empty
+    pub vault_backend: String,
+    // basic auth for http stores, none when the user is empty
+    pub store_user: String,
+    pub store_password: String,
+    // previous versions kept of every vault, none when 0
+    pub backup_count: usize,
+    pub generator_length: usize,
+    // generate flags applied before the ones given on the command line
+    pub generator_policy: String,
+    pub prompt_color: String,
+    pub vault_color: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            session_ttl: SESSION_TTL.num_minutes(),
+            session_lifetime: SESSION_LIFETIME.num_minutes(),
+            clipboard_ttl: CLIPBOARD_TTL.num_seconds(),
+            vault_dir: None,
+            vault_backend: String::new(),
+            store_user: String::new(),
+            store_password: String::new(),
+            backup_count: VAULT_BACKUP_COUNT,
+            generator_length: PASSWORD_MIN_LEN,
+            generator_policy: String::new(),
+            prompt_color: "bright cyan".to_string(),
+            vault_color: "bright cyan".to_string(),
+        }
+    }
+}
+
+impl Config {
+    pub fn session_ttl(&self) -> TimeDelta {
+        Duration::minutes(self.session_ttl)
+    }
+
+    pub fn session_lifetime(&self) -> TimeDelta {
+        Duration::minutes(self.session_lifetime)
+    }
+
+    pub fn clipboard_ttl(&self) -> TimeDelta {
+        Duration::seconds(self.clipboard_ttl)
+    }
+
+    pub fn vault_dir(&self) -> Option<PathBuf> {
+        self.vault_dir.as_ref().map(PathBuf::from)
+    }
+
+    pub fn prompt_color(&self) -> Color {
+        self.prompt_color.parse().unwrap_or(Color::BrightCyan)
+    }
+
+    pub fn vault_color(&self) -> Color {
+        self.vault_color.parse().unwrap_or(Color::BrightCyan)
+    }
+
+    pub fn get(&self, key: &str) -> Result<String, AppError> {
+        let value = match key {
+            "session_ttl" => self.session_ttl.to_string(),
+            "session_lifetime" => self.session_lifetime.to_string(),
+            "clipboard_ttl" => self.clipboard_ttl.to_string(),
+            "vault_dir" => self.vault_dir.clone().unwrap_or_default(),
+            "vault_backend" => self.vault_backend.clone(),
+            "store_user" => self.store_user.clone(),
+            // masked, so the store password is never printed
+            "store_password" if !self.store_password.is_empty() => "********".to_string(),
+            "store_password" => String::new(),
+            "backup_count" => self.backup_count.to_string(),
+            "generator_length" => self.generator_length.to_string(),
+            "generator_policy" => self.generator_policy.clone(),
+            "prompt_color" => self.prompt_color.clone(),
+            "vault_color" => self.vault_color.clone(),
+            _ => return Err(AppError::InvalidArgument(key.to_string())),
+        };
+        Ok(value)
+    }
+
+    // an empty value resets the key to its default
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), AppError> {
+        let value = value.trim();
+        let invalid = || AppError::InvalidArgument(key.to_string());
+        let defaults = Config::default();
+        match key {
+            "session_ttl" => {
+                self.session_ttl = match value {
+                    "" => defaults.session_ttl,
+                    _ => value.parse::<i64>().ok()
+                        .filter(|n| (1..=SESSION_TTL_MAX_MINUTES).contains(n))
+                        .ok_or_else(invalid)?,
+                };
+            }
+            "session_lifetime" => {
+                self.session_lifetime = match value {
+                    "" => defaults.session_lifetime,
+                    _ => value.parse::<i64>().ok()
+                        .filter(|n| (1..=SESSION_LIFETIME_MAX_MINUTES).contains(n))
+                        .ok_or_else(invalid)?,
+                };
+            }
+            "clipboard_ttl" => {
+                self.clipboard_ttl = match value {
+                    "" => defaults.clipboard_ttl,
+                    _ => value.parse::<i64>().ok()
+                        .filter(|n| (1..=CLIPBOARD_TTL_MAX_SECONDS).contains(n))
+                        .ok_or_else(invalid)?,
+                };
+            }
+            "vault_dir" => {
+                self.vault_dir = match value {
+                    "" => None,
+                    _ if PathBuf::from(value).is_absolute() => Some(value.to_string()),
+                    _ => return Err(invalid()),
+                };
+            }
+            "vault_backend" => {
+                let relative = value.strip_prefix(VAULT_LOCATOR_SQLITE).is_some_and(|path| !Path::new(path).is_absolute());
+                let store = VaultLocator::parse(value, "").is_ok_and(|locator| locator.is_store());
+                if !value.is_empty() && (relative || !store) {
+                    return Err(invalid());
+                }
+                self.vault_backend = value.to_string();
+            }
+            "store_user" => {
+                // basic auth cannot carry a colon in the user
+                if value.contains(':') {
+                    return Err(invalid());
+                }
+                self.store_user = value.to_string();
+            }
+            "store_password" => self.store_password = value.to_string(),
+            "backup_count" => {
+                self.backup_count = match value {
+                    "" => defaults.backup_count,
+                    _ => value.parse::<usize>().ok()
+                        .filter(|n| *n <= VAULT_BACKUP_COUNT_MAX)
+                        .ok_or_else(invalid)?,
+                };
+            }
+            "generator_length" => {
+                self.generator_length = match value {
+                    "" => defaults.generator_length,
+                    _ => value.parse::<usize>().ok()
+                        .filter(|n| (PASSWORD_MIN_LEN..=PASSWORD_MAX_LEN).contains(n))
+                        .ok_or_else(invalid)?,
+                };
+            }
+            "generator_policy" => {
+                if value.split_whitespace().any(|flag| !flag.starts_with('-')) {
+                    return Err(invalid());
+                }
+                self.generator_policy = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            }
+            "prompt_color" | "vault_color" => {
+                let color = match value {
+                    "" => defaults.get(key)?,
+                    _ => value.replace('_', " ").to_lowercase(),
+                };
+                color.parse::<Color>().map_err(|_| invalid())?;
+                match key {
+                    "prompt_color" => self.prompt_color = color,
+                    _ => self.vault_color = color,
+                }
+            }
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    // re-applies every value through set so hand-edited files get the same checks
+    pub fn validate(&self) -> Result<(), AppError> {
+        let mut copy = Config::default();
+        for key in CONFIG_KEYS {
+            copy.set(key, &self.get(key)?)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lines: Vec<String> = CONFIG_KEYS.iter()
+            .map(|key| format!("{} = {}", key, self.get(key).unwrap_or_default()))
+            .collect();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
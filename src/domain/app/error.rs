@@ -1,10 +1,12 @@
 use thiserror::Error;
+use crate::domain::vault::vault_error::VaultError;
 
 #[derive(Debug, Error)]
 pub enum AppError {
     InvalidCommand,
     InvalidArgument(String),
     MissingArgument(String),
+    Vault(#[from] VaultError),
     Other(String),
 }
 
@@ -14,6 +16,7 @@ impl std::fmt::Display for AppError {
             AppError::InvalidCommand => write!(f, "Invalid command"),
             AppError::InvalidArgument(arg) => write!(f, "Invalid argument: <{}>", arg),
             AppError::MissingArgument(arg) => write!(f, "Missing argument: <{}>", arg),
+            AppError::Vault(err) => write!(f, "Error: {}", err),
             AppError::Other(err) => write!(f, "Error: {}", err),
         }
     }
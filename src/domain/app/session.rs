@@ -7,4 +7,6 @@ pub struct AppSession {
     pub name: String,
     pub secret: SecretBox<String>,
     pub expires_at: DateTime<Utc>,
+    // activity extends expires_at up to this point but never past it
+    pub max_expires_at: DateTime<Utc>,
 }
\ No newline at end of file
@@ -0,0 +1,22 @@
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditCheck {
+    Weak,
+    Reused,
+    UsernameMatch,
+    Old,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AuditSort {
+    Severity,
+    Name,
+    Score,
+    Age,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditParams {
+    pub checks: Vec<AuditCheck>,
+    pub sort: AuditSort,
+    pub max_age_days: u32,
+}
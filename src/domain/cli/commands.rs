@@ -1,10 +1,16 @@
+use crate::domain::cli::audit_params::AuditParams;
 use crate::domain::cli::field::Field;
+use crate::domain::cli::export_params::ExportParams;
+use crate::domain::cli::import_params::ImportParams;
+use crate::domain::cli::passphrase_params::PassphraseParams;
 use crate::domain::cli::password_params::PasswordParams;
 
 type Name = String;
 type Password = String;
 type Service = String;
+type Path = String;
 type Copy = bool;
+type Key = String;
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
@@ -13,21 +19,60 @@ pub enum Command {
     Exit,
     Vault(VaultCommand),
     Panic,
-    Analyze(Password),
-    Generate(PasswordParams, Copy)
+    Analyze(Password, Option<Path>),
+    Generate(PasswordParams, Copy),
+    Passphrase(PassphraseParams, Copy),
+    Config(ConfigCommand),
+    Agent(AgentCommand),
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum VaultCommand {
-    New(Name),
-    Open(Name),
+    New(Name, Option<Path>),
+    Open(Name, Option<Path>),
     Close,
     List,
+    Move(Name, Path),
+    Rename(Name, Name),
+    Sync,
     Show(Option<Service>, bool),
-    Add(Service),
-    Update(Service, Field, String),
+    Add(Service, Option<PasswordParams>, Copy),
+    Update(Service, Field, Option<String>),
+    Regenerate(Service, PasswordParams, Copy),
     Delete(Service),
     Copy(Service, Field),
+    Get(Service, Field),
+    Otp(Service, Copy),
+    History(Service, bool),
+    Revert(Service, usize),
+    Stale(u32),
+    Search(String),
+    Import(ImportParams),
+    Export(ExportParams),
+    Audit(AuditParams),
+    BreachCheck(Path),
     Destroy,
+    ChangePassword,
+    Backups,
+    Restore(usize),
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigCommand {
+    Show,
+    Get(Key),
+    // an empty value resets the key to its default
+    Set(Key, String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AgentCommand {
+    Start,
+    Stop,
+    // serves the socket in the foreground, start spawns this in the background
+    Run,
+    List,
+    // without a name the open vault is handed over
+    Add(Option<Name>),
+    Lock(Option<Name>),
+}
@@ -0,0 +1,32 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportFormat {
+    Csv,
+    Json,
+    Bitwarden,
+    Encrypted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportParams {
+    pub format: ExportFormat,
+    pub path: String,
+}
+
+impl ExportFormat {
+    pub fn is_plaintext(&self) -> bool {
+        !matches!(self, ExportFormat::Encrypted)
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Csv => write!(f, "CSV"),
+            ExportFormat::Json => write!(f, "JSON"),
+            ExportFormat::Bitwarden => write!(f, "Bitwarden"),
+            ExportFormat::Encrypted => write!(f, "encrypted"),
+        }
+    }
+}
@@ -1,9 +1,15 @@
 use std::fmt;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Field {
     Username,
     Password,
+    Url,
+    Notes,
+    Tags,
+    Custom { name: String, secret: bool },
+    Otp,
 }
 
 impl fmt::Display for Field {
@@ -11,7 +17,11 @@ impl fmt::Display for Field {
         match self {
             Field::Username => write!(f, "username"),
             Field::Password => write!(f, "password"),
+            Field::Url => write!(f, "url"),
+            Field::Notes => write!(f, "notes"),
+            Field::Tags => write!(f, "tags"),
+            Field::Custom { name, .. } => write!(f, "{}", name),
+            Field::Otp => write!(f, "otp"),
         }
     }
 }
-
@@ -0,0 +1,36 @@
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImportFormat {
+    Bitwarden,
+    KeePass,
+    OnePassword,
+    Chrome,
+    Encrypted,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DuplicateStrategy {
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportParams {
+    pub format: ImportFormat,
+    pub path: String,
+    pub duplicates: DuplicateStrategy,
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFormat::Bitwarden => write!(f, "Bitwarden"),
+            ImportFormat::KeePass => write!(f, "KeePass"),
+            ImportFormat::OnePassword => write!(f, "1Password"),
+            ImportFormat::Chrome => write!(f, "Chrome"),
+            ImportFormat::Encrypted => write!(f, "Encrypted"),
+        }
+    }
+}
@@ -0,0 +1,15 @@
+#[derive(Debug, Clone, PartialEq)]
+pub enum PasswordSource {
+    Prompt,
+    Stdin,
+    Fd(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OneShotArgs {
+    pub command: String,
+    pub vault: Option<String>,
+    // set when the vault was given as a file path
+    pub vault_path: Option<String>,
+    pub password: PasswordSource,
+}
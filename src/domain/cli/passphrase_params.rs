@@ -0,0 +1,8 @@
+#[derive(Debug, Clone, PartialEq)]
+pub struct PassphraseParams {
+    pub words: usize,
+    pub separator: String,
+    pub capitalize: bool,
+    pub digit: bool,
+    pub symbol: bool,
+}
@@ -1,5 +1,38 @@
+use crate::utils::constants::PASSWORD_MIN_LEN;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassPolicy {
+    pub enabled: bool,
+    pub min: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PasswordParams {
     pub length: usize,
     pub avoid_ambiguous: bool,
-}
\ No newline at end of file
+    pub lowercase: ClassPolicy,
+    pub uppercase: ClassPolicy,
+    pub digits: ClassPolicy,
+    pub symbols: ClassPolicy,
+    pub symbol_set: Option<String>,
+}
+
+impl Default for ClassPolicy {
+    fn default() -> Self {
+        ClassPolicy { enabled: true, min: 0 }
+    }
+}
+
+impl Default for PasswordParams {
+    fn default() -> Self {
+        PasswordParams {
+            length: PASSWORD_MIN_LEN,
+            avoid_ambiguous: false,
+            lowercase: ClassPolicy::default(),
+            uppercase: ClassPolicy::default(),
+            digits: ClassPolicy::default(),
+            symbols: ClassPolicy::default(),
+            symbol_set: None,
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::fmt;
+use crate::domain::cli::audit_params::AuditCheck;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum AuditIssue {
+    Weak(String),
+    Reused(usize),
+    UsernameMatch,
+    Old(i64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditFinding {
+    pub name: String,
+    pub strength: u8,
+    pub guesses_log10: f64,
+    pub age_days: Option<i64>,
+    pub issues: Vec<AuditIssue>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct AuditReport {
+    pub findings: Vec<AuditFinding>,
+    pub audited: usize,
+    pub healthy: usize,
+}
+
+impl AuditIssue {
+    pub fn check(&self) -> AuditCheck {
+        match self {
+            AuditIssue::Weak(_) => AuditCheck::Weak,
+            AuditIssue::Reused(_) => AuditCheck::Reused,
+            AuditIssue::UsernameMatch => AuditCheck::UsernameMatch,
+            AuditIssue::Old(_) => AuditCheck::Old,
+        }
+    }
+}
+
+impl AuditReport {
+    // share of audited entries without any issue, as a percentage
+    pub fn health(&self) -> f64 {
+        if self.audited == 0 {
+            return 100.0;
+        }
+        self.healthy as f64 / self.audited as f64 * 100.0
+    }
+}
+
+impl fmt::Display for AuditIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuditIssue::Weak(classification) => write!(f, "weak ({})", classification.to_lowercase()),
+            AuditIssue::Reused(count) => write!(f, "reused in {} entries", count),
+            AuditIssue::UsernameMatch => write!(f, "matches username"),
+            AuditIssue::Old(days) => write!(f, "{} days old", days),
+        }
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.findings.iter().map(|finding| finding.name.len()).max().unwrap_or(0);
+        for finding in &self.findings {
+            let issues = finding.issues.iter().map(|issue| issue.to_string()).collect::<Vec<String>>();
+            writeln!(f, "{:width$}  {}/4  {}", finding.name, finding.strength, issues.join(", "), width = width)?;
+        }
+        write!(
+            f,
+            "Vault health: {:.0}% ({} of {} entries without issues)",
+            self.health(),
+            self.healthy,
+            self.audited,
+        )
+    }
+}
@@ -0,0 +1,25 @@
+use secrecy::SecretBox;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+use crate::domain::vault::password_entry::secret_serde;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomField {
+    pub name: String,
+    #[serde(with = "secret_serde")]
+    pub value: SecretBox<String>,
+    pub secret: bool,
+}
+
+impl Zeroize for CustomField {
+    fn zeroize(&mut self) {
+        self.name.zeroize();
+        self.value = SecretBox::new(Box::default());
+    }
+}
+
+impl Drop for CustomField {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
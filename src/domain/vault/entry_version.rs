@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use secrecy::SecretBox;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+use crate::domain::vault::password_entry::secret_serde;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntryVersion {
+    pub username: String,
+    #[serde(with = "secret_serde")]
+    pub password: SecretBox<String>,
+    pub replaced_at: DateTime<Utc>,
+}
+
+impl Zeroize for EntryVersion {
+    fn zeroize(&mut self) {
+        self.username.zeroize();
+        self.password = SecretBox::new(Box::default());
+    }
+}
+
+impl Drop for EntryVersion {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
@@ -0,0 +1,23 @@
+use std::fmt;
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub overwritten: usize,
+    pub renamed: usize,
+    pub skipped: usize,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Imported {} entries ({} added, {} overwritten, {} renamed), skipped {} duplicates",
+            self.added + self.overwritten + self.renamed,
+            self.added,
+            self.overwritten,
+            self.renamed,
+            self.skipped,
+        )
+    }
+}
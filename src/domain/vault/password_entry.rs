@@ -1,21 +1,149 @@
+use chrono::{DateTime, Utc};
+use data_encoding::HEXLOWER;
+use rand::Rng;
+use sha2::{Digest, Sha256};
 use secrecy::{ExposeSecret, SecretBox};
 use std::fmt;
 use colored::Colorize;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
+use crate::domain::cli::field::Field;
+use crate::domain::vault::custom_field::CustomField;
+use crate::domain::vault::entry_version::EntryVersion;
+use crate::domain::vault::vault_file::Sealed;
+use crate::utils::constants::ENTRY_HISTORY_LEN;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct PasswordEntry {
+    // stable across renames so synced copies of a vault can be merged entry by entry
+    #[serde(default)]
+    pub id: String,
     pub name: String,
     pub username: String,
     #[serde(with = "secret_serde")]
     pub password: SecretBox<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<CustomField>,
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "optional_secret_serde")]
+    pub otp: Option<SecretBox<String>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub history: Vec<EntryVersion>,
+    #[serde(default)]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub password_changed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_copied_at: Option<DateTime<Utc>>,
+    // the secrets of an entry loaded from a format 2 vault, until they are needed
+    #[serde(skip)]
+    pub sealed: Option<Sealed>,
+}
+
+impl PasswordEntry {
+    pub fn new(name: &str, username: &str, password: &str) -> Self {
+        Self {
+            id: Self::new_id(),
+            name: String::from(name),
+            username: String::from(username),
+            password: SecretBox::new(Box::from(String::from(password))),
+            url: None,
+            notes: None,
+            tags: Vec::new(),
+            fields: Vec::new(),
+            otp: None,
+            history: Vec::new(),
+            created_at: None,
+            updated_at: None,
+            password_changed_at: None,
+            last_copied_at: None,
+            sealed: None,
+        }
+    }
+
+    pub fn new_id() -> String {
+        HEXLOWER.encode(&rand::rng().random::<[u8; 16]>())
+    }
+
+    // entries saved before ids existed derive one from their name and creation date,
+    // so every copy of the same vault assigns the same id
+    pub fn legacy_id(&self) -> String {
+        let created_at = self.created_at.map(|date| date.to_rfc3339()).unwrap_or_default();
+        let digest = Sha256::digest(format!("{}|{}", self.name, created_at).as_bytes());
+        HEXLOWER.encode(&digest[..16])
+    }
+
+    pub fn derived_id(&self) -> String {
+        let digest = Sha256::digest(format!("{}|{}", self.id, self.name).as_bytes());
+        HEXLOWER.encode(&digest[..16])
+    }
+
+    pub fn field(&self, name: &str) -> Option<&CustomField> {
+        self.fields.iter().find(|f| f.name == name)
+    }
+
+    // keeps the current credentials as the newest version, older ones fall off the end
+    pub fn record_version(&mut self) {
+        let version = EntryVersion {
+            username: self.username.clone(),
+            password: SecretBox::new(Box::from(self.password.expose_secret().clone())),
+            replaced_at: Utc::now(),
+        };
+        self.history.insert(0, version);
+        self.history.truncate(ENTRY_HISTORY_LEN);
+    }
+
+    pub fn revert(&mut self, index: usize) -> Option<()> {
+        if index >= self.history.len() {
+            return None;
+        }
+        let version = self.history.remove(index);
+        self.record_version();
+        self.username = version.username.clone();
+        self.password = SecretBox::new(Box::from(version.password.expose_secret().clone()));
+        let now = Utc::now();
+        self.updated_at = Some(now);
+        self.password_changed_at = Some(now);
+        Some(())
+    }
+
+    // entries from vaults that predate timestamps fall back to their creation date, if any
+    pub fn password_age(&self) -> Option<chrono::TimeDelta> {
+        self.password_changed_at.or(self.created_at).map(|date| Utc::now() - date)
+    }
+
+    pub fn get(&self, field: &Field) -> Option<String> {
+        match field {
+            Field::Username => Some(self.username.clone()),
+            Field::Password => Some(self.password.expose_secret().clone()),
+            Field::Url => self.url.clone(),
+            Field::Notes => self.notes.clone(),
+            Field::Tags => Some(self.tags.join(",")).filter(|tags| !tags.is_empty()),
+            Field::Custom { name, .. } => self.field(name).map(|f| f.value.expose_secret().clone()),
+            Field::Otp => self.otp.as_ref().map(|otp| otp.expose_secret().clone()),
+        }
+    }
 }
 
 impl Zeroize for PasswordEntry {
     fn zeroize(&mut self) {
         self.name.zeroize();
         self.username.zeroize();
+        self.url.zeroize();
+        self.notes.zeroize();
+        self.tags.zeroize();
+        self.fields.iter_mut().for_each(|field| field.zeroize());
+        self.fields.clear();
+        self.otp = None;
+        self.history.iter_mut().for_each(|version| version.zeroize());
+        self.history.clear();
 
         let mut empty = String::new();
         empty.zeroize(); // prevent old password from being left in memory
@@ -35,18 +163,23 @@ pub struct PasswordEntryDebug<'a> {
 }
 
 impl PasswordEntry {
-    pub fn expose(&self) -> PasswordEntryDebug {
+    pub fn expose(&self) -> PasswordEntryDebug<'_> {
         PasswordEntryDebug { entry: self, expose: true }
     }
 }
 
-impl fmt::Display for PasswordEntryDebug<'_> {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let password_str = if self.expose {
-            self.entry.password.expose_secret().to_string()
+impl PasswordEntryDebug<'_> {
+    fn reveal(&self, secret: &SecretBox<String>) -> String {
+        if self.expose {
+            secret.expose_secret().to_string()
         } else {
             "<hidden>".to_string()
-        };
+        }
+    }
+}
+
+impl fmt::Display for PasswordEntryDebug<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
             "{}\n  {} {}\n  {} {}\n",
@@ -54,12 +187,48 @@ impl fmt::Display for PasswordEntryDebug<'_> {
             "Username:".italic(),
             self.entry.username.white(),
             "Password:".italic(),
-            password_str.white(),
-        )
+            self.reveal(&self.entry.password).white(),
+        )?;
+        if let Some(url) = &self.entry.url {
+            writeln!(f, "  {} {}", "URL:".italic(), url.white())?;
+        }
+        if !self.entry.tags.is_empty() {
+            writeln!(f, "  {} {}", "Tags:".italic(), self.entry.tags.join(", ").white())?;
+        }
+        for field in &self.entry.fields {
+            let value = if field.secret {
+                self.reveal(&field.value)
+            } else {
+                field.value.expose_secret().to_string()
+            };
+            writeln!(f, "  {} {}", format!("{}:", field.name).italic(), value.white())?;
+        }
+        if let Some(otp) = &self.entry.otp {
+            writeln!(f, "  {} {}", "OTP:".italic(), self.reveal(otp).white())?;
+        }
+        let format_date = |date: &Option<DateTime<Utc>>| match date {
+            Some(date) => date.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+            None => "unknown".to_string(),
+        };
+        writeln!(f, "  {} {}", "Created:".italic(), format_date(&self.entry.created_at).white())?;
+        writeln!(f, "  {} {}", "Updated:".italic(), format_date(&self.entry.updated_at).white())?;
+        writeln!(f, "  {} {}", "Password changed:".italic(), format_date(&self.entry.password_changed_at).white())?;
+        let last_copied = match self.entry.last_copied_at {
+            Some(_) => format_date(&self.entry.last_copied_at),
+            None => "never".to_string(),
+        };
+        writeln!(f, "  {} {}", "Last copied:".italic(), last_copied.white())?;
+        if let Some(notes) = &self.entry.notes {
+            writeln!(f, "  {}", "Notes:".italic())?;
+            for line in notes.lines() {
+                writeln!(f, "    {}", line.white())?;
+            }
+        }
+        Ok(())
     }
 }
 
-mod secret_serde {
+pub(crate) mod secret_serde {
     use secrecy::{ExposeSecret, SecretBox};
     use serde::{Serializer, Deserializer, Deserialize};
 
@@ -77,3 +246,25 @@ mod secret_serde {
         String::deserialize(deserializer).map(|s| SecretBox::new(Box::from(s)))
     }
 }
+
+pub(crate) mod optional_secret_serde {
+    use secrecy::{ExposeSecret, SecretBox};
+    use serde::{Serializer, Deserializer, Deserialize};
+
+    pub fn serialize<S>(secret: &Option<SecretBox<String>>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match secret {
+            Some(secret) => serializer.serialize_some(secret.expose_secret()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<SecretBox<String>>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Option::<String>::deserialize(deserializer).map(|s| s.map(|s| SecretBox::new(Box::from(s))))
+    }
+}
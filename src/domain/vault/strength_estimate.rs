@@ -0,0 +1,97 @@
+use std::fmt;
+use crate::utils::constants::ATTACK_MODELS;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Dictionary {
+    Passwords,
+    English,
+    Names,
+    UserInputs,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Pattern {
+    Dictionary { dictionary: Dictionary, word: String, rank: usize, reversed: bool, l33t: bool },
+    Spatial { turns: usize, shifted: usize },
+    Repeat { base: String, count: usize },
+    Sequence { ascending: bool },
+    Date { year: i32, separator: bool },
+    Year,
+    Bruteforce,
+}
+
+// a part of the password, covering the characters i..=j
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatternMatch {
+    pub i: usize,
+    pub j: usize,
+    pub token: String,
+    pub pattern: Pattern,
+    pub guesses_log10: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrengthEstimate {
+    pub guesses_log10: f64,
+    pub score: u8,
+    pub sequence: Vec<PatternMatch>,
+    pub warning: Option<String>,
+    pub suggestions: Vec<String>,
+}
+
+impl StrengthEstimate {
+    pub fn classification(&self) -> &'static str {
+        match self.score {
+            0 => "Very weak",
+            1 => "Weak",
+            2 => "Fair",
+            3 => "Strong",
+            _ => "Very strong",
+        }
+    }
+
+    // seconds needed to find the password under each attack model
+    pub fn crack_times(&self) -> Vec<(&'static str, f64)> {
+        ATTACK_MODELS.iter()
+            .map(|(name, rate)| (*name, 10f64.powf(self.guesses_log10) / rate))
+            .collect()
+    }
+}
+
+fn display_time(seconds: f64) -> String {
+    let units = [
+        ("minute", 60.0),
+        ("hour", 3600.0),
+        ("day", 86400.0),
+        ("month", 86400.0 * 31.0),
+        ("year", 86400.0 * 365.0),
+    ];
+    if seconds < 1.0 {
+        return "less than a second".to_string();
+    }
+    if seconds >= 86400.0 * 365.0 * 100.0 {
+        return "centuries".to_string();
+    }
+    let (unit, size) = units.iter().rev()
+        .find(|(_, size)| seconds >= *size)
+        .copied()
+        .unwrap_or(("second", 1.0));
+    let count = (seconds / size).round() as u64;
+    format!("{} {}{}", count, unit, if count == 1 { "" } else { "s" })
+}
+
+impl fmt::Display for StrengthEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Strength: {}/4 ({}), about 10^{:.1} guesses", self.score, self.classification(), self.guesses_log10)?;
+        for (model, seconds) in self.crack_times() {
+            write!(f, "\n  {:<20} {}", model, display_time(seconds))?;
+        }
+        if let Some(warning) = &self.warning {
+            write!(f, "\nWarning: {}", warning)?;
+        }
+        for suggestion in &self.suggestions {
+            write!(f, "\n  - {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
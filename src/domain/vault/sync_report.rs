@@ -0,0 +1,86 @@
+use std::fmt;
+use chrono::{DateTime, Utc};
+use crate::domain::vault::password_entry::PasswordEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    Local,
+    Remote,
+    Both,
+}
+
+// an entry changed differently on both sides, a missing side means it was deleted there
+pub struct MergeConflict<'a> {
+    pub local: Option<&'a PasswordEntry>,
+    pub remote: Option<&'a PasswordEntry>,
+}
+
+impl MergeConflict<'_> {
+    pub fn name(&self) -> &str {
+        self.local.or(self.remote).map(|entry| entry.name.as_str()).unwrap_or_default()
+    }
+
+    // the side modified last, a deletion has no modification time and loses
+    pub fn newer(&self) -> Resolution {
+        let modified = |entry: Option<&PasswordEntry>| entry.map(|e| e.updated_at.or(e.created_at).unwrap_or(DateTime::<Utc>::MIN_UTC));
+        match (modified(self.local), modified(self.remote)) {
+            (Some(local), Some(remote)) if remote > local => Resolution::Remote,
+            (None, Some(_)) => Resolution::Remote,
+            _ => Resolution::Local,
+        }
+    }
+}
+
+// changes taken from the remote vault
+#[derive(Debug, Default, PartialEq)]
+pub struct MergeReport {
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub conflicts: usize,
+    pub renamed: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct SyncReport {
+    pub committed: bool,
+    pub fast_forwarded: bool,
+    pub merge: Option<MergeReport>,
+    pub pushed: bool,
+}
+
+impl fmt::Display for MergeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Merged remote changes: {} added, {} updated, {} deleted, {} conflicts resolved",
+            self.added, self.updated, self.deleted, self.conflicts
+        )?;
+        for name in &self.renamed {
+            write!(f, "\nRenamed duplicate entry to {}", name)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for SyncReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut lines = Vec::new();
+        if self.committed {
+            lines.push("Committed local changes".to_string());
+        }
+        if self.fast_forwarded {
+            lines.push("Pulled remote changes".to_string());
+        }
+        if let Some(merge) = &self.merge {
+            lines.push(merge.to_string());
+        }
+        if self.pushed {
+            lines.push("Pushed to remote".to_string());
+        }
+        if lines.is_empty() {
+            lines.push("Already up to date".to_string());
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
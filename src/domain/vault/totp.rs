@@ -0,0 +1,22 @@
+use zeroize::Zeroize;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TotpAlgorithm {
+    Sha1,
+    Sha256,
+    Sha512,
+}
+
+#[derive(Debug)]
+pub struct Totp {
+    pub secret: Vec<u8>,
+    pub algorithm: TotpAlgorithm,
+    pub digits: u32,
+    pub period: u64,
+}
+
+impl Drop for Totp {
+    fn drop(&mut self) {
+        self.secret.zeroize();
+    }
+}
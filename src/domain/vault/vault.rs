@@ -1,10 +1,40 @@
+use std::collections::HashSet;
+use secrecy::SecretBox;
 use serde::{Deserialize, Serialize};
 use zeroize::Zeroize;
 use crate::domain::vault::password_entry::PasswordEntry;
+use crate::utils::constants::VAULT_KEY_LENGTH;
 
 #[derive(Serialize, Deserialize)]
 pub struct Vault {
     pub entries: Vec<PasswordEntry>,
+    // the data key entries were sealed under, kept so unchanged entries are not sealed again
+    #[serde(skip)]
+    pub key: Option<SecretBox<[u8; VAULT_KEY_LENGTH]>>,
+}
+
+impl Vault {
+    pub fn new(entries: Vec<PasswordEntry>) -> Self {
+        Self { entries, key: None }
+    }
+
+    // a taken id, as from importing a vault's own export, is replaced by one derived from it,
+    // so every copy of the same vault settles on the same ids
+    pub fn assign_ids(&mut self) {
+        let mut seen: HashSet<String> = HashSet::new();
+        for entry in self.entries.iter_mut() {
+            if entry.id.is_empty() {
+                entry.id = entry.legacy_id();
+            }
+            if seen.contains(&entry.id) {
+                entry.id = entry.derived_id();
+            }
+            while seen.contains(&entry.id) {
+                entry.id = PasswordEntry::new_id();
+            }
+            seen.insert(entry.id.clone());
+        }
+    }
 }
 
 impl Zeroize for Vault {
@@ -14,6 +44,7 @@ impl Zeroize for Vault {
         });
         self.entries.clear();
         self.entries.shrink_to_fit();
+        self.key = None;
     }
 }
 
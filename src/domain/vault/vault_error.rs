@@ -0,0 +1,40 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum VaultError {
+    // what was missing, e.g. "Vault" or "Backup"
+    #[error("{0} not found")]
+    NotFound(String),
+    #[error("{0} already exists")]
+    AlreadyExists(String),
+    #[error("Wrong vault password")]
+    WrongPassword,
+    #[error("Vault file is corrupt: {0}")]
+    Corrupt(String),
+    #[error("Unsupported vault version: {0}")]
+    UnsupportedVersion(u32),
+    // a cipher or key derivation function this build does not know
+    #[error("Unsupported {0}")]
+    Unsupported(String),
+    #[error("Invalid vault registry: {0}")]
+    Registry(String),
+    #[error("Invalid vault path: {0}")]
+    InvalidPath(String),
+    #[error("{0}: {1}")]
+    Io(String, #[source] io::Error),
+    #[error("{0}")]
+    Crypto(String),
+    #[error("{0}")]
+    Git(String),
+    // an SQLite or HTTP vault store failed
+    #[error("{0}")]
+    Store(String),
+}
+
+impl VaultError {
+    // for map_err, keeps the io error and says what was being done
+    pub fn io(context: &str) -> impl FnOnce(io::Error) -> VaultError + '_ {
+        move |err| VaultError::Io(context.to_string(), err)
+    }
+}
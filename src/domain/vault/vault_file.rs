@@ -1,8 +1,67 @@
 use serde::{Deserialize, Serialize};
+use crate::utils::constants::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultHeader {
+    pub version: u32,
+    pub cipher: String,
+    pub kdf: String,
+    pub m_cost: u32,
+    pub t_cost: u32,
+    pub p_cost: u32,
+}
+
+impl VaultHeader {
+    pub fn current() -> Self {
+        Self {
+            version: VAULT_FORMAT_VERSION,
+            cipher: CIPHER_AES_256_GCM_SIV.to_string(),
+            kdf: KDF_ARGON2ID.to_string(),
+            m_cost: ARGON2_M_COST,
+            t_cost: ARGON2_T_COST,
+            p_cost: ARGON2_P_COST,
+        }
+    }
+
+    // bundles stay a single blob, they are read once and never need one entry on its own
+    pub fn blob() -> Self {
+        Self { version: BLOB_VAULT_FORMAT_VERSION, ..Self::current() }
+    }
+
+    // headerless files were always written with these parameters
+    pub fn legacy() -> Self {
+        Self {
+            version: LEGACY_VAULT_FORMAT_VERSION,
+            cipher: CIPHER_AES_256_GCM_SIV.to_string(),
+            kdf: KDF_ARGON2ID.to_string(),
+            m_cost: 32,
+            t_cost: 3,
+            p_cost: 1,
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct VaultFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub header: Option<VaultHeader>,
     pub salt: String,
     pub nonce: String,
+    // in format 2 the ciphertext is the vault data key, wrapped by the password-derived key
     pub ciphertext: String,
-}
\ No newline at end of file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<SealedEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Sealed {
+    pub nonce: String,
+    pub ciphertext: String,
+}
+
+// metadata is enough to list and search, the secrets are only opened when needed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SealedEntry {
+    pub metadata: Sealed,
+    pub secrets: Sealed,
+}
@@ -0,0 +1,109 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::path::{self, PathBuf};
+use crate::domain::vault::vault_error::VaultError;
+use crate::utils::constants::{VAULT_EXTENSION, VAULT_LOCATOR_SQLITE};
+
+// where a vault is kept: a file, a row in an SQLite database or an object under an HTTP collection
+#[derive(Debug, Clone, PartialEq)]
+pub enum VaultLocator {
+    File(PathBuf),
+    Sqlite { database: PathBuf, name: String },
+    // base ends with a slash, the vault lives at <base><name>.vault
+    Http { base: String, name: String },
+}
+
+impl VaultLocator {
+    pub fn is_remote(location: &str) -> bool {
+        location.starts_with(VAULT_LOCATOR_SQLITE)
+            || location.starts_with("http://")
+            || location.starts_with("https://")
+    }
+
+    // a store like sqlite:/path/vaults.db or https://host/dav/ holds the vault under
+    // the given name, a full locator like sqlite:/path/vaults.db#work or
+    // https://host/dav/work.vault names it explicitly
+    pub fn parse(location: &str, name: &str) -> Result<Self, VaultError> {
+        let invalid = || VaultError::InvalidPath(location.to_string());
+        if let Some(rest) = location.strip_prefix(VAULT_LOCATOR_SQLITE) {
+            let (database, key) = rest.split_once('#').unwrap_or((rest, name));
+            if database.is_empty() {
+                return Err(invalid());
+            }
+            let database = path::absolute(database).map_err(|_| invalid())?;
+            return Ok(VaultLocator::Sqlite { database, name: key.to_string() });
+        }
+        if Self::is_remote(location) {
+            let (base, file) = location.rsplit_once('/').ok_or_else(invalid)?;
+            let key = match file {
+                "" => name,
+                file => file.strip_suffix(VAULT_EXTENSION).ok_or_else(invalid)?,
+            };
+            let host = match base.split_once("://") {
+                Some((_, rest)) => Self::host(rest),
+                None => "",
+            };
+            if host.is_empty() {
+                return Err(invalid());
+            }
+            // vaults are encrypted, but credentials are not, so plain http stays on this machine
+            if location.starts_with("http://") && !Self::is_loopback(host) {
+                return Err(VaultError::Unsupported(format!("plain http to {}, use https", host)));
+            }
+            return Ok(VaultLocator::Http { base: format!("{}/", base), name: key.to_string() });
+        }
+        Ok(VaultLocator::File(path::absolute(location).map_err(|_| invalid())?))
+    }
+
+    // the host of an url without its scheme, dropping any user and port
+    fn host(rest: &str) -> &str {
+        let authority = rest.split('/').next().unwrap_or_default();
+        let authority = authority.rsplit_once('@').map(|(_, host)| host).unwrap_or(authority);
+        match authority.strip_prefix('[') {
+            Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+            None => authority.split(':').next().unwrap_or_default(),
+        }
+    }
+
+    fn is_loopback(host: &str) -> bool {
+        host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+    }
+
+    // a store on its own, as vault_backend names it
+    pub fn is_store(&self) -> bool {
+        match self {
+            VaultLocator::File(_) => false,
+            VaultLocator::Sqlite { name, .. } | VaultLocator::Http { name, .. } => name.is_empty(),
+        }
+    }
+
+    // the name the vault is stored under
+    pub fn name(&self) -> Option<String> {
+        match self {
+            VaultLocator::File(path) => path.file_name()
+                .and_then(|file| file.to_str())
+                .and_then(|file| file.strip_suffix(VAULT_EXTENSION))
+                .map(|name| name.to_string()),
+            VaultLocator::Sqlite { name, .. } | VaultLocator::Http { name, .. } => Some(name.clone()),
+        }
+    }
+
+    // the same store, holding the vault under another name
+    pub fn renamed(&self, new_name: &str) -> Self {
+        match self {
+            VaultLocator::File(path) => VaultLocator::File(path.with_file_name(format!("{}{}", new_name, VAULT_EXTENSION))),
+            VaultLocator::Sqlite { database, .. } => VaultLocator::Sqlite { database: database.clone(), name: new_name.to_string() },
+            VaultLocator::Http { base, .. } => VaultLocator::Http { base: base.clone(), name: new_name.to_string() },
+        }
+    }
+}
+
+impl fmt::Display for VaultLocator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VaultLocator::File(path) => write!(f, "{}", path.display()),
+            VaultLocator::Sqlite { database, name } => write!(f, "{}{}#{}", VAULT_LOCATOR_SQLITE, database.display(), name),
+            VaultLocator::Http { base, name } => write!(f, "{}{}{}", base, name, VAULT_EXTENSION),
+        }
+    }
+}
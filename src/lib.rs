@@ -2,6 +2,9 @@ pub mod cli {
     pub mod commands;
     pub mod parser;
     pub mod io;
+    pub mod oneshot;
+    #[cfg(unix)]
+    pub mod agent;
 }
 
 pub mod domain {
@@ -9,22 +12,49 @@ pub mod domain {
         pub mod commands;
         pub mod field;
         pub mod password_params;
+        pub mod passphrase_params;
+        pub mod oneshot_args;
+        pub mod import_params;
+        pub mod export_params;
+        pub mod audit_params;
     }
     pub mod vault {
+        #[allow(clippy::module_inception)]
         pub mod vault;
         pub mod vault_file;
         pub mod password_entry;
+        pub mod custom_field;
+        pub mod totp;
+        pub mod entry_version;
+        pub mod import_report;
+        pub mod audit_report;
+        pub mod strength_estimate;
+        pub mod sync_report;
+        pub mod vault_error;
+        pub mod vault_locator;
     }
     pub mod app {
         pub mod state;
         pub mod session;
         pub mod error;
+        pub mod config;
+        pub mod agent_protocol;
     }
 }
 
 pub mod services {
     pub mod vault_service;
     pub mod password_service;
+    pub mod otp_service;
+    pub mod search_service;
+    pub mod import_service;
+    pub mod export_service;
+    pub mod audit_service;
+    pub mod breach_service;
+    pub mod strength_service;
+    pub mod config_service;
+    pub mod sync_service;
+    pub mod agent_service;
 }
 
 pub mod repository {
@@ -32,6 +62,12 @@ pub mod repository {
         pub mod vault_crypto;
         pub mod vault_manager;
         pub mod vault_manager_trait;
+        pub mod vault_storage;
+        pub mod vault_registry;
+        pub mod vault_git;
+        pub mod vault_sqlite;
+        pub mod vault_http;
+        pub mod vault_router;
     }
 }
 
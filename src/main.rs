@@ -1,14 +1,25 @@
 use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
 use std::thread;
 use ctrlc::set_handler;
 use passman::cli::commands::execute_cmd;
-use passman::cli::io::{read_line_with_prefix, clear_console};
+use passman::cli::io::{read_line_with_prefix, clear_console, print_prefix};
+use passman::cli::oneshot::run_oneshot;
 use passman::cli::parser::parse_cmd;
 use passman::domain::app::state::AppState;
-use passman::repository::vault::vault_manager::VaultManager;
+use passman::repository::vault::vault_router::VaultRouter;
 use passman::services::vault_service::VaultService;
+use passman::utils::constants::SESSION_CHECK_INTERVAL;
 
 fn main() {
+    let argv: Vec<String> = std::env::args().skip(1).collect();
+    if !argv.is_empty() {
+        std::process::exit(run_oneshot(&argv));
+    }
+    repl();
+}
+
+fn repl() {
     clear_console();
     println!("Welcome to Passman!");
     println!("Type 'help' to see the list of commands.");
@@ -36,29 +47,37 @@ fn main() {
 
     // main loop
     let mut state = AppState { session: None };
-    let vault_service = VaultService::new(VaultManager);
+    let vault_service = VaultService::new(VaultRouter);
     loop {
-        match in_rx.recv() {
-            Ok(line) => {
-                match parse_cmd(&line) {
-                    Ok(cmd) => {
-                        match execute_cmd(cmd, &vault_service, &mut state) {
-                            Ok(msg) => {
-                                if let Some(m) = msg {
-                                    if !m.is_empty() {
-                                        println!("{}", m);
-                                    }
-                                }
+        let line = match in_rx.recv_timeout(SESSION_CHECK_INTERVAL.to_std().unwrap()) {
+            Ok(line) => line,
+            // lock an idle vault while the prompt is still waiting for input
+            Err(RecvTimeoutError::Timeout) => {
+                if let Some(notice) = vault_service.expire(&mut state) {
+                    clear_console();
+                    println!("{}", notice);
+                    print_prefix(None);
+                }
+                continue;
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        };
+        match parse_cmd(&line) {
+            Ok(cmd) => {
+                match execute_cmd(cmd, &vault_service, &mut state) {
+                    Ok(msg) => {
+                        if let Some(m) = msg {
+                            if !m.is_empty() {
+                                println!("{}", m);
                             }
-                            Err(err) => println!("{}", err)
                         }
-                    },
+                    }
                     Err(err) => println!("{}", err)
                 }
-                let vault = state.session.as_ref().map(|s| s.name.clone());
-                let _ = out_tx.send(vault);
-            }
-            Err(_) => break
+            },
+            Err(err) => println!("{}", err)
         }
+        let vault = state.session.as_ref().map(|s| s.name.clone());
+        let _ = out_tx.send(vault);
     }
 }
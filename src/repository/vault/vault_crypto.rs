@@ -1,7 +1,12 @@
-use crate::utils::constants::{NONCE_LENGTH, SALT_LENGTH};
+use crate::utils::constants::{ARGON2_M_COST_MAX, ARGON2_P_COST_MAX, ARGON2_T_COST_MAX, CIPHER_AES_256_GCM_SIV, KDF_ARGON2ID, LEGACY_VAULT_FORMAT_VERSION, NONCE_LENGTH, SALT_LENGTH, VAULT_KEY_LENGTH};
+use crate::domain::vault::custom_field::CustomField;
+use crate::domain::vault::entry_version::EntryVersion;
+use crate::domain::vault::password_entry::{optional_secret_serde, secret_serde, PasswordEntry};
 use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
+use crate::domain::vault::vault_file::{Sealed, SealedEntry, VaultFile, VaultHeader};
 use aes_gcm_siv::{
-    aead::{generic_array::GenericArray, Aead, KeyInit},
+    aead::{generic_array::GenericArray, Aead, KeyInit, Payload},
     Aes256GcmSiv
 };
 use argon2::{self, password_hash::{PasswordHasher, SaltString}, Argon2, Params};
@@ -9,21 +14,59 @@ use base64::engine::general_purpose::STANDARD;
 use base64::Engine as _;
 use rand::Rng;
 use secrecy::{ExposeSecret, SecretBox};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+type VaultKey = SecretBox<[u8; VAULT_KEY_LENGTH]>;
+
+const METADATA_AAD: &[u8] = b"metadata";
+
+// the secrets blob of a format 2 entry, borrowed from the entry while it is sealed
+#[derive(Serialize)]
+struct EntrySecrets<'a> {
+    password: &'a str,
+    otp: Option<&'a str>,
+    history: &'a [EntryVersion],
+    fields: Vec<SecretValue<'a>>,
+}
+
+// secret field values go by their index, names need not be unique
+#[derive(Serialize)]
+struct SecretValue<'a> {
+    index: usize,
+    value: &'a str,
+}
+
+#[derive(Deserialize)]
+struct OpenedSecrets {
+    #[serde(with = "secret_serde")]
+    password: SecretBox<String>,
+    #[serde(default, with = "optional_secret_serde")]
+    otp: Option<SecretBox<String>>,
+    #[serde(default)]
+    history: Vec<EntryVersion>,
+    #[serde(default)]
+    fields: Vec<OpenedValue>,
+}
+
+#[derive(Deserialize)]
+struct OpenedValue {
+    index: usize,
+    #[serde(with = "secret_serde")]
+    value: SecretBox<String>,
+}
 
 pub struct VaultCrypto;
 
 impl VaultCrypto {
 
-    pub fn encrypt(vault: &Vault, password: &SecretBox<String>) -> (String, String, String) {
-        let data = serde_json::to_vec(vault).expect("Serialization failed");
-        let mut rng = rand::rng();
-        let salt: [u8; SALT_LENGTH] = rng.random();
-        let nonce: [u8; NONCE_LENGTH] = rng.random();
-
-        let key = Self::derive_key(password, &salt);
-        let cipher = Aes256GcmSiv::new(GenericArray::from_slice(key.expose_secret()));
-        let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), data.as_ref()).expect("Encryption failed");
-        (STANDARD.encode(salt), STANDARD.encode(nonce), STANDARD.encode(ciphertext))
+    pub fn encrypt(vault: &Vault, password: &SecretBox<String>, header: &VaultHeader) -> Result<(String, String, String), VaultError> {
+        let data = Self::to_bytes(vault)?;
+        let salt: [u8; SALT_LENGTH] = rand::rng().random();
+        let key = Self::derive_key(password, &salt, header)?;
+        let sealed = Self::seal_bytes(&key, &data, &Self::associated_data(header)?)?;
+        Ok((STANDARD.encode(salt), sealed.nonce, sealed.ciphertext))
     }
 
     pub fn decrypt(
@@ -31,36 +74,279 @@ impl VaultCrypto {
         ciphertext: &str,
         salt: &str,
         nonce: &str,
-    ) -> Result<Vault, String> {
-        let ciphertext_bytes = STANDARD.decode(ciphertext)
-            .map_err(|e| format!("Ciphertext decoding failed: {}", e))?;
+        header: &VaultHeader,
+    ) -> Result<Vault, VaultError> {
+        if header.cipher != CIPHER_AES_256_GCM_SIV {
+            return Err(VaultError::Unsupported(format!("cipher: {}", header.cipher)));
+        }
         let salt_bytes = STANDARD.decode(salt)
-            .map_err(|e| format!("Salt decoding failed: {}", e))?;
-        let nonce_bytes = STANDARD.decode(nonce)
-            .map_err(|e| format!("Nonce decoding failed: {}", e))?;
+            .map_err(|e| VaultError::Corrupt(format!("Salt decoding failed: {}", e)))?;
+        let key = Self::derive_key(password, &salt_bytes, header)?;
+        let sealed = Sealed { nonce: nonce.to_string(), ciphertext: ciphertext.to_string() };
+        // authentication covers the header too, so a tampered file is indistinguishable from a wrong password
+        let decrypted_data = Self::open_bytes(&key, &sealed, &Self::associated_data(header)?, VaultError::WrongPassword)?;
+
+        serde_json::from_slice(&decrypted_data)
+            .map_err(|e| VaultError::Corrupt(format!("Deserialization failed: {}", e)))
+    }
+
+    // format 2 seals every entry under a random data key and only wraps that key with the password,
+    // so a vault can be listed without its secrets and unchanged entries keep their ciphertext
+    pub fn seal(vault: &Vault, password: &SecretBox<String>, header: &VaultHeader) -> Result<VaultFile, VaultError> {
+        let generated;
+        let key = match &vault.key {
+            Some(key) => key,
+            None => {
+                generated = SecretBox::new(Box::new(rand::rng().random::<[u8; VAULT_KEY_LENGTH]>()));
+                &generated
+            }
+        };
+        let entries = vault.entries.iter()
+            .map(|entry| Self::seal_entry(key, entry, vault.key.is_some()))
+            .collect::<Result<Vec<SealedEntry>, VaultError>>()?;
+        let salt: [u8; SALT_LENGTH] = rand::rng().random();
+        let password_key = Self::derive_key(password, &salt, header)?;
+        let wrapped = Self::seal_bytes(&password_key, key.expose_secret(), &Self::key_associated_data(header, &entries)?)?;
+        Ok(VaultFile {
+            header: Some(header.clone()),
+            salt: STANDARD.encode(salt),
+            nonce: wrapped.nonce,
+            ciphertext: wrapped.ciphertext,
+            entries,
+        })
+    }
+
+    // decrypts the metadata of every entry, their secrets stay sealed until unsealed
+    pub fn open(vault_file: &VaultFile, password: &SecretBox<String>, header: &VaultHeader) -> Result<Vault, VaultError> {
+        if header.cipher != CIPHER_AES_256_GCM_SIV {
+            return Err(VaultError::Unsupported(format!("cipher: {}", header.cipher)));
+        }
+        let salt = STANDARD.decode(&vault_file.salt)
+            .map_err(|e| VaultError::Corrupt(format!("Salt decoding failed: {}", e)))?;
+        let password_key = Self::derive_key(password, &salt, header)?;
+        let wrapped = Sealed { nonce: vault_file.nonce.clone(), ciphertext: vault_file.ciphertext.clone() };
+        let aad = Self::key_associated_data(header, &vault_file.entries)?;
+        let key = Self::open_bytes(&password_key, &wrapped, &aad, VaultError::WrongPassword)?;
+        let key: [u8; VAULT_KEY_LENGTH] = key.as_slice().try_into()
+            .map_err(|_| VaultError::Corrupt("Invalid vault key length".to_string()))?;
+        let mut vault = Vault::new(Vec::with_capacity(vault_file.entries.len()));
+        let key = vault.key.insert(SecretBox::new(Box::new(key)));
+        for sealed in &vault_file.entries {
+            let failed = VaultError::Corrupt("An entry failed authentication".to_string());
+            let metadata = Self::open_bytes(key, &sealed.metadata, METADATA_AAD, failed)?;
+            let mut entry: PasswordEntry = serde_json::from_slice(&metadata)
+                .map_err(|e| VaultError::Corrupt(format!("Deserialization failed: {}", e)))?;
+            entry.sealed = Some(sealed.secrets.clone());
+            vault.entries.push(entry);
+        }
+        Ok(vault)
+    }
+
+    // decrypts the secrets of one entry in place
+    pub fn unseal(vault: &mut Vault, index: usize) -> Result<(), VaultError> {
+        match vault.entries.get_mut(index) {
+            Some(entry) => Self::open_secrets(vault.key.as_ref(), entry),
+            None => Ok(()),
+        }
+    }
+
+    pub fn unseal_all(vault: &mut Vault) -> Result<(), VaultError> {
+        (0..vault.entries.len()).try_for_each(|index| Self::unseal(vault, index))
+    }
 
-        let key = Self::derive_key(password, &salt_bytes);
+    // a decrypted copy of one entry for reading, the vault keeps it sealed
+    pub fn unsealed(vault: &Vault, index: usize) -> Result<PasswordEntry, VaultError> {
+        let entry = vault.entries.get(index).ok_or(VaultError::NotFound("Entry".to_string()))?;
+        let mut copy = Self::copy_entry(entry, true);
+        Self::open_secrets(vault.key.as_ref(), &mut copy)?;
+        Ok(copy)
+    }
+
+    // an exact copy of an entry, sealed secrets stay sealed
+    pub fn copy(entry: &PasswordEntry) -> PasswordEntry {
+        Self::copy_entry(entry, true)
+    }
+
+    // every entry decrypted into a separate vault, for reports and exports
+    pub fn unsealed_all(vault: &Vault) -> Result<Vault, VaultError> {
+        let entries = (0..vault.entries.len())
+            .map(|index| Self::unsealed(vault, index))
+            .collect::<Result<Vec<PasswordEntry>, VaultError>>()?;
+        Ok(Vault::new(entries))
+    }
+
+    fn open_secrets(key: Option<&VaultKey>, entry: &mut PasswordEntry) -> Result<(), VaultError> {
+        let Some(sealed) = &entry.sealed else {
+            return Ok(());
+        };
+        let key = key.ok_or(VaultError::Crypto("The vault key is missing".to_string()))?;
+        let failed = VaultError::Corrupt(format!("The secrets of {} failed authentication", entry.name));
+        let secrets = Self::open_bytes(key, sealed, &Self::secrets_aad(&entry.id), failed)?;
+        let secrets: OpenedSecrets = serde_json::from_slice(&secrets)
+            .map_err(|e| VaultError::Corrupt(format!("Deserialization failed: {}", e)))?;
+        if secrets.fields.iter().any(|value| entry.fields.get(value.index).is_none_or(|field| !field.secret)) {
+            return Err(VaultError::Corrupt(format!("The secrets of {} do not match its fields", entry.name)));
+        }
+        entry.password = secrets.password;
+        entry.otp = secrets.otp;
+        entry.history = secrets.history;
+        for value in secrets.fields {
+            entry.fields[value.index].value = value.value;
+        }
+        entry.sealed = None;
+        Ok(())
+    }
+
+    // the password, otp, secret field values and history go into their own blob, the metadata
+    // keeps empty placeholders so a sealed entry still shows which secrets it has
+    fn seal_entry(key: &VaultKey, entry: &PasswordEntry, reuse: bool) -> Result<SealedEntry, VaultError> {
+        let mut metadata = Self::copy_entry(entry, false);
+        // ids bind the secrets to their entry, so one is fixed before sealing
+        if metadata.id.is_empty() {
+            metadata.id = entry.legacy_id();
+        }
+        let secrets = match (&entry.sealed, reuse) {
+            (Some(sealed), true) => sealed.clone(),
+            (Some(_), false) => return Err(VaultError::Crypto("A sealed entry has no vault key".to_string())),
+            (None, _) => {
+                let secrets = EntrySecrets {
+                    password: entry.password.expose_secret(),
+                    otp: entry.otp.as_ref().map(|otp| otp.expose_secret().as_str()),
+                    history: &entry.history,
+                    fields: entry.fields.iter()
+                        .enumerate()
+                        .filter(|(_, field)| field.secret)
+                        .map(|(index, field)| SecretValue { index, value: field.value.expose_secret() })
+                        .collect(),
+                };
+                Self::seal_bytes(key, &Self::to_bytes(&secrets)?, &Self::secrets_aad(&metadata.id))?
+            }
+        };
+        Ok(SealedEntry { metadata: Self::seal_bytes(key, &Self::to_bytes(&metadata)?, METADATA_AAD)?, secrets })
+    }
+
+    // entries cannot be cloned, their secrets are SecretBoxes. without secrets the copy keeps
+    // empty placeholders and no history, which is what the metadata of a sealed entry holds
+    fn copy_entry(entry: &PasswordEntry, secrets: bool) -> PasswordEntry {
+        let copy = |value: &SecretBox<String>| SecretBox::new(Box::new(value.expose_secret().clone()));
+        let secret = |value: &SecretBox<String>| match secrets {
+            true => copy(value),
+            false => SecretBox::new(Box::default()),
+        };
+        PasswordEntry {
+            id: entry.id.clone(),
+            name: entry.name.clone(),
+            username: entry.username.clone(),
+            password: secret(&entry.password),
+            url: entry.url.clone(),
+            notes: entry.notes.clone(),
+            tags: entry.tags.clone(),
+            fields: entry.fields.iter()
+                .map(|field| CustomField {
+                    name: field.name.clone(),
+                    value: if field.secret { secret(&field.value) } else { copy(&field.value) },
+                    secret: field.secret,
+                })
+                .collect(),
+            otp: entry.otp.as_ref().map(secret),
+            history: match secrets {
+                true => entry.history.iter()
+                    .map(|version| EntryVersion {
+                        username: version.username.clone(),
+                        password: copy(&version.password),
+                        replaced_at: version.replaced_at,
+                    })
+                    .collect(),
+                false => Vec::new(),
+            },
+            created_at: entry.created_at,
+            updated_at: entry.updated_at,
+            password_changed_at: entry.password_changed_at,
+            last_copied_at: entry.last_copied_at,
+            sealed: entry.sealed.clone(),
+        }
+    }
+
+    fn secrets_aad(id: &str) -> Vec<u8> {
+        format!("secrets:{}", id).into_bytes()
+    }
+
+    fn seal_bytes(key: &VaultKey, data: &[u8], aad: &[u8]) -> Result<Sealed, VaultError> {
+        let nonce: [u8; NONCE_LENGTH] = rand::rng().random();
         let cipher = Aes256GcmSiv::new(GenericArray::from_slice(key.expose_secret()));
-        let decrypted_data = cipher.decrypt(
-            GenericArray::from_slice(&nonce_bytes),
-            ciphertext_bytes.as_ref()
-        ).map_err(|_| "Wrong vault password")?;
+        let ciphertext = cipher.encrypt(GenericArray::from_slice(&nonce), Payload { msg: data, aad })
+            .map_err(|_| VaultError::Crypto("Encryption failed".to_string()))?;
+        Ok(Sealed { nonce: STANDARD.encode(nonce), ciphertext: STANDARD.encode(ciphertext) })
+    }
 
-        serde_json::from_slice(&decrypted_data)
-            .map_err(|e| format!("Deserialization failed: {}", e))
+    fn open_bytes(key: &VaultKey, sealed: &Sealed, aad: &[u8], failed: VaultError) -> Result<Zeroizing<Vec<u8>>, VaultError> {
+        let ciphertext = STANDARD.decode(&sealed.ciphertext)
+            .map_err(|e| VaultError::Corrupt(format!("Ciphertext decoding failed: {}", e)))?;
+        let nonce = STANDARD.decode(&sealed.nonce)
+            .map_err(|e| VaultError::Corrupt(format!("Nonce decoding failed: {}", e)))?;
+        if nonce.len() != NONCE_LENGTH {
+            return Err(VaultError::Corrupt("Invalid nonce length".to_string()));
+        }
+        let cipher = Aes256GcmSiv::new(GenericArray::from_slice(key.expose_secret()));
+        cipher.decrypt(GenericArray::from_slice(&nonce), Payload { msg: &ciphertext, aad })
+            .map(Zeroizing::new)
+            .map_err(|_| failed)
+    }
+
+    fn to_bytes(value: &impl Serialize) -> Result<Zeroizing<Vec<u8>>, VaultError> {
+        serde_json::to_vec(value)
+            .map(Zeroizing::new)
+            .map_err(|e| VaultError::Crypto(format!("Serialization failed: {}", e)))
+    }
+
+    // the header is bound to the ciphertext so its parameters cannot be tampered with
+    fn associated_data(header: &VaultHeader) -> Result<Vec<u8>, VaultError> {
+        if header.version == LEGACY_VAULT_FORMAT_VERSION {
+            Ok(Vec::new())
+        } else {
+            serde_json::to_vec(header).map_err(|e| VaultError::Crypto(format!("Serialization failed: {}", e)))
+        }
+    }
+
+    // the wrapped key also covers every sealed entry in order, so dropping, duplicating or
+    // reordering entries, or putting one back from an older save, fails like a tampered header
+    fn key_associated_data(header: &VaultHeader, entries: &[SealedEntry]) -> Result<Vec<u8>, VaultError> {
+        let mut digest = Sha256::new();
+        for entry in entries {
+            for sealed in [&entry.metadata, &entry.secrets] {
+                digest.update(sealed.nonce.as_bytes());
+                digest.update(b".");
+                digest.update(sealed.ciphertext.as_bytes());
+                digest.update(b"\n");
+            }
+        }
+        let mut aad = Self::associated_data(header)?;
+        aad.extend_from_slice(&digest.finalize());
+        Ok(aad)
     }
 
-    fn derive_key(password: &SecretBox<String>, salt: &[u8]) -> SecretBox<[u8; 32]> {
-        let params = Params::new(32, 3, 1, None).expect("Invalid Argon2 parameters");
+    fn derive_key(password: &SecretBox<String>, salt: &[u8], header: &VaultHeader) -> Result<SecretBox<[u8; 32]>, VaultError> {
+        if header.kdf != KDF_ARGON2ID {
+            return Err(VaultError::Unsupported(format!("key derivation function: {}", header.kdf)));
+        }
+        if header.m_cost > ARGON2_M_COST_MAX || header.t_cost > ARGON2_T_COST_MAX || header.p_cost > ARGON2_P_COST_MAX {
+            return Err(VaultError::Unsupported(format!(
+                "Argon2 parameters: m_cost {}, t_cost {}, p_cost {}", header.m_cost, header.t_cost, header.p_cost
+            )));
+        }
+        let params = Params::new(header.m_cost, header.t_cost, header.p_cost, None)
+            .map_err(|e| VaultError::Corrupt(format!("Invalid Argon2 parameters: {}", e)))?;
         let argon2 = Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
-        let salt_str = SaltString::encode_b64(salt).expect("Failed to convert salt");
+        let salt_str = SaltString::encode_b64(salt)
+            .map_err(|e| VaultError::Corrupt(format!("Failed to convert salt: {}", e)))?;
         let password_hash = argon2
             .hash_password(password.expose_secret().as_bytes(), &salt_str)
-            .expect("Password hashing failed");
+            .map_err(|e| VaultError::Crypto(format!("Password hashing failed: {}", e)))?;
 
-        let hash_bytes = password_hash.hash.expect("Failed to extract hash bytes");
+        let hash_bytes = password_hash.hash
+            .ok_or(VaultError::Crypto("Failed to extract hash bytes".to_string()))?;
         let mut key = [0u8; 32];
         key.copy_from_slice(hash_bytes.as_bytes());
-        SecretBox::new(Box::from(key))
+        Ok(SecretBox::new(Box::from(key)))
     }
 }
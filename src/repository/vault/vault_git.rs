@@ -0,0 +1,52 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use crate::domain::vault::vault_error::VaultError;
+
+// thin wrapper over the git executable, run inside the repository holding a vault
+pub struct VaultGit;
+
+impl VaultGit {
+    pub fn run(root: &Path, args: &[&str]) -> Result<String, VaultError> {
+        let output = Self::output(root, args)?;
+        Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+    }
+
+    pub fn succeeds(root: &Path, args: &[&str]) -> bool {
+        Self::output(root, args).is_ok()
+    }
+
+    pub fn root(path: &Path) -> Result<PathBuf, VaultError> {
+        let dir = path.parent().ok_or_else(|| VaultError::InvalidPath(path.display().to_string()))?;
+        Self::run(dir, &["rev-parse", "--show-toplevel"])
+            .map(PathBuf::from)
+            .map_err(|_| VaultError::Git("The vault is not inside a git repository".to_string()))
+    }
+
+    // the file as of a revision, none if it did not exist there
+    pub fn show(root: &Path, rev: &str, file: &str) -> Result<Option<Vec<u8>>, VaultError> {
+        let object = format!("{}:{}", rev, file);
+        if !Self::succeeds(root, &["cat-file", "-e", &object]) {
+            return Ok(None);
+        }
+        Self::output(root, &["show", &object]).map(|output| Some(output.stdout))
+    }
+
+    fn output(root: &Path, args: &[&str]) -> Result<Output, VaultError> {
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(root)
+            .args(args)
+            // a credential prompt would fight the repl for stdin
+            .env("GIT_TERMINAL_PROMPT", "0")
+            .output()
+            .map_err(VaultError::io("Failed to run git"))?;
+        if output.status.success() {
+            Ok(output)
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let message = if stderr.trim().is_empty() { stdout } else { stderr };
+            Err(VaultError::Git(format!("git {} failed: {}", args.first().unwrap_or(&""), message.trim())))
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use std::io::Read;
+use std::path::PathBuf;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use chrono::{DateTime, Utc};
+use secrecy::SecretBox;
+use ureq::{Agent, AgentBuilder};
+use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
+use crate::repository::vault::vault_manager::VaultManager;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::services::config_service::ConfigService;
+use crate::utils::constants::{DAV_HREF_REGEX, VAULT_EXTENSION, VAULT_STORE_TIMEOUT};
+
+// encrypted vault files kept under a WebDAV collection, read and written with GET, PUT and DELETE
+// and listed with PROPFIND, behind Basic auth; S3 is not supported as it needs signed requests
+pub struct VaultHttp {
+    agent: Agent,
+    base: String,
+    // the Authorization header from store_user and store_password
+    auth: Option<String>,
+}
+
+impl VaultHttp {
+    pub fn new(base: &str) -> Self {
+        let agent = AgentBuilder::new().timeout(VAULT_STORE_TIMEOUT.to_std().unwrap()).build();
+        let base = match base.ends_with('/') {
+            true => base.to_string(),
+            false => format!("{}/", base),
+        };
+        let config = ConfigService::current();
+        let auth = match config.store_user.is_empty() {
+            true => None,
+            false => Some(format!("Basic {}", STANDARD.encode(format!("{}:{}", config.store_user, config.store_password)))),
+        };
+        Self { agent, base, auth }
+    }
+
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, VaultError> {
+        self.get(&Self::file(name))?.ok_or(VaultError::NotFound("Vault".to_string()))
+    }
+
+    // the previous object is copied to a backup object beside it first. backups are found
+    // again by listing the collection, so servers that cannot list get none at all
+    pub fn write(&self, name: &str, data: &[u8], backups: usize) -> Result<(), VaultError> {
+        let file = Self::file(name);
+        if let Some(files) = self.files()?.filter(|_| backups > 0) {
+            if let Some(previous) = self.get(&file)? {
+                self.put(&VaultStorage::backup_name(name, &Utc::now()), &previous)?;
+                for (_, old) in Self::backups_among(name, files).iter().skip(backups - 1) {
+                    self.delete_file(old)?;
+                }
+            }
+        }
+        self.put(&file, data)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), VaultError> {
+        if !self.exists(name)? {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        for (_, backup) in self.backup_files(name)? {
+            self.delete_file(&backup)?;
+        }
+        self.delete_file(&Self::file(name))
+    }
+
+    fn file(name: &str) -> String {
+        format!("{}{}", name, VAULT_EXTENSION)
+    }
+
+    fn url(&self, file: &str) -> String {
+        format!("{}{}", self.base, file)
+    }
+
+    fn request(&self, method: &str, url: &str) -> ureq::Request {
+        let request = self.agent.request(method, url);
+        match &self.auth {
+            Some(auth) => request.set("Authorization", auth),
+            None => request,
+        }
+    }
+
+    fn get(&self, file: &str) -> Result<Option<Vec<u8>>, VaultError> {
+        match self.request("GET", &self.url(file)).call() {
+            Ok(response) => {
+                let mut data = Vec::new();
+                response.into_reader().read_to_end(&mut data)
+                    .map_err(VaultError::io("Failed to read from vault store"))?;
+                Ok(Some(data))
+            }
+            Err(ureq::Error::Status(404, _)) => Ok(None),
+            Err(e) => Err(Self::error(e)),
+        }
+    }
+
+    fn put(&self, file: &str, data: &[u8]) -> Result<(), VaultError> {
+        self.request("PUT", &self.url(file))
+            .set("Content-Type", "application/octet-stream")
+            .send_bytes(data)
+            .map_err(Self::error)?;
+        Ok(())
+    }
+
+    fn delete_file(&self, file: &str) -> Result<(), VaultError> {
+        match self.request("DELETE", &self.url(file)).call() {
+            Ok(_) | Err(ureq::Error::Status(404, _)) => Ok(()),
+            Err(e) => Err(Self::error(e)),
+        }
+    }
+
+    // file names in the collection, none when the server has no WebDAV to list it with
+    fn files(&self) -> Result<Option<Vec<String>>, VaultError> {
+        let response = match self.request("PROPFIND", &self.base).set("Depth", "1").call() {
+            Ok(response) => response,
+            Err(ureq::Error::Status(405 | 501, _)) => return Ok(None),
+            Err(e) => return Err(Self::error(e)),
+        };
+        let body = response.into_string().map_err(VaultError::io("Failed to read from vault store"))?;
+        Ok(Some(DAV_HREF_REGEX.captures_iter(&body)
+            .filter_map(|href| href[1].rsplit('/').next().map(Self::decode))
+            .filter(|file| !file.is_empty())
+            .collect()))
+    }
+
+    fn backup_files(&self, name: &str) -> Result<Vec<(DateTime<Utc>, String)>, VaultError> {
+        Ok(Self::backups_among(name, self.files()?.unwrap_or_default()))
+    }
+
+    // newest first
+    fn backups_among(name: &str, files: Vec<String>) -> Vec<(DateTime<Utc>, String)> {
+        let mut backups: Vec<(DateTime<Utc>, String)> = files
+            .into_iter()
+            .filter_map(|file| Some((VaultStorage::backup_date(name, &file)?, file)))
+            .collect();
+        backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+        backups
+    }
+
+    // undoes percent-encoding in an href path segment
+    fn decode(segment: &str) -> String {
+        let bytes = segment.as_bytes();
+        let mut decoded = Vec::with_capacity(bytes.len());
+        let mut i = 0;
+        while i < bytes.len() {
+            let hex = bytes.get(i + 1..i + 3)
+                .and_then(|hex| std::str::from_utf8(hex).ok())
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+            match (bytes[i], hex) {
+                (b'%', Some(byte)) => {
+                    decoded.push(byte);
+                    i += 3;
+                }
+                (byte, _) => {
+                    decoded.push(byte);
+                    i += 1;
+                }
+            }
+        }
+        String::from_utf8_lossy(&decoded).to_string()
+    }
+
+    fn error(err: ureq::Error) -> VaultError {
+        VaultError::Store(format!("HTTP store failed: {}", err))
+    }
+}
+
+impl VaultManagerTrait for VaultHttp {
+
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError> {
+        let vault = Vault::new(Vec::new());
+        self.save(name, password, &vault)
+    }
+
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let data = VaultManager::encode(vault, password)?;
+        self.write(name, &data, ConfigService::current().backup_count)
+    }
+
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        self.write(name, &VaultManager::encode(vault, password)?, 0)
+    }
+
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        VaultManager::decode(&self.read(name)?, password)
+    }
+
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError> {
+        let mut names: Vec<String> = self.files()?
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|file| file.strip_suffix(VAULT_EXTENSION).map(|name| name.to_string()))
+            .collect();
+        names.sort();
+        Ok(names.into_iter().map(|name| {
+            let location = self.url(&Self::file(&name));
+            (name, location)
+        }).collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
+        self.remove(name)
+    }
+
+    fn file_path(&self, name: &str) -> Result<PathBuf, VaultError> {
+        Err(VaultError::InvalidPath(format!("{} is not a file", self.url(&Self::file(name)))))
+    }
+
+    // locations are kept by the router, a store only knows its own objects
+    fn register(&self, _name: &str, location: &str) -> Result<(), VaultError> {
+        Err(VaultError::Unsupported(format!("location {}", location)))
+    }
+
+    fn unregister(&self, _name: &str) -> Result<(), VaultError> {
+        Ok(())
+    }
+
+    fn relocate(&self, _name: &str, location: &str) -> Result<String, VaultError> {
+        Err(VaultError::Unsupported(format!("location {}", location)))
+    }
+
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError> {
+        if self.exists(new_name)? {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}'", new_name)));
+        }
+        let data = self.read(name)?;
+        self.put(&Self::file(new_name), &data)?;
+        for (date, backup) in self.backup_files(name)? {
+            if let Some(data) = self.get(&backup)? {
+                self.put(&VaultStorage::backup_name(new_name, &date), &data)?;
+            }
+        }
+        self.remove(name)
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, VaultError> {
+        match self.request("HEAD", &self.url(&Self::file(name))).call() {
+            Ok(_) => Ok(true),
+            Err(ureq::Error::Status(404, _)) => Ok(false),
+            Err(e) => Err(Self::error(e)),
+        }
+    }
+
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError> {
+        Ok(self.backup_files(name)?.into_iter().map(|(date, _)| date).collect())
+    }
+
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError> {
+        let backups = self.backup_files(name)?;
+        let (_, backup) = backups.get(index).ok_or(VaultError::NotFound("Backup".to_string()))?;
+        let data = self.get(backup)?.ok_or(VaultError::NotFound("Backup".to_string()))?;
+        // the current version is backed up too, so a restore can be undone
+        self.write(name, &data, ConfigService::current().backup_count)
+    }
+}
@@ -1,73 +1,215 @@
+use std::collections::BTreeMap;
 use std::fs;
+use std::io::ErrorKind;
 use std::path::PathBuf;
+use chrono::{DateTime, Utc};
 use directories::ProjectDirs;
 use secrecy::SecretBox;
 use crate::domain::vault::vault::Vault;
-use crate::domain::vault::vault_file::VaultFile;
+use crate::domain::vault::vault_error::VaultError;
+use crate::domain::vault::vault_file::{VaultFile, VaultHeader};
 use crate::repository::vault::vault_crypto::VaultCrypto;
 use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::repository::vault::vault_registry::VaultRegistry;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::services::config_service::ConfigService;
+use crate::utils::constants::{BLOB_VAULT_FORMAT_VERSION, LEGACY_VAULT_FORMAT_VERSION, VAULT_EXTENSION, VAULT_FORMAT_VERSION};
 
 pub struct VaultManager;
 
 impl VaultManager {
-    fn get_path(name: Option<&str>) -> PathBuf {
-        let dirs = ProjectDirs::from("com", "passman", "Passman")
-            .expect("Failed to find project directory");
-        let mut path = dirs.data_dir().to_path_buf();
-        if let Some(name) = name {
-            path.push(format!("{}.vault", name));
+    fn default_dir() -> Result<PathBuf, VaultError> {
+        match ConfigService::current().vault_dir() {
+            Some(dir) => Ok(dir),
+            None => ProjectDirs::from("com", "passman", "Passman")
+                .map(|dirs| dirs.data_dir().to_path_buf())
+                .ok_or(VaultError::InvalidPath("no home directory to store vaults in".to_string())),
+        }
+    }
+
+    pub(crate) fn default_path(name: &str) -> Result<PathBuf, VaultError> {
+        Ok(Self::default_dir()?.join(format!("{}{}", name, VAULT_EXTENSION)))
+    }
+
+    fn registry() -> Result<VaultRegistry, VaultError> {
+        VaultRegistry::load(&ConfigService::registry_path())
+    }
+
+    // registered locations take precedence over the default directory
+    fn get_path(name: &str) -> Result<PathBuf, VaultError> {
+        let registry = Self::registry()?;
+        match registry.get(name) {
+            Some(location) => Ok(PathBuf::from(location)),
+            None => Self::default_path(name),
+        }
+    }
+
+    // keeps the registry limited to vaults outside the default directory
+    fn set_location(name: &str, path: Option<PathBuf>) -> Result<(), VaultError> {
+        let mut registry = Self::registry()?;
+        match path {
+            Some(path) if path != Self::default_path(name)? => registry.insert(name, &path.to_string_lossy()),
+            _ => { registry.remove(name); }
+        }
+        registry.save(&ConfigService::registry_path())
+    }
+
+    fn absolute(location: &str) -> Result<PathBuf, VaultError> {
+        std::path::absolute(location).map_err(|e| VaultError::InvalidPath(format!("{}: {}", location, e)))
+    }
+
+    // a directory, or a path not ending in .vault, gets <name>.vault appended
+    pub(crate) fn destination(name: &str, location: &str) -> Result<PathBuf, VaultError> {
+        let mut path = Self::absolute(location)?;
+        if path.is_dir() || !location.ends_with(VAULT_EXTENSION) {
+            path.push(format!("{}{}", name, VAULT_EXTENSION));
+        }
+        Ok(path)
+    }
+
+    pub fn encode(vault: &Vault, password: &SecretBox<String>) -> Result<Vec<u8>, VaultError> {
+        let header = VaultHeader::current();
+        let vault_file = VaultCrypto::seal(vault, password, &header)?;
+        serde_json::to_vec(&vault_file).map_err(|e| VaultError::Crypto(format!("Serialization failed: {}", e)))
+    }
+
+    pub fn decode(data: &[u8], password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        let vault_file: VaultFile = serde_json::from_slice(data)
+            .map_err(|e| VaultError::Corrupt(format!("Deserialization failed: {}", e)))?;
+        // headerless files predate versioning, they and single blobs are upgraded on the next save
+        let header = vault_file.header.clone().unwrap_or_else(VaultHeader::legacy);
+        match header.version {
+            LEGACY_VAULT_FORMAT_VERSION | BLOB_VAULT_FORMAT_VERSION => {
+                VaultCrypto::decrypt(password, &vault_file.ciphertext, &vault_file.salt, &vault_file.nonce, &header)
+            }
+            VAULT_FORMAT_VERSION => VaultCrypto::open(&vault_file, password, &header),
+            version => Err(VaultError::UnsupportedVersion(version)),
         }
-        path
     }
 }
 
 impl VaultManagerTrait for VaultManager {
 
-    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), String> {
-        let vault = Vault { entries: Vec::new() };
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError> {
+        let vault = Vault::new(Vec::new());
         self.save(name, password, &vault)
     }
 
-    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), String> {
-        let (salt, nonce, ciphertext) = VaultCrypto::encrypt(vault, password);
-        let vault_file = VaultFile { salt, nonce, ciphertext };
-        let data = serde_json::to_vec(&vault_file).map_err(|e| format!("Serialization failed: {}", e))?;
-        let path = Self::get_path(Option::from(name));
-        fs::create_dir_all(path.parent().unwrap()).map_err(|e| format!("Directory creation failed: {}", e))?;
-        fs::write(path, data).map_err(|e| format!("Failed to write vault file: {}", e))
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let data = Self::encode(vault, password)?;
+        let path = Self::get_path(name)?;
+        VaultStorage::write(&path, &data, ConfigService::current().backup_count)
+    }
+
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let data = Self::encode(vault, password)?;
+        VaultStorage::write(&Self::get_path(name)?, &data, 0)
+    }
+
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        let path = Self::get_path(name)?;
+        let data = fs::read(path).map_err(|e| match e.kind() {
+            ErrorKind::NotFound => VaultError::NotFound("Vault".to_string()),
+            _ => VaultError::io("Failed to read vault file")(e),
+        })?;
+        Self::decode(&data, password)
+    }
+
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError> {
+        let mut vaults: BTreeMap<String, PathBuf> = BTreeMap::new();
+        // a missing default directory only means no vault was created there yet
+        if let Ok(files) = fs::read_dir(Self::default_dir()?) {
+            for file in files {
+                let file = file.map_err(VaultError::io("Failed to process entry"))?;
+                let file_name = file.file_name().to_string_lossy().to_string();
+                if let Some(name) = file_name.strip_suffix(VAULT_EXTENSION) {
+                    vaults.insert(name.to_string(), file.path());
+                }
+            }
+        }
+        for (name, location) in Self::registry()?.iter() {
+            vaults.insert(name.clone(), PathBuf::from(location));
+        }
+        Ok(vaults.into_iter().map(|(name, path)| (name, path.display().to_string())).collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
+        let path = Self::get_path(name)?;
+        fs::remove_file(path).map_err(VaultError::io("Failed to delete vault"))?;
+        Self::set_location(name, None)
+    }
+
+    fn file_path(&self, name: &str) -> Result<PathBuf, VaultError> {
+        Self::get_path(name)
     }
 
-    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, String> {
-        let path = Self::get_path(Option::from(name));
-        let data = fs::read(path).expect("Failed to read vault file");
-        let vault_file: VaultFile = serde_json::from_slice(&data).expect("Deserialization failed");
-        let VaultFile { ciphertext, salt, nonce } = vault_file;
-        VaultCrypto::decrypt(password, &ciphertext, &salt, &nonce)
+    fn register(&self, name: &str, location: &str) -> Result<(), VaultError> {
+        let path = Self::absolute(location)?;
+        let current = Self::get_path(name)?;
+        if current == path {
+            return Ok(());
+        }
+        if current.exists() {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}' at {}", name, current.display())));
+        }
+        Self::set_location(name, Some(path))
     }
 
-    fn list(&self) -> Result<Vec<String>, String> {
-        let path = Self::get_path(None);
-        let files = fs::read_dir(path)
-            .map_err(|e| format!("Failed to read vault directory: {}", e))?;
-        files
-            .map(|entry| {
-                entry
-                    .map_err(|e| format!("Failed to process entry: {}", e))
-                    .map(|file| file.file_name().to_string_lossy().to_string())
-            })
-            .collect()
+    fn unregister(&self, name: &str) -> Result<(), VaultError> {
+        Self::set_location(name, None)
     }
 
-    fn delete(&self, name: &str) -> Result<(), String> {
-        let path = Self::get_path(Option::from(name));
-        match fs::remove_file(path) {
-            Ok(_) => Ok(()),
-            Err(_) => Err("Failed to delete vault".into())
+    fn relocate(&self, name: &str, location: &str) -> Result<String, VaultError> {
+        let from = Self::get_path(name)?;
+        if !from.exists() {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        let to = Self::destination(name, location)?;
+        if to == from {
+            return Err(VaultError::AlreadyExists(format!("The vault at {}", to.display())));
         }
+        VaultStorage::copy(&from, &to)?;
+        if let Err(e) = Self::set_location(name, Some(to.clone())) {
+            let _ = VaultStorage::remove(&to);
+            return Err(e);
+        }
+        VaultStorage::remove(&from)?;
+        Ok(to.display().to_string())
+    }
+
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError> {
+        let from = Self::get_path(name)?;
+        if !from.exists() {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        if self.exists(new_name)? {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}'", new_name)));
+        }
+        let to = from.with_file_name(format!("{}{}", new_name, VAULT_EXTENSION));
+        VaultStorage::copy(&from, &to)?;
+        let mut registry = Self::registry()?;
+        registry.remove(name);
+        if to != Self::default_path(new_name)? {
+            registry.insert(new_name, &to.to_string_lossy());
+        }
+        if let Err(e) = registry.save(&ConfigService::registry_path()) {
+            let _ = VaultStorage::remove(&to);
+            return Err(e);
+        }
+        VaultStorage::remove(&from)
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, VaultError> {
+        Ok(Self::get_path(name)?.exists())
+    }
+
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError> {
+        let path = Self::get_path(name)?;
+        Ok(VaultStorage::backups(&path)?.into_iter().map(|(date, _)| date).collect())
     }
 
-    fn exists(&self, name: &str) -> Result<bool, String> {
-        let path = Self::get_path(Option::from(name));
-        Ok(path.exists())
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError> {
+        let path = Self::get_path(name)?;
+        VaultStorage::restore(&path, index, ConfigService::current().backup_count)
     }
 }
@@ -1,11 +1,26 @@
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
 use secrecy::SecretBox;
 use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
 
 pub trait VaultManagerTrait {
-    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), String>;
-    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), String>;
-    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, String>;
-    fn list(&self) -> Result<Vec<String>, String>;
-    fn delete(&self, name: &str) -> Result<(), String>;
-    fn exists(&self, name: &str) -> Result<bool, String>;
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError>;
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError>;
+    // saves bookkeeping like copy times without taking a backup of the previous version
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError>;
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError>;
+    // names with the location each one is stored at
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError>;
+    fn delete(&self, name: &str) -> Result<(), VaultError>;
+    fn file_path(&self, name: &str) -> Result<PathBuf, VaultError>;
+    fn register(&self, name: &str, location: &str) -> Result<(), VaultError>;
+    // forgets a registered location, the vault itself is left alone
+    fn unregister(&self, name: &str) -> Result<(), VaultError>;
+    // returns the resolved destination
+    fn relocate(&self, name: &str, location: &str) -> Result<String, VaultError>;
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError>;
+    fn exists(&self, name: &str) -> Result<bool, VaultError>;
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError>;
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError>;
 }
\ No newline at end of file
@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+use crate::domain::vault::vault_error::VaultError;
+use crate::repository::vault::vault_storage::VaultStorage;
+
+// named vaults stored outside the default data directory, by path or store locator
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct VaultRegistry {
+    #[serde(default)]
+    vaults: BTreeMap<String, String>,
+}
+
+impl VaultRegistry {
+    pub fn load(path: &Path) -> Result<Self, VaultError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(VaultError::io("Failed to read vault registry"))?;
+        toml::from_str(&text)
+            .map_err(|e| VaultError::Registry(format!("{}: {}", path.display(), e.message())))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), VaultError> {
+        let text = toml::to_string(self)
+            .map_err(|e| VaultError::Registry(format!("Serialization failed: {}", e)))?;
+        VaultStorage::write(path, text.as_bytes(), 0)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.vaults.get(name).map(|location| location.as_str())
+    }
+
+    pub fn insert(&mut self, name: &str, location: &str) {
+        self.vaults.insert(name.to_string(), location.to_string());
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<String> {
+        self.vaults.remove(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.vaults.iter()
+    }
+}
@@ -0,0 +1,240 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use chrono::{DateTime, Utc};
+use secrecy::SecretBox;
+use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
+use crate::domain::vault::vault_locator::VaultLocator;
+use crate::repository::vault::vault_http::VaultHttp;
+use crate::repository::vault::vault_manager::VaultManager;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::repository::vault::vault_registry::VaultRegistry;
+use crate::repository::vault::vault_sqlite::VaultSqlite;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::services::config_service::ConfigService;
+
+// sends each vault to the store holding it: a registered location first, then a file in
+// the default directory, then the configured vault_backend, and new files otherwise
+pub struct VaultRouter;
+
+impl VaultRouter {
+    pub fn resolve(name: &str) -> Result<VaultLocator, VaultError> {
+        if let Some(location) = Self::registry()?.get(name) {
+            return VaultLocator::parse(location, name);
+        }
+        let path = VaultManager::default_path(name)?;
+        let backend = ConfigService::current().vault_backend;
+        if backend.is_empty() || path.exists() {
+            return Ok(VaultLocator::File(path));
+        }
+        VaultLocator::parse(&backend, name)
+    }
+
+    fn registry() -> Result<VaultRegistry, VaultError> {
+        VaultRegistry::load(&ConfigService::registry_path())
+    }
+
+    // files in the default directory need no entry
+    fn set_location(name: &str, locator: Option<&VaultLocator>) -> Result<(), VaultError> {
+        let mut registry = Self::registry()?;
+        match locator {
+            Some(VaultLocator::File(path)) if *path == VaultManager::default_path(name)? => { registry.remove(name); }
+            Some(locator) => registry.insert(name, &locator.to_string()),
+            None => { registry.remove(name); }
+        }
+        registry.save(&ConfigService::registry_path())
+    }
+
+    fn store(locator: &VaultLocator) -> Result<Box<dyn VaultManagerTrait>, VaultError> {
+        match locator {
+            VaultLocator::File(_) => Ok(Box::new(VaultManager)),
+            VaultLocator::Sqlite { database, .. } => Ok(Box::new(VaultSqlite::open(database)?)),
+            VaultLocator::Http { base, .. } => Ok(Box::new(VaultHttp::new(base))),
+        }
+    }
+
+    // the name a vault is kept under in its store, files look their path up by vault name
+    fn key(locator: &VaultLocator, name: &str) -> String {
+        match locator {
+            VaultLocator::File(_) => name.to_string(),
+            VaultLocator::Sqlite { name, .. } | VaultLocator::Http { name, .. } => name.clone(),
+        }
+    }
+
+    fn route(name: &str) -> Result<(Box<dyn VaultManagerTrait>, String), VaultError> {
+        let locator = Self::resolve(name)?;
+        Ok((Self::store(&locator)?, Self::key(&locator, name)))
+    }
+
+    fn stored(locator: &VaultLocator) -> Result<bool, VaultError> {
+        match locator {
+            VaultLocator::File(path) => Ok(path.exists()),
+            VaultLocator::Sqlite { database, name } => VaultSqlite::open(database)?.exists(name),
+            VaultLocator::Http { base, name } => VaultHttp::new(base).exists(name),
+        }
+    }
+
+    fn read(locator: &VaultLocator) -> Result<Vec<u8>, VaultError> {
+        match locator {
+            VaultLocator::File(path) => fs::read(path).map_err(VaultError::io("Failed to read vault file")),
+            VaultLocator::Sqlite { database, name } => VaultSqlite::open(database)?.read(name),
+            VaultLocator::Http { base, name } => VaultHttp::new(base).read(name),
+        }
+    }
+
+    fn write(locator: &VaultLocator, data: &[u8]) -> Result<(), VaultError> {
+        match locator {
+            VaultLocator::File(path) => VaultStorage::write(path, data, 0),
+            VaultLocator::Sqlite { database, name } => VaultSqlite::open(database)?.write(name, data, 0),
+            VaultLocator::Http { base, name } => VaultHttp::new(base).write(name, data, 0),
+        }
+    }
+
+    fn remove(locator: &VaultLocator) -> Result<(), VaultError> {
+        match locator {
+            VaultLocator::File(path) => VaultStorage::remove(path),
+            VaultLocator::Sqlite { database, name } => VaultSqlite::open(database)?.remove(name),
+            VaultLocator::Http { base, name } => VaultHttp::new(base).remove(name),
+        }
+    }
+}
+
+impl VaultManagerTrait for VaultRouter {
+
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError> {
+        let locator = Self::resolve(name)?;
+        // callers check files up front, a shared store may already hold the vault
+        if !matches!(locator, VaultLocator::File(_)) && Self::stored(&locator)? {
+            return Err(VaultError::AlreadyExists(format!("The vault at {}", locator)));
+        }
+        Self::store(&locator)?.create(&Self::key(&locator, name), password)
+    }
+
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.save(&key, password, vault)
+    }
+
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.touch(&key, password, vault)
+    }
+
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.load(&key, password)
+    }
+
+    // files and registered locations, then whatever the configured backend holds
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError> {
+        let mut vaults: BTreeMap<String, String> = VaultManager.list()?.into_iter().collect();
+        let backend = ConfigService::current().vault_backend;
+        if !backend.is_empty() {
+            for (name, location) in Self::store(&VaultLocator::parse(&backend, "")?)?.list()? {
+                vaults.entry(name).or_insert(location);
+            }
+        }
+        Ok(vaults.into_iter().collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
+        let locator = Self::resolve(name)?;
+        Self::store(&locator)?.delete(&Self::key(&locator, name))?;
+        match locator {
+            VaultLocator::File(_) => Ok(()),
+            _ => Self::set_location(name, None),
+        }
+    }
+
+    fn file_path(&self, name: &str) -> Result<PathBuf, VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.file_path(&key)
+    }
+
+    fn register(&self, name: &str, location: &str) -> Result<(), VaultError> {
+        let current = Self::resolve(name)?;
+        if !VaultLocator::is_remote(location) && matches!(current, VaultLocator::File(_)) {
+            return VaultManager.register(name, location);
+        }
+        let locator = VaultLocator::parse(location, name)?;
+        if current == locator {
+            return Ok(());
+        }
+        if Self::stored(&current)? {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}' at {}", name, current)));
+        }
+        Self::set_location(name, Some(&locator))
+    }
+
+    fn unregister(&self, name: &str) -> Result<(), VaultError> {
+        Self::set_location(name, None)
+    }
+
+    // moves between stores copy the vault alone, backups stay behind and are removed with the source
+    fn relocate(&self, name: &str, location: &str) -> Result<String, VaultError> {
+        let from = Self::resolve(name)?;
+        let remote = VaultLocator::is_remote(location);
+        if !remote && matches!(from, VaultLocator::File(_)) {
+            return VaultManager.relocate(name, location);
+        }
+        if !Self::stored(&from)? {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        let to = match remote {
+            true => VaultLocator::parse(location, name)?,
+            false => VaultLocator::File(VaultManager::destination(name, location)?),
+        };
+        if to == from {
+            return Err(VaultError::AlreadyExists(format!("The vault at {}", to)));
+        }
+        if Self::stored(&to)? {
+            return Err(VaultError::AlreadyExists(to.to_string()));
+        }
+        let data = Self::read(&from)?;
+        Self::write(&to, &data)?;
+        // read the copy back before the original is removed
+        if Self::read(&to)? != data {
+            let _ = Self::remove(&to);
+            return Err(VaultError::Corrupt("the copy does not match the original".to_string()));
+        }
+        if let Err(e) = Self::set_location(name, Some(&to)) {
+            let _ = Self::remove(&to);
+            return Err(e);
+        }
+        Self::remove(&from)?;
+        Ok(to.to_string())
+    }
+
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError> {
+        if self.exists(new_name)? {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}'", new_name)));
+        }
+        let from = Self::resolve(name)?;
+        if let VaultLocator::File(_) = from {
+            return VaultManager.rename(name, new_name);
+        }
+        Self::store(&from)?.rename(&Self::key(&from, name), new_name)?;
+        let mut registry = Self::registry()?;
+        if registry.remove(name).is_some() {
+            registry.insert(new_name, &from.renamed(new_name).to_string());
+            registry.save(&ConfigService::registry_path())?;
+        }
+        Ok(())
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.exists(&key)
+    }
+
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.backups(&key)
+    }
+
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError> {
+        let (store, key) = Self::route(name)?;
+        store.restore(&key, index)
+    }
+}
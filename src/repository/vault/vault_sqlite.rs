@@ -0,0 +1,207 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use secrecy::SecretBox;
+use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
+use crate::domain::vault::vault_locator::VaultLocator;
+use crate::repository::vault::vault_manager::VaultManager;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::services::config_service::ConfigService;
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS vaults (
+        name TEXT PRIMARY KEY,
+        data BLOB NOT NULL,
+        updated_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS backups (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        data BLOB NOT NULL,
+        created_at TEXT NOT NULL
+    );
+";
+
+// encrypted vault files kept as blobs in an SQLite database, one row per vault
+pub struct VaultSqlite {
+    connection: Connection,
+    database: PathBuf,
+}
+
+impl VaultSqlite {
+    pub fn open(database: &Path) -> Result<Self, VaultError> {
+        let dir = database.parent().ok_or_else(|| VaultError::InvalidPath(database.display().to_string()))?;
+        fs::create_dir_all(dir).map_err(VaultError::io("Directory creation failed"))?;
+        #[cfg(unix)]
+        let created = !database.exists();
+        let connection = Connection::open(database).map_err(Self::error)?;
+        // only the owner may read a store it created, like vault files
+        #[cfg(unix)]
+        if created {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(database, fs::Permissions::from_mode(0o600))
+                .map_err(VaultError::io("Failed to restrict store permissions"))?;
+        }
+        connection.execute_batch(SCHEMA).map_err(Self::error)?;
+        Ok(Self { connection, database: database.to_path_buf() })
+    }
+
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, VaultError> {
+        self.connection
+            .query_row("SELECT data FROM vaults WHERE name = ?1", [name], |row| row.get(0))
+            .optional()
+            .map_err(Self::error)?
+            .ok_or(VaultError::NotFound("Vault".to_string()))
+    }
+
+    // the previous blob becomes a backup in the same transaction
+    pub fn write(&self, name: &str, data: &[u8], backups: usize) -> Result<(), VaultError> {
+        let now = Utc::now().to_rfc3339();
+        let tx = self.connection.unchecked_transaction().map_err(Self::error)?;
+        if backups > 0 {
+            tx.execute(
+                "INSERT INTO backups (name, data, created_at) SELECT name, data, ?2 FROM vaults WHERE name = ?1",
+                params![name, now],
+            ).map_err(Self::error)?;
+            tx.execute(
+                "DELETE FROM backups WHERE name = ?1 AND id NOT IN
+                    (SELECT id FROM backups WHERE name = ?1 ORDER BY id DESC LIMIT ?2)",
+                params![name, backups as i64],
+            ).map_err(Self::error)?;
+        }
+        tx.execute(
+            "INSERT INTO vaults (name, data, updated_at) VALUES (?1, ?2, ?3)
+                ON CONFLICT(name) DO UPDATE SET data = excluded.data, updated_at = excluded.updated_at",
+            params![name, data, now],
+        ).map_err(Self::error)?;
+        tx.commit().map_err(Self::error)
+    }
+
+    pub fn remove(&self, name: &str) -> Result<(), VaultError> {
+        let tx = self.connection.unchecked_transaction().map_err(Self::error)?;
+        let removed = tx.execute("DELETE FROM vaults WHERE name = ?1", [name]).map_err(Self::error)?;
+        if removed == 0 {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        tx.execute("DELETE FROM backups WHERE name = ?1", [name]).map_err(Self::error)?;
+        tx.commit().map_err(Self::error)
+    }
+
+    // newest first
+    fn backup_rows(&self, name: &str) -> Result<Vec<(i64, DateTime<Utc>)>, VaultError> {
+        let mut statement = self.connection
+            .prepare("SELECT id, created_at FROM backups WHERE name = ?1 ORDER BY id DESC")
+            .map_err(Self::error)?;
+        let rows = statement
+            .query_map([name], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+            .map_err(Self::error)?;
+        let mut backups = Vec::new();
+        for row in rows {
+            let (id, created_at) = row.map_err(Self::error)?;
+            let date = DateTime::parse_from_rfc3339(&created_at)
+                .map_err(|e| VaultError::Corrupt(format!("backup date {}: {}", created_at, e)))?;
+            backups.push((id, date.with_timezone(&Utc)));
+        }
+        Ok(backups)
+    }
+
+    fn locator(&self, name: &str) -> VaultLocator {
+        VaultLocator::Sqlite { database: self.database.clone(), name: name.to_string() }
+    }
+
+    fn error(err: rusqlite::Error) -> VaultError {
+        VaultError::Store(format!("SQLite store failed: {}", err))
+    }
+}
+
+impl VaultManagerTrait for VaultSqlite {
+
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError> {
+        let vault = Vault::new(Vec::new());
+        self.save(name, password, &vault)
+    }
+
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        let data = VaultManager::encode(vault, password)?;
+        self.write(name, &data, ConfigService::current().backup_count)
+    }
+
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        self.write(name, &VaultManager::encode(vault, password)?, 0)
+    }
+
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        VaultManager::decode(&self.read(name)?, password)
+    }
+
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError> {
+        let mut statement = self.connection
+            .prepare("SELECT name FROM vaults ORDER BY name")
+            .map_err(Self::error)?;
+        let names = statement
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(Self::error)?
+            .collect::<Result<Vec<String>, _>>()
+            .map_err(Self::error)?;
+        Ok(names.into_iter().map(|name| {
+            let location = self.locator(&name).to_string();
+            (name, location)
+        }).collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
+        self.remove(name)
+    }
+
+    fn file_path(&self, name: &str) -> Result<PathBuf, VaultError> {
+        Err(VaultError::InvalidPath(format!("{} is not a file", self.locator(name))))
+    }
+
+    // locations are kept by the router, a store only knows its own rows
+    fn register(&self, _name: &str, location: &str) -> Result<(), VaultError> {
+        Err(VaultError::Unsupported(format!("location {}", location)))
+    }
+
+    fn unregister(&self, _name: &str) -> Result<(), VaultError> {
+        Ok(())
+    }
+
+    fn relocate(&self, _name: &str, location: &str) -> Result<String, VaultError> {
+        Err(VaultError::Unsupported(format!("location {}", location)))
+    }
+
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError> {
+        if self.exists(new_name)? {
+            return Err(VaultError::AlreadyExists(format!("A vault named '{}'", new_name)));
+        }
+        let tx = self.connection.unchecked_transaction().map_err(Self::error)?;
+        let renamed = tx.execute("UPDATE vaults SET name = ?2 WHERE name = ?1", [name, new_name])
+            .map_err(Self::error)?;
+        if renamed == 0 {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        tx.execute("UPDATE backups SET name = ?2 WHERE name = ?1", [name, new_name]).map_err(Self::error)?;
+        tx.commit().map_err(Self::error)
+    }
+
+    fn exists(&self, name: &str) -> Result<bool, VaultError> {
+        self.connection
+            .query_row("SELECT EXISTS (SELECT 1 FROM vaults WHERE name = ?1)", [name], |row| row.get(0))
+            .map_err(Self::error)
+    }
+
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError> {
+        Ok(self.backup_rows(name)?.into_iter().map(|(_, date)| date).collect())
+    }
+
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError> {
+        let (id, _) = *self.backup_rows(name)?.get(index).ok_or(VaultError::NotFound("Backup".to_string()))?;
+        let data: Vec<u8> = self.connection
+            .query_row("SELECT data FROM backups WHERE id = ?1", [id], |row| row.get(0))
+            .map_err(Self::error)?;
+        // the current version is backed up too, so a restore can be undone
+        self.write(name, &data, ConfigService::current().backup_count)
+    }
+}
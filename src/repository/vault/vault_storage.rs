@@ -0,0 +1,144 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use rand::Rng;
+use crate::domain::vault::vault_error::VaultError;
+
+const BACKUP_EXTENSION: &str = ".vault.bak";
+const BACKUP_TIMESTAMP_FORMAT: &str = "%Y%m%dT%H%M%S%.6f";
+
+pub struct VaultStorage;
+
+impl VaultStorage {
+
+    pub fn write(path: &Path, data: &[u8], backups: usize) -> Result<(), VaultError> {
+        let dir = path.parent().ok_or_else(|| VaultError::InvalidPath(path.display().to_string()))?;
+        fs::create_dir_all(dir).map_err(VaultError::io("Directory creation failed"))?;
+        if path.exists() && backups > 0 {
+            Self::backup(path, backups)?;
+        }
+        Self::write_atomic(path, data)
+    }
+
+    pub fn backups(path: &Path) -> Result<Vec<(DateTime<Utc>, PathBuf)>, VaultError> {
+        let dir = path.parent().ok_or_else(|| VaultError::InvalidPath(path.display().to_string()))?;
+        let stem = Self::stem(path)?;
+        let files = match fs::read_dir(dir) {
+            Ok(files) => files,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let mut backups: Vec<(DateTime<Utc>, PathBuf)> = files
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let date = Self::backup_date(&stem, &entry.file_name().to_string_lossy())?;
+                Some((date, entry.path()))
+            })
+            .collect();
+        backups.sort_by_key(|b| std::cmp::Reverse(b.0));
+        Ok(backups)
+    }
+
+    pub fn restore(path: &Path, index: usize, backups: usize) -> Result<(), VaultError> {
+        let available = Self::backups(path)?;
+        let (_, backup) = available.get(index).ok_or(VaultError::NotFound("Backup".to_string()))?;
+        let data = fs::read(backup).map_err(VaultError::io("Failed to read backup"))?;
+        // the current version is backed up too, so a restore can be undone
+        Self::write(path, &data, backups)
+    }
+
+    // copies a vault and its backups, reading the copy back before the caller removes anything
+    pub fn copy(from: &Path, to: &Path) -> Result<(), VaultError> {
+        if to.exists() {
+            return Err(VaultError::AlreadyExists(to.display().to_string()));
+        }
+        let data = fs::read(from).map_err(VaultError::io("Failed to read vault file"))?;
+        let stem = Self::stem(to)?;
+        let backups = Self::backups(from)?;
+        Self::write(to, &data, 0)?;
+        let written = fs::read(to).map_err(VaultError::io("Failed to read copied vault"))?;
+        if written != data {
+            let _ = fs::remove_file(to);
+            return Err(VaultError::Corrupt("the copy does not match the original".to_string()));
+        }
+        // a copy that fails halfway is removed again, including the backups copied so far
+        let mut copied = vec![to.to_path_buf()];
+        for (date, backup) in backups {
+            let target = to.with_file_name(Self::backup_name(&stem, &date));
+            if let Err(e) = fs::copy(&backup, &target) {
+                for file in copied {
+                    let _ = fs::remove_file(file);
+                }
+                return Err(VaultError::io("Failed to copy backup")(e));
+            }
+            copied.push(target);
+        }
+        Ok(())
+    }
+
+    pub fn remove(path: &Path) -> Result<(), VaultError> {
+        for (_, backup) in Self::backups(path)? {
+            fs::remove_file(backup).map_err(VaultError::io("Failed to remove backup"))?;
+        }
+        fs::remove_file(path).map_err(VaultError::io("Failed to remove vault file"))
+    }
+
+    // <stem>.<timestamp>.vault.bak, also used by stores that keep backups as objects
+    pub fn backup_name(stem: &str, date: &DateTime<Utc>) -> String {
+        format!("{}.{}{}", stem, date.format(BACKUP_TIMESTAMP_FORMAT), BACKUP_EXTENSION)
+    }
+
+    pub fn backup_date(stem: &str, file_name: &str) -> Option<DateTime<Utc>> {
+        let timestamp = file_name.strip_prefix(stem)?.strip_prefix('.')?.strip_suffix(BACKUP_EXTENSION)?;
+        let date = NaiveDateTime::parse_from_str(timestamp, BACKUP_TIMESTAMP_FORMAT).ok()?;
+        Some(date.and_utc())
+    }
+
+    fn backup(path: &Path, backups: usize) -> Result<(), VaultError> {
+        let backup = path.with_file_name(Self::backup_name(&Self::stem(path)?, &Utc::now()));
+        fs::copy(path, &backup).map_err(VaultError::io("Failed to back up vault"))?;
+        for (_, old) in Self::backups(path)?.iter().skip(backups) {
+            fs::remove_file(old).map_err(VaultError::io("Failed to remove old backup"))?;
+        }
+        Ok(())
+    }
+
+    // write to a temporary sibling and rename it over the original,
+    // so a crash never leaves a partially written vault behind
+    fn write_atomic(path: &Path, data: &[u8]) -> Result<(), VaultError> {
+        // the random part keeps two writers in one process apart
+        let tmp = path.with_file_name(format!(".{}.tmp-{}-{:016x}", Self::stem(path)?, std::process::id(), rand::rng().random::<u64>()));
+        let _ = fs::remove_file(&tmp);
+        let mut options = OpenOptions::new();
+        options.write(true).create_new(true);
+        // only the owner may read vaults and exports
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let result = options.open(&tmp)
+            .and_then(|mut file| {
+                file.write_all(data)?;
+                file.sync_all()
+            })
+            .and_then(|_| fs::rename(&tmp, path));
+        if let Err(e) = result {
+            let _ = fs::remove_file(&tmp);
+            return Err(VaultError::io("Failed to write vault file")(e));
+        }
+        // persist the rename itself
+        if let Some(dir) = path.parent() {
+            if let Ok(dir) = File::open(dir) {
+                let _ = dir.sync_all();
+            }
+        }
+        Ok(())
+    }
+
+    fn stem(path: &Path) -> Result<String, VaultError> {
+        path.file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .ok_or_else(|| VaultError::InvalidPath(path.display().to_string()))
+    }
+}
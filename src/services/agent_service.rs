@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+use chrono::Utc;
+use secrecy::SecretBox;
+use crate::domain::app::error::AppError;
+use crate::domain::app::state::AppState;
+use crate::domain::cli::field::Field;
+use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::services::vault_service::VaultService;
+
+// unlocked vaults held by the agent, each in its own session with the usual ttl
+pub struct AgentService<V: VaultManagerTrait> {
+    vault: VaultService<V>,
+    sessions: HashMap<String, AppState>,
+}
+
+impl<V: VaultManagerTrait> AgentService<V> {
+
+    pub fn new(vault: V) -> Self {
+        Self { vault: VaultService::new(vault), sessions: HashMap::new() }
+    }
+
+    pub fn unlock(&mut self, name: &str, secret: &SecretBox<String>) -> Result<(), AppError> {
+        self.vault.exists(name)?;
+        let mut state = AppState { session: None };
+        self.vault.open(name, secret, &mut state)?;
+        self.lock(Some(name));
+        self.sessions.insert(name.to_string(), state);
+        Ok(())
+    }
+
+    pub fn is_unlocked(&mut self, name: &str) -> bool {
+        self.sweep();
+        self.sessions.contains_key(name)
+    }
+
+    // every access extends the session like it does in the repl
+    pub fn field(&mut self, name: &str, entry: &str, field: &Field, copied: bool) -> Result<String, AppError> {
+        let state = self.sessions.get_mut(name).ok_or(AppError::Other("Vault is locked".to_string()))?;
+        self.vault.is_accessible(state)?;
+        let mut matches = self.vault.find_entries(entry, state);
+        let entry = match matches.len() {
+            0 => return Err(AppError::Other("Entry not found".to_string())),
+            1 => matches.remove(0),
+            _ => return Err(AppError::Other(format!("Multiple entries match: {}", matches.join(", ")))),
+        };
+        let value = self.vault.field(&entry, field, state)?;
+        if copied {
+            self.vault.mark_copied_in_store(&entry, state)?;
+        }
+        Ok(value)
+    }
+
+    pub fn list(&mut self) -> String {
+        self.sweep();
+        if self.sessions.is_empty() {
+            return "No vaults unlocked".to_string();
+        }
+        let now = Utc::now();
+        let mut lines: Vec<String> = self.sessions.iter()
+            .filter_map(|(name, state)| state.session.as_ref().map(|s| (name, s.expires_at - now)))
+            .map(|(name, left)| format!("{} (locks in {}m {}s)", name, left.num_minutes(), left.num_seconds() % 60))
+            .collect();
+        lines.sort();
+        lines.join("\n")
+    }
+
+    // locks one vault or all of them, returning how many were locked
+    pub fn lock(&mut self, name: Option<&str>) -> usize {
+        let names: Vec<String> = match name {
+            Some(name) => self.sessions.keys().filter(|n| *n == name).cloned().collect(),
+            None => self.sessions.keys().cloned().collect(),
+        };
+        for name in &names {
+            if let Some(mut state) = self.sessions.remove(name) {
+                self.vault.close(&mut state);
+            }
+        }
+        names.len()
+    }
+
+    pub fn sweep(&mut self) {
+        let now = Utc::now();
+        let expired: Vec<String> = self.sessions.iter()
+            .filter(|(_, state)| state.session.as_ref().is_none_or(|s| s.expires_at < now))
+            .map(|(name, _)| name.clone())
+            .collect();
+        for name in expired {
+            self.lock(Some(&name));
+        }
+    }
+}
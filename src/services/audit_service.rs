@@ -0,0 +1,86 @@
+use std::collections::HashMap;
+use secrecy::ExposeSecret;
+use crate::domain::cli::audit_params::{AuditParams, AuditSort};
+use crate::domain::vault::audit_report::{AuditFinding, AuditIssue, AuditReport};
+use crate::domain::vault::password_entry::PasswordEntry;
+use crate::services::password_service::PasswordService;
+use crate::utils::constants::AUDIT_MIN_STRENGTH;
+
+pub struct AuditService;
+
+impl AuditService {
+
+    pub fn audit(entries: &[PasswordEntry], params: &AuditParams) -> AuditReport {
+        // entries without a password, such as imported notes, have nothing to audit
+        let entries: Vec<&PasswordEntry> = entries.iter()
+            .filter(|entry| !entry.password.expose_secret().is_empty())
+            .collect();
+        let mut uses: HashMap<&str, usize> = HashMap::new();
+        for entry in &entries {
+            *uses.entry(entry.password.expose_secret().as_str()).or_default() += 1;
+        }
+
+        let mut report = AuditReport { audited: entries.len(), ..AuditReport::default() };
+        for entry in entries {
+            let finding = Self::inspect(entry, uses[entry.password.expose_secret().as_str()], params);
+            if finding.issues.is_empty() {
+                report.healthy += 1;
+            }
+            let selected = match params.checks.is_empty() {
+                true => !finding.issues.is_empty(),
+                false => finding.issues.iter().any(|issue| params.checks.contains(&issue.check())),
+            };
+            if selected {
+                report.findings.push(finding);
+            }
+        }
+        Self::sort(&mut report.findings, params.sort);
+        report
+    }
+
+    fn inspect(entry: &PasswordEntry, uses: usize, params: &AuditParams) -> AuditFinding {
+        let password = entry.password.expose_secret();
+        let estimate = PasswordService::analyze(password, &[&entry.name, &entry.username]);
+        let age_days = entry.password_age().map(|age| age.num_days());
+
+        let mut issues = Vec::new();
+        if estimate.score < AUDIT_MIN_STRENGTH {
+            issues.push(AuditIssue::Weak(estimate.classification().to_string()));
+        }
+        if uses > 1 {
+            issues.push(AuditIssue::Reused(uses));
+        }
+        if Self::matches_username(password, &entry.username) {
+            issues.push(AuditIssue::UsernameMatch);
+        }
+        if let Some(days) = age_days.filter(|days| *days >= params.max_age_days as i64) {
+            issues.push(AuditIssue::Old(days));
+        }
+        AuditFinding {
+            name: entry.name.clone(),
+            strength: estimate.score,
+            guesses_log10: estimate.guesses_log10,
+            age_days,
+            issues,
+        }
+    }
+
+    // a password that contains the username, or the part of an email address before the @
+    fn matches_username(password: &str, username: &str) -> bool {
+        let password = password.to_lowercase();
+        let username = username.to_lowercase();
+        let local = username.split('@').next().unwrap_or_default();
+        [username.as_str(), local].iter().any(|name| name.len() >= 3 && password.contains(name))
+    }
+
+    fn sort(findings: &mut [AuditFinding], sort: AuditSort) {
+        match sort {
+            AuditSort::Severity => findings.sort_by(|a, b| {
+                b.issues.len().cmp(&a.issues.len()).then(a.guesses_log10.total_cmp(&b.guesses_log10))
+            }),
+            AuditSort::Name => findings.sort_by(|a, b| a.name.cmp(&b.name)),
+            AuditSort::Score => findings.sort_by(|a, b| a.guesses_log10.total_cmp(&b.guesses_log10)),
+            AuditSort::Age => findings.sort_by_key(|finding| std::cmp::Reverse(finding.age_days.unwrap_or(i64::MAX))),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use data_encoding::HEXUPPER;
+use md4::Md4;
+use secrecy::ExposeSecret;
+use sha1::{Digest, Sha1};
+use crate::domain::app::error::AppError;
+use crate::domain::vault::password_entry::PasswordEntry;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HashKind {
+    Sha1,
+    Ntlm,
+}
+
+// a sorted "<hash>:<count>" file as published by have i been pwned, searched in place
+pub struct BreachList {
+    reader: BufReader<File>,
+    len: u64,
+    pub kind: HashKind,
+}
+
+pub struct BreachService;
+
+impl BreachService {
+
+    pub fn open(path: &str) -> Result<BreachList, AppError> {
+        let io_err = |e: std::io::Error| AppError::Other(format!("Failed to read {}: {}", path, e));
+        let file = File::open(path).map_err(io_err)?;
+        let len = file.metadata().map_err(io_err)?.len();
+        let mut reader = BufReader::new(file);
+        let mut first = String::new();
+        reader.read_line(&mut first).map_err(io_err)?;
+        let kind = match Self::hash_of(first.as_bytes()).len() {
+            40 => HashKind::Sha1,
+            32 => HashKind::Ntlm,
+            _ => return Err(AppError::Other(format!("{} is not a SHA-1 or NTLM hash list", path))),
+        };
+        Ok(BreachList { reader, len, kind })
+    }
+
+    // how often the password appears in the list, zero when it is not breached
+    pub fn occurrences(list: &mut BreachList, password: &str) -> Result<u64, AppError> {
+        let target = Self::hash(list.kind, password);
+        let (mut low, mut high) = (0, list.len);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match Self::line_at(list, middle)? {
+                Some(line) if Self::hash_of(&line).to_ascii_uppercase() < target => low = middle + 1,
+                _ => high = middle,
+            }
+        }
+        let Some(line) = Self::line_at(list, low)? else { return Ok(0) };
+        if Self::hash_of(&line).to_ascii_uppercase() != target {
+            return Ok(0);
+        }
+        let count = line.split(|b| *b == b':').nth(1)
+            .and_then(|count| std::str::from_utf8(count).ok())
+            .and_then(|count| count.trim().parse::<u64>().ok())
+            .unwrap_or(1);
+        Ok(count)
+    }
+
+    // breached entries with their occurrence counts, most breached first
+    pub fn check(entries: &[PasswordEntry], list: &mut BreachList) -> Result<Vec<(String, u64)>, AppError> {
+        let mut breached = Vec::new();
+        for entry in entries.iter().filter(|entry| !entry.password.expose_secret().is_empty()) {
+            let count = Self::occurrences(list, entry.password.expose_secret())?;
+            if count > 0 {
+                breached.push((entry.name.clone(), count));
+            }
+        }
+        breached.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        Ok(breached)
+    }
+
+    pub fn hash(kind: HashKind, password: &str) -> Vec<u8> {
+        let digest = match kind {
+            HashKind::Sha1 => Sha1::digest(password.as_bytes()).to_vec(),
+            // ntlm is md4 over the utf-16le encoded password
+            HashKind::Ntlm => {
+                let utf16: Vec<u8> = password.encode_utf16().flat_map(|unit| unit.to_le_bytes()).collect();
+                Md4::digest(&utf16).to_vec()
+            }
+        };
+        HEXUPPER.encode(&digest).into_bytes()
+    }
+
+    // first complete line starting at or after pos, none at the end of the file
+    fn line_at(list: &mut BreachList, pos: u64) -> Result<Option<Vec<u8>>, AppError> {
+        let io_err = |e: std::io::Error| AppError::Other(format!("Failed to read hash list: {}", e));
+        let mut line = Vec::new();
+        if pos > 0 {
+            list.reader.seek(SeekFrom::Start(pos - 1)).map_err(io_err)?;
+            list.reader.read_until(b'\n', &mut line).map_err(io_err)?;
+            line.clear();
+        } else {
+            list.reader.seek(SeekFrom::Start(0)).map_err(io_err)?;
+        }
+        list.reader.read_until(b'\n', &mut line).map_err(io_err)?;
+        Ok(if line.is_empty() { None } else { Some(line) })
+    }
+
+    fn hash_of(line: &[u8]) -> &[u8] {
+        let hash = line.split(|b| *b == b':').next().unwrap_or_default();
+        hash.trim_ascii()
+    }
+}
@@ -0,0 +1,94 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use directories::ProjectDirs;
+use lazy_static::lazy_static;
+use crate::domain::app::config::{Config, CONFIG_KEYS};
+use crate::domain::app::error::AppError;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::utils::constants::{CONFIG_ENV_PREFIX, CONFIG_FILE_NAME, CONFIG_PATH_ENV, VAULT_REGISTRY_FILE_NAME};
+
+lazy_static! {
+    static ref CONFIG: RwLock<Config> = RwLock::new(ConfigService::load().unwrap_or_else(|err| {
+        eprintln!("{}, falling back to defaults", err);
+        Config::default()
+    }));
+}
+
+pub struct ConfigService;
+
+impl ConfigService {
+    // the loaded config, read once per process and refreshed by set
+    pub fn current() -> Config {
+        CONFIG.read().expect("Config lock poisoned").clone()
+    }
+
+    pub fn path() -> PathBuf {
+        if let Some(path) = env::var_os(CONFIG_PATH_ENV) {
+            return PathBuf::from(path);
+        }
+        let dirs = ProjectDirs::from("com", "passman", "Passman")
+            .expect("Failed to find project directory");
+        dirs.config_dir().join(CONFIG_FILE_NAME)
+    }
+
+    // kept next to the config file so PASSMAN_CONFIG relocates both
+    pub fn registry_path() -> PathBuf {
+        Self::path().with_file_name(VAULT_REGISTRY_FILE_NAME)
+    }
+
+    pub fn env_var(key: &str) -> String {
+        format!("{}{}", CONFIG_ENV_PREFIX, key.to_uppercase())
+    }
+
+    pub fn load() -> Result<Config, AppError> {
+        Self::load_from(&Self::path(), |name| env::var(name).ok())
+    }
+
+    // file values first, then PASSMAN_<KEY> environment overrides
+    pub fn load_from(path: &Path, env: impl Fn(&str) -> Option<String>) -> Result<Config, AppError> {
+        let mut config = Self::read(path)?;
+        for key in CONFIG_KEYS {
+            if let Some(value) = env(&Self::env_var(key)) {
+                config.set(key, &value)
+                    .map_err(|_| AppError::Other(format!("Invalid value in {}", Self::env_var(key))))?;
+            }
+        }
+        Ok(config)
+    }
+
+    // the file alone, without overrides, so set never persists an environment value
+    pub fn read(path: &Path) -> Result<Config, AppError> {
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+        let text = fs::read_to_string(path)
+            .map_err(|e| AppError::Other(format!("Failed to read config file: {}", e)))?;
+        let config: Config = toml::from_str(&text)
+            .map_err(|e| AppError::Other(format!("Invalid config file: {}", e.message())))?;
+        config.validate()
+            .map_err(|e| AppError::Other(format!("Invalid config file: {}", e)))?;
+        Ok(config)
+    }
+
+    pub fn write(path: &Path, config: &Config) -> Result<(), AppError> {
+        let text = toml::to_string(config)
+            .map_err(|e| AppError::Other(format!("Serialization failed: {}", e)))?;
+        Ok(VaultStorage::write(path, text.as_bytes(), 0)?)
+    }
+
+    pub fn get(key: &str) -> Result<String, AppError> {
+        Self::current().get(key)
+    }
+
+    pub fn set(key: &str, value: &str) -> Result<(), AppError> {
+        let path = Self::path();
+        let mut config = Self::read(&path)?;
+        config.set(key, value)?;
+        Self::write(&path, &config)?;
+        let reloaded = Self::load()?;
+        *CONFIG.write().expect("Config lock poisoned") = reloaded;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use secrecy::{ExposeSecret, SecretBox};
+use serde_json::{json, Value};
+use crate::domain::app::error::AppError;
+use crate::domain::cli::export_params::ExportFormat;
+use crate::domain::vault::custom_field::CustomField;
+use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_file::{VaultFile, VaultHeader};
+use crate::repository::vault::vault_crypto::VaultCrypto;
+use crate::utils::constants::{CSV_FIELD_PREFIX, CSV_SECRET_FIELD_PREFIX};
+
+const BITWARDEN_LOGIN: u8 = 1;
+const BITWARDEN_TEXT_FIELD: u8 = 0;
+const BITWARDEN_HIDDEN_FIELD: u8 = 1;
+
+pub struct ExportService;
+
+impl ExportService {
+
+    pub fn export(format: &ExportFormat, vault: &Vault, passphrase: Option<&SecretBox<String>>) -> Result<String, AppError> {
+        match format {
+            ExportFormat::Csv => Self::to_csv(vault),
+            ExportFormat::Json => serde_json::to_string_pretty(vault)
+                .map_err(|e| AppError::Other(format!("Serialization failed: {}", e))),
+            ExportFormat::Bitwarden => Self::to_bitwarden(vault),
+            ExportFormat::Encrypted => {
+                let passphrase = passphrase.ok_or(AppError::MissingArgument("passphrase".to_string()))?;
+                Self::to_bundle(vault, passphrase)
+            }
+        }
+    }
+
+    // what a format cannot carry, to be mentioned next to the export
+    pub fn omitted(format: &ExportFormat, vault: &Vault) -> Option<String> {
+        let history = vault.entries.iter().filter(|entry| !entry.history.is_empty()).count();
+        match format {
+            ExportFormat::Csv | ExportFormat::Bitwarden if history > 0 => {
+                Some(format!("the password history of {} entries is not part of a {} export", history, format))
+            }
+            _ => None,
+        }
+    }
+
+    // custom fields get a field:<name> or secret:<name> column, repeated once for every
+    // time a name occurs within one entry
+    fn to_csv(vault: &Vault) -> Result<String, AppError> {
+        let key = |field: &CustomField| (field.name.clone(), field.secret);
+        let mut columns: Vec<(String, bool)> = Vec::new();
+        for entry in &vault.entries {
+            for (i, field) in entry.fields.iter().enumerate() {
+                let occurrences = entry.fields[..=i].iter().filter(|f| key(f) == key(field)).count();
+                if columns.iter().filter(|column| **column == key(field)).count() < occurrences {
+                    columns.push(key(field));
+                }
+            }
+        }
+
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let csv_err = |e: csv::Error| AppError::Other(format!("CSV export failed: {}", e));
+        let mut header: Vec<String> = ["name", "url", "username", "password", "notes", "totp", "tags"].map(String::from).to_vec();
+        header.extend(columns.iter().map(|(name, secret)| match secret {
+            true => format!("{}{}", CSV_SECRET_FIELD_PREFIX, name),
+            false => format!("{}{}", CSV_FIELD_PREFIX, name),
+        }));
+        writer.write_record(&header).map_err(csv_err)?;
+        for entry in &vault.entries {
+            let tags = entry.tags.join(",");
+            let mut record = vec![
+                entry.name.as_str(),
+                entry.url.as_deref().unwrap_or_default(),
+                entry.username.as_str(),
+                entry.password.expose_secret().as_str(),
+                entry.notes.as_deref().unwrap_or_default(),
+                entry.otp.as_ref().map(|otp| otp.expose_secret().as_str()).unwrap_or_default(),
+                tags.as_str(),
+            ];
+            for (i, column) in columns.iter().enumerate() {
+                let occurrence = columns[..i].iter().filter(|c| *c == column).count();
+                let value = entry.fields.iter()
+                    .filter(|field| key(field) == *column)
+                    .nth(occurrence)
+                    .map(|field| field.value.expose_secret().as_str());
+                record.push(value.unwrap_or_default());
+            }
+            writer.write_record(&record).map_err(csv_err)?;
+        }
+        let data = writer.into_inner().map_err(|e| AppError::Other(format!("CSV export failed: {}", e)))?;
+        String::from_utf8(data).map_err(|e| AppError::Other(format!("CSV export failed: {}", e)))
+    }
+
+    // mirrors the unencrypted json export of bitwarden, with the first tag of an entry as its folder
+    fn to_bitwarden(vault: &Vault) -> Result<String, AppError> {
+        let mut folders: Vec<&str> = vault.entries.iter()
+            .filter_map(|entry| entry.tags.first().map(|tag| tag.as_str()))
+            .collect();
+        folders.sort();
+        folders.dedup();
+        let folder_id = |tag: &str| format!("passman-folder-{}", folders.iter().position(|f| *f == tag).unwrap());
+
+        let items: Vec<Value> = vault.entries.iter()
+            .map(|entry| json!({
+                "type": BITWARDEN_LOGIN,
+                "name": entry.name,
+                "notes": entry.notes,
+                "favorite": false,
+                "folderId": entry.tags.first().map(|tag| folder_id(tag)),
+                "login": {
+                    "username": entry.username,
+                    "password": entry.password.expose_secret(),
+                    "totp": entry.otp.as_ref().map(|otp| otp.expose_secret()),
+                    "uris": entry.url.iter().map(|url| json!({ "match": null, "uri": url })).collect::<Vec<Value>>(),
+                },
+                "fields": entry.fields.iter().map(|field| json!({
+                    "name": field.name,
+                    "value": field.value.expose_secret(),
+                    "type": if field.secret { BITWARDEN_HIDDEN_FIELD } else { BITWARDEN_TEXT_FIELD },
+                })).collect::<Vec<Value>>(),
+            }))
+            .collect();
+        let export = json!({
+            "encrypted": false,
+            "folders": folders.iter().map(|name| json!({ "id": folder_id(name), "name": name })).collect::<Vec<Value>>(),
+            "items": items,
+        });
+        serde_json::to_string_pretty(&export).map_err(|e| AppError::Other(format!("Serialization failed: {}", e)))
+    }
+
+    // a bundle is a single blob vault file keyed by the export passphrase, so it never
+    // carries the data key of the vault it came from
+    fn to_bundle(vault: &Vault, passphrase: &SecretBox<String>) -> Result<String, AppError> {
+        let header = VaultHeader::blob();
+        let (salt, nonce, ciphertext) = VaultCrypto::encrypt(vault, passphrase, &header)?;
+        let bundle = VaultFile { header: Some(header), salt, nonce, ciphertext, entries: Vec::new() };
+        serde_json::to_string(&bundle).map_err(|e| AppError::Other(format!("Serialization failed: {}", e)))
+    }
+}
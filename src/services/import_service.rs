@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use secrecy::SecretBox;
+use serde::Deserialize;
+use crate::domain::app::error::AppError;
+use crate::domain::cli::import_params::ImportFormat;
+use crate::domain::vault::custom_field::CustomField;
+use crate::domain::vault::password_entry::PasswordEntry;
+use crate::repository::vault::vault_crypto::VaultCrypto;
+use crate::repository::vault::vault_manager::VaultManager;
+use crate::services::otp_service::OtpService;
+use crate::utils::constants::{ARG_MAX_LEN, ARG_REGEX, CSV_FIELD_PREFIX, CSV_SECRET_FIELD_PREFIX};
+
+// header aliases for each csv column, compared case-insensitively
+struct CsvColumns {
+    name: &'static [&'static str],
+    username: &'static [&'static str],
+    password: &'static [&'static str],
+    url: &'static [&'static str],
+    notes: &'static [&'static str],
+    otp: &'static [&'static str],
+    tags: &'static [&'static str],
+}
+
+const KEEPASS_COLUMNS: CsvColumns = CsvColumns {
+    name: &["title", "account"],
+    username: &["username", "login name", "user name"],
+    password: &["password"],
+    url: &["url", "web site"],
+    notes: &["notes", "comments"],
+    otp: &["totp"],
+    tags: &["group", "tags"],
+};
+
+const ONEPASSWORD_COLUMNS: CsvColumns = CsvColumns {
+    name: &["title"],
+    username: &["username"],
+    password: &["password"],
+    url: &["url", "website"],
+    notes: &["notes", "notesplain"],
+    otp: &["otpauth", "one-time password"],
+    tags: &["tags"],
+};
+
+// firefox exports the same columns without a name, which is then taken from the url
+const CHROME_COLUMNS: CsvColumns = CsvColumns {
+    name: &["name"],
+    username: &["username"],
+    password: &["password"],
+    url: &["url"],
+    notes: &["note", "notes"],
+    otp: &[],
+    tags: &[],
+};
+
+#[derive(Deserialize)]
+struct BitwardenExport {
+    #[serde(default)]
+    encrypted: bool,
+    #[serde(default)]
+    folders: Vec<BitwardenFolder>,
+    #[serde(default)]
+    items: Vec<BitwardenItem>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenFolder {
+    id: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BitwardenItem {
+    #[serde(rename = "type")]
+    item_type: u8,
+    name: Option<String>,
+    notes: Option<String>,
+    folder_id: Option<String>,
+    login: Option<BitwardenLogin>,
+    #[serde(default)]
+    fields: Vec<BitwardenField>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenLogin {
+    username: Option<String>,
+    password: Option<String>,
+    totp: Option<String>,
+    #[serde(default)]
+    uris: Vec<BitwardenUri>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenUri {
+    uri: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BitwardenField {
+    name: Option<String>,
+    value: Option<String>,
+    #[serde(rename = "type")]
+    field_type: u8,
+}
+
+const BITWARDEN_LOGIN: u8 = 1;
+const BITWARDEN_SECURE_NOTE: u8 = 2;
+const BITWARDEN_HIDDEN_FIELD: u8 = 1;
+
+pub struct ImportService;
+
+impl ImportService {
+
+    pub fn parse(format: &ImportFormat, data: &str) -> Result<Vec<PasswordEntry>, AppError> {
+        match format {
+            ImportFormat::Bitwarden => Self::parse_bitwarden(data),
+            ImportFormat::KeePass => Self::parse_csv(data, &KEEPASS_COLUMNS),
+            ImportFormat::OnePassword => Self::parse_csv(data, &ONEPASSWORD_COLUMNS),
+            ImportFormat::Chrome => Self::parse_csv(data, &CHROME_COLUMNS),
+            ImportFormat::Encrypted => Err(AppError::MissingArgument("passphrase".to_string())),
+        }
+    }
+
+    pub fn parse_bundle(data: &str, passphrase: &SecretBox<String>) -> Result<Vec<PasswordEntry>, AppError> {
+        let mut vault = VaultManager::decode(data.as_bytes(), passphrase)
+            .and_then(|mut vault| VaultCrypto::unseal_all(&mut vault).map(|_| vault))
+            .map_err(|e| AppError::Other(format!("Failed to open bundle: {}", e)))?;
+        Ok(std::mem::take(&mut vault.entries))
+    }
+
+    fn parse_bitwarden(data: &str) -> Result<Vec<PasswordEntry>, AppError> {
+        let export: BitwardenExport = serde_json::from_str(data)
+            .map_err(|e| AppError::Other(format!("Invalid Bitwarden export: {}", e)))?;
+        if export.encrypted {
+            return Err(AppError::Other("Encrypted Bitwarden exports are not supported".to_string()));
+        }
+        let folders: HashMap<String, String> = export.folders.into_iter()
+            .map(|folder| (folder.id, folder.name))
+            .collect();
+        let entries = export.items.into_iter()
+            .filter(|item| item.item_type == BITWARDEN_LOGIN || item.item_type == BITWARDEN_SECURE_NOTE)
+            .map(|item| {
+                let login = item.login.unwrap_or(BitwardenLogin { username: None, password: None, totp: None, uris: Vec::new() });
+                let url = login.uris.into_iter().find_map(|uri| uri.uri);
+                let mut entry = Self::build_entry(
+                    item.name.as_deref(),
+                    login.username.as_deref(),
+                    login.password.as_deref(),
+                    url.as_deref(),
+                );
+                entry.notes = item.notes.filter(|notes| !notes.is_empty());
+                entry.tags = item.folder_id
+                    .and_then(|id| folders.get(&id).cloned())
+                    .into_iter()
+                    .collect();
+                entry.fields = item.fields.into_iter()
+                    .filter_map(|field| Some(CustomField {
+                        name: Self::sanitize_name(&field.name?),
+                        value: SecretBox::new(Box::from(field.value.unwrap_or_default())),
+                        secret: field.field_type == BITWARDEN_HIDDEN_FIELD,
+                    }))
+                    .collect();
+                Self::set_otp(&mut entry, login.totp.as_deref());
+                entry
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    fn parse_csv(data: &str, columns: &CsvColumns) -> Result<Vec<PasswordEntry>, AppError> {
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .from_reader(data.as_bytes());
+        let raw_headers: Vec<String> = reader.headers()
+            .map_err(|e| AppError::Other(format!("Invalid CSV export: {}", e)))?
+            .iter()
+            .map(|header| header.trim().to_string())
+            .collect();
+        let headers: Vec<String> = raw_headers.iter().map(|header| header.to_lowercase()).collect();
+        // custom field columns as passman exports them, names keep their case
+        let fields: Vec<(usize, &str, bool)> = raw_headers.iter()
+            .enumerate()
+            .filter_map(|(i, header)| header.strip_prefix(CSV_SECRET_FIELD_PREFIX).map(|name| (i, name, true))
+                .or_else(|| header.strip_prefix(CSV_FIELD_PREFIX).map(|name| (i, name, false))))
+            .collect();
+        let index = |aliases: &[&str]| headers.iter().position(|header| aliases.contains(&header.as_str()));
+        let (name, username, password, url, notes, otp, tags) = (
+            index(columns.name),
+            index(columns.username),
+            index(columns.password),
+            index(columns.url),
+            index(columns.notes),
+            index(columns.otp),
+            index(columns.tags),
+        );
+        if password.is_none() {
+            return Err(AppError::Other("CSV export has no password column".to_string()));
+        }
+
+        let mut entries = Vec::new();
+        for record in reader.records() {
+            let record = record.map_err(|e| AppError::Other(format!("Invalid CSV export: {}", e)))?;
+            let column = |i: Option<usize>| i.and_then(|i| record.get(i)).filter(|value| !value.is_empty());
+            let mut entry = Self::build_entry(column(name), column(username), column(password), column(url));
+            entry.notes = column(notes).map(String::from);
+            entry.tags = column(tags)
+                .map(|tags| tags.split([',', ';', '/']).map(|t| t.trim().to_string()).filter(|t| !t.is_empty()).collect())
+                .unwrap_or_default();
+            Self::set_otp(&mut entry, column(otp));
+            for (i, name, secret) in &fields {
+                if let Some(value) = column(Some(*i)) {
+                    entry.fields.push(CustomField {
+                        name: Self::sanitize_name(name),
+                        value: SecretBox::new(Box::from(value.to_string())),
+                        secret: *secret,
+                    });
+                }
+            }
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    fn build_entry(name: Option<&str>, username: Option<&str>, password: Option<&str>, url: Option<&str>) -> PasswordEntry {
+        // entries without a title are named after the host of their url
+        let name = name
+            .filter(|name| !name.trim().is_empty())
+            .map(String::from)
+            .or_else(|| url.map(Self::host))
+            .unwrap_or_else(|| "imported".to_string());
+        let mut entry = PasswordEntry::new(
+            &Self::sanitize_name(&name),
+            username.unwrap_or_default(),
+            password.unwrap_or_default(),
+        );
+        entry.url = url.map(String::from);
+        entry
+    }
+
+    fn set_otp(entry: &mut PasswordEntry, otp: Option<&str>) {
+        let Some(otp) = otp.filter(|otp| !otp.is_empty()) else { return };
+        if OtpService::parse(otp).is_ok() {
+            entry.otp = Some(SecretBox::new(Box::from(otp.to_string())));
+        } else {
+            entry.fields.push(CustomField {
+                name: "totp".to_string(),
+                value: SecretBox::new(Box::from(otp.to_string())),
+                secret: true,
+            });
+        }
+    }
+
+    fn host(url: &str) -> String {
+        let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+        without_scheme.split(['/', '?', '#', ':']).next().unwrap_or_default().to_string()
+    }
+
+    // imported names must be usable as command arguments
+    pub fn sanitize_name(name: &str) -> String {
+        let sanitized: String = name.trim()
+            .chars()
+            .map(|c| if ARG_REGEX.is_match(&c.to_string()) { c } else { '_' })
+            .take(ARG_MAX_LEN)
+            .collect();
+        if sanitized.is_empty() { "imported".to_string() } else { sanitized }
+    }
+}
@@ -0,0 +1,100 @@
+use chrono::Utc;
+use data_encoding::BASE32_NOPAD;
+use hmac::{Hmac, Mac};
+use sha1::Sha1;
+use sha2::{Sha256, Sha512};
+use crate::domain::app::error::AppError;
+use crate::domain::vault::totp::{Totp, TotpAlgorithm};
+use crate::utils::constants::{TOTP_DEFAULT_DIGITS, TOTP_DEFAULT_PERIOD};
+
+pub struct OtpService;
+
+impl OtpService {
+
+    // accepts either an otpauth://totp uri or a bare base32 secret
+    pub fn parse(input: &str) -> Result<Totp, AppError> {
+        let input = input.trim();
+        let mut totp = Totp {
+            secret: Vec::new(),
+            algorithm: TotpAlgorithm::Sha1,
+            digits: TOTP_DEFAULT_DIGITS,
+            period: TOTP_DEFAULT_PERIOD,
+        };
+        let secret = match input.strip_prefix("otpauth://totp/") {
+            Some(rest) => {
+                let (_label, query) = rest.split_once('?')
+                    .ok_or(AppError::Other("Invalid otpauth URI".to_string()))?;
+                let mut secret = None;
+                for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+                    match key.to_lowercase().as_str() {
+                        "secret" => secret = Some(value.to_string()),
+                        "algorithm" => totp.algorithm = match value.to_uppercase().as_str() {
+                            "SHA1" => TotpAlgorithm::Sha1,
+                            "SHA256" => TotpAlgorithm::Sha256,
+                            "SHA512" => TotpAlgorithm::Sha512,
+                            _ => return Err(AppError::Other(format!("Unsupported OTP algorithm: {}", value))),
+                        },
+                        "digits" => totp.digits = value.parse()
+                            .map_err(|_| AppError::Other("Invalid OTP digits".to_string()))?,
+                        "period" => totp.period = value.parse()
+                            .map_err(|_| AppError::Other("Invalid OTP period".to_string()))?,
+                        _ => {}
+                    }
+                }
+                secret.ok_or(AppError::Other("Missing OTP secret".to_string()))?
+            }
+            None if input.starts_with("otpauth://") => {
+                return Err(AppError::Other("Only TOTP URIs are supported".to_string()));
+            }
+            None => input.to_string(),
+        };
+        if totp.digits != 6 && totp.digits != 8 {
+            return Err(AppError::Other("OTP codes must have 6 or 8 digits".to_string()));
+        }
+        if totp.period == 0 {
+            return Err(AppError::Other("Invalid OTP period".to_string()));
+        }
+        totp.secret = Self::decode_secret(&secret)?;
+        Ok(totp)
+    }
+
+    pub fn generate(totp: &Totp, unix_time: u64) -> String {
+        let counter = (unix_time / totp.period).to_be_bytes();
+        let hash = match totp.algorithm {
+            TotpAlgorithm::Sha1 => Self::hmac::<Hmac<Sha1>>(&totp.secret, &counter),
+            TotpAlgorithm::Sha256 => Self::hmac::<Hmac<Sha256>>(&totp.secret, &counter),
+            TotpAlgorithm::Sha512 => Self::hmac::<Hmac<Sha512>>(&totp.secret, &counter),
+        };
+        // dynamic truncation (rfc 4226, section 5.3)
+        let offset = (hash[hash.len() - 1] & 0x0f) as usize;
+        let binary = u32::from_be_bytes([hash[offset], hash[offset + 1], hash[offset + 2], hash[offset + 3]]) & 0x7fff_ffff;
+        let code = binary % 10u32.pow(totp.digits);
+        format!("{:0width$}", code, width = totp.digits as usize)
+    }
+
+    pub fn current(totp: &Totp) -> (String, u64) {
+        let now = Utc::now().timestamp() as u64;
+        let remaining = totp.period - now % totp.period;
+        (Self::generate(totp, now), remaining)
+    }
+
+    fn hmac<M: Mac + hmac::digest::KeyInit>(key: &[u8], message: &[u8]) -> Vec<u8> {
+        let mut mac = <M as Mac>::new_from_slice(key).expect("HMAC accepts keys of any length");
+        mac.update(message);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    fn decode_secret(secret: &str) -> Result<Vec<u8>, AppError> {
+        let normalized: String = secret
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '=' && *c != '-')
+            .collect::<String>()
+            .to_uppercase();
+        let decoded = BASE32_NOPAD.decode(normalized.as_bytes())
+            .map_err(|_| AppError::Other("OTP secret must be base32".to_string()))?;
+        if decoded.is_empty() {
+            return Err(AppError::Other("OTP secret cannot be empty".to_string()));
+        }
+        Ok(decoded)
+    }
+}
@@ -1,8 +1,10 @@
-use passwords::analyzer::analyze;
-use passwords::PasswordGenerator;
-use passwords::scorer::score;
+use rand::Rng;
+use rand::seq::SliceRandom;
 use crate::domain::app::error::AppError;
+use crate::domain::cli::passphrase_params::PassphraseParams;
 use crate::domain::cli::password_params::PasswordParams;
+use crate::domain::vault::strength_estimate::StrengthEstimate;
+use crate::services::strength_service::StrengthService;
 use crate::utils::constants::*;
 
 pub struct PasswordService;
@@ -10,7 +12,11 @@ pub struct PasswordService;
 impl PasswordService {
 
     pub fn generate(params: PasswordParams) -> Result<String, AppError> {
-        if !(PASSWORD_MIN_LEN..PASSWORD_MAX_LEN).contains(&params.length) {
+        Self::generate_with(&params, &mut rand::rng())
+    }
+
+    pub fn generate_with<R: Rng + ?Sized>(params: &PasswordParams, rng: &mut R) -> Result<String, AppError> {
+        if !(PASSWORD_MIN_LEN..=PASSWORD_MAX_LEN).contains(&params.length) {
             return Err(AppError::Other(
                 format!(
                     "Password length must be between {} and {} characters",
@@ -19,37 +25,158 @@ impl PasswordService {
                 ))
             );
         }
-        let generator = PasswordGenerator::new()
-            .length(params.length)
-            .numbers(true)
-            .symbols(true)
-            .lowercase_letters(true)
-            .uppercase_letters(true)
-            .symbols(true)
-            .exclude_similar_characters(params.avoid_ambiguous);
-        Ok(generator.generate_one().unwrap())
-    }
-
-    pub fn analyze(password: String) -> (f64, String) {
-        let analyzed = analyze(password);
-        let score = score(&analyzed);
-        let classification = if score < 20.0 {
-            "Very dangerous"
-        } else if score < 40.0 {
-            "Dangerous"
-        } else if score < 60.0 {
-            "Very weak"
-        } else if score < 80.0 {
-            "Weak"
-        } else if score < 90.0 {
-            "Medium"
-        } else if score < 95.0 {
-            "Strong"
-        } else if score < 99.0 {
-            "Very strong"
-        } else {
-            "Invulnerable"
+        let classes = Self::char_classes(params)?;
+        let required: usize = classes.iter().map(|(_, min)| min).sum();
+        if required > params.length {
+            return Err(AppError::Other(
+                format!("Minimum character counts ({}) exceed the password length ({})", required, params.length)
+            ));
+        }
+
+        let counts = Self::class_counts(&classes, params.length, rng);
+        let mut password = Vec::with_capacity(params.length);
+        for ((alphabet, _), count) in classes.iter().zip(counts) {
+            for _ in 0..count {
+                password.push(alphabet[rng.random_range(0..alphabet.len())]);
+            }
+        }
+        password.shuffle(rng);
+        Ok(password.into_iter().collect())
+    }
+
+    // enabled classes with their alphabet and minimum count
+    fn char_classes(params: &PasswordParams) -> Result<Vec<(Vec<char>, usize)>, AppError> {
+        let symbols = params.symbol_set.as_deref().unwrap_or(SYMBOL_CHARS);
+        let policies = [
+            ("lowercase", LOWERCASE_CHARS, &params.lowercase),
+            ("uppercase", UPPERCASE_CHARS, &params.uppercase),
+            ("digits", DIGIT_CHARS, &params.digits),
+            ("symbols", symbols, &params.symbols),
+        ];
+        let mut classes = Vec::new();
+        for (name, chars, policy) in policies {
+            if !policy.enabled {
+                if policy.min > 0 {
+                    return Err(AppError::Other(format!("Cannot require {} while excluding them", name)));
+                }
+                continue;
+            }
+            let mut alphabet: Vec<char> = chars.chars()
+                .filter(|c| !c.is_whitespace())
+                .filter(|c| !params.avoid_ambiguous || !AMBIGUOUS_CHARS.contains(*c))
+                .collect();
+            alphabet.sort();
+            alphabet.dedup();
+            if alphabet.is_empty() {
+                return Err(AppError::Other(format!("No {} left to generate from", name)));
+            }
+            classes.push((alphabet, policy.min));
+        }
+        if classes.is_empty() {
+            return Err(AppError::Other("At least one character class must be enabled".to_string()));
+        }
+        Ok(classes)
+    }
+
+    // picks how many characters each class gets, weighted by how many passwords have that split,
+    // so that every password meeting the minimums is equally likely
+    fn class_counts<R: Rng + ?Sized>(classes: &[(Vec<char>, usize)], length: usize, rng: &mut R) -> Vec<usize> {
+        let ln_factorial: Vec<f64> = (0..=length)
+            .scan(0.0, |acc, i| {
+                if i > 0 { *acc += (i as f64).ln(); }
+                Some(*acc)
+            })
+            .collect();
+        // log of length! / prod(k!) * prod(alphabet^k), without the constant length!
+        let weight = |counts: &[usize]| -> f64 {
+            counts.iter().zip(classes)
+                .map(|(&k, (alphabet, _))| k as f64 * (alphabet.len() as f64).ln() - ln_factorial[k])
+                .sum::<f64>()
+                .exp()
         };
-        (score, classification.to_string())
+        let mins: Vec<usize> = classes.iter().map(|(_, min)| *min).collect();
+
+        let mut total = 0.0;
+        Self::compositions(&mins, length, &mut Vec::new(), &mut |counts| {
+            total += weight(counts);
+            true
+        });
+        let mut target = rng.random::<f64>() * total;
+        let mut chosen = mins.clone();
+        Self::compositions(&mins, length, &mut Vec::new(), &mut |counts| {
+            chosen = counts.to_vec();
+            target -= weight(counts);
+            target > 0.0
+        });
+        chosen
+    }
+
+    // visits every split of length over the classes that meets their minimums, until visit returns false
+    fn compositions(mins: &[usize], remaining: usize, counts: &mut Vec<usize>, visit: &mut dyn FnMut(&[usize]) -> bool) -> bool {
+        let min = mins[counts.len()];
+        if counts.len() == mins.len() - 1 {
+            if remaining < min {
+                return true;
+            }
+            counts.push(remaining);
+            let proceed = visit(counts);
+            counts.pop();
+            return proceed;
+        }
+        let reserved: usize = mins[counts.len() + 1..].iter().sum();
+        for count in min..=remaining.saturating_sub(reserved) {
+            counts.push(count);
+            let proceed = Self::compositions(mins, remaining - count, counts, visit);
+            counts.pop();
+            if !proceed {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn passphrase(params: &PassphraseParams) -> Result<(String, f64), AppError> {
+        Self::passphrase_with(params, &mut rand::rng())
+    }
+
+    // rng is a parameter so the word and character distribution can be tested
+    pub fn passphrase_with<R: Rng + ?Sized>(params: &PassphraseParams, rng: &mut R) -> Result<(String, f64), AppError> {
+        if !(PASSPHRASE_MIN_WORDS..=PASSPHRASE_MAX_WORDS).contains(&params.words) {
+            return Err(AppError::Other(
+                format!(
+                    "Passphrase must have between {} and {} words",
+                    PASSPHRASE_MIN_WORDS,
+                    PASSPHRASE_MAX_WORDS
+                ))
+            );
+        }
+        let mut words: Vec<String> = (0..params.words)
+            .map(|_| WORDS[rng.random_range(0..WORDS.len())].to_string())
+            .collect();
+        let mut entropy = params.words as f64 * (WORDS.len() as f64).log2();
+
+        if params.capitalize {
+            for word in words.iter_mut() {
+                word[..1].make_ascii_uppercase();
+            }
+        }
+        // extra characters are appended to a random word, which adds the choice of word to the entropy
+        let mut insert = |alphabet: &str, words: &mut Vec<String>| {
+            let chars: Vec<char> = alphabet.chars().collect();
+            let position = rng.random_range(0..words.len());
+            words[position].push(chars[rng.random_range(0..chars.len())]);
+            (chars.len() as f64).log2() + (words.len() as f64).log2()
+        };
+        if params.digit {
+            entropy += insert(DIGIT_CHARS, &mut words);
+        }
+        if params.symbol {
+            entropy += insert(PASSPHRASE_SYMBOL_CHARS, &mut words);
+        }
+        Ok((words.join(&params.separator), entropy))
+    }
+
+    pub fn analyze(password: &str, user_inputs: &[&str]) -> StrengthEstimate {
+        StrengthService::estimate(password, user_inputs)
     }
 }
@@ -0,0 +1,88 @@
+use crate::domain::vault::password_entry::PasswordEntry;
+
+const EXACT_SCORE: u32 = 1000;
+const PREFIX_SCORE: u32 = 800;
+const SUBSTRING_SCORE: u32 = 600;
+const SUBSEQUENCE_MAX_SCORE: u32 = 500;
+
+pub struct SearchService;
+
+impl SearchService {
+
+    pub fn score(query: &str, candidate: &str) -> Option<u32> {
+        let query = query.to_lowercase();
+        let candidate = candidate.to_lowercase();
+        if query.is_empty() || candidate.is_empty() {
+            return None;
+        }
+        // shorter candidates rank higher among equally good matches
+        let extra = (candidate.chars().count().saturating_sub(query.chars().count())).min(100) as u32;
+        if candidate == query {
+            Some(EXACT_SCORE)
+        } else if candidate.starts_with(&query) {
+            Some(PREFIX_SCORE - extra)
+        } else if candidate.contains(&query) {
+            Some(SUBSTRING_SCORE - extra)
+        } else {
+            Self::subsequence_score(&query, &candidate)
+        }
+    }
+
+    pub fn rank<'a>(query: &str, entries: &'a [PasswordEntry]) -> Vec<(&'a PasswordEntry, u32)> {
+        let mut ranked: Vec<(&PasswordEntry, u32)> = entries.iter()
+            .filter_map(|entry| Self::score_entry(query, entry).map(|score| (entry, score)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+        ranked
+    }
+
+    // the entries a query stands for when one is expected: an exact name, else every name match,
+    // else every match on other fields, best first, so anything but one match needs a choice
+    pub fn resolve<'a>(query: &str, entries: &'a [PasswordEntry]) -> Vec<&'a PasswordEntry> {
+        if let Some(entry) = entries.iter().find(|e| e.name == query) {
+            return vec![entry];
+        }
+        let mut ranked: Vec<(&PasswordEntry, u32)> = entries.iter()
+            .filter_map(|entry| Self::score(query, &entry.name).map(|score| (entry, score)))
+            .collect();
+        if ranked.is_empty() {
+            ranked = Self::rank(query, entries);
+        }
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+        ranked.into_iter().map(|(entry, _)| entry).collect()
+    }
+
+    fn score_entry(query: &str, entry: &PasswordEntry) -> Option<u32> {
+        // matches on the name outrank matches on secondary fields
+        let secondary = [Some(entry.username.as_str()), entry.url.as_deref()]
+            .into_iter()
+            .flatten()
+            .chain(entry.tags.iter().map(|tag| tag.as_str()))
+            .filter_map(|field| Self::score(query, field))
+            .map(|score| score * 3 / 4)
+            .max();
+        Self::score(query, &entry.name).max(secondary)
+    }
+
+    // every query character must appear in order, consecutive runs and word starts score higher
+    fn subsequence_score(query: &str, candidate: &str) -> Option<u32> {
+        let candidate: Vec<char> = candidate.chars().collect();
+        let mut score: u32 = 0;
+        let mut position = 0;
+        let mut previous: Option<usize> = None;
+        for q in query.chars() {
+            let found = (position..candidate.len()).find(|&i| candidate[i] == q)?;
+            score += 10;
+            if previous.is_some_and(|p| p + 1 == found) {
+                score += 15;
+            }
+            if found == 0 || matches!(candidate[found - 1], '.' | '-' | '_' | '@' | '/' | ' ') {
+                score += 20;
+            }
+            previous = Some(found);
+            position = found + 1;
+        }
+        let gaps = (candidate.len() - query.chars().count()) as u32;
+        Some(score.saturating_sub(gaps).clamp(1, SUBSEQUENCE_MAX_SCORE))
+    }
+}
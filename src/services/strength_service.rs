@@ -0,0 +1,597 @@
+use std::collections::HashMap;
+use chrono::Datelike;
+use lazy_static::lazy_static;
+use regex::Regex;
+use crate::domain::vault::strength_estimate::{Dictionary, Pattern, PatternMatch, StrengthEstimate};
+use crate::utils::constants::*;
+
+const KEYBOARD_ROWS: [&str; 4] = ["1234567890-=", "qwertyuiop[]\\", "asdfghjkl;'", "zxcvbnm,./"];
+const KEYBOARD_SHIFTED_ROWS: [&str; 4] = ["!@#$%^&*()_+", "QWERTYUIOP{}|", "ASDFGHJKL:\"", "ZXCVBNM<>?"];
+// rows are staggered by half a key, so every key has up to six neighbours
+const KEYBOARD_DIRECTIONS: [(isize, isize); 6] = [(0, -1), (0, 1), (-1, 0), (-1, 1), (1, -1), (1, 0)];
+const L33T_TABLE: [(char, &str); 12] = [
+    ('a', "4@"),
+    ('b', "8"),
+    ('c', "({[<"),
+    ('e', "3"),
+    ('g', "69"),
+    ('i', "1!|"),
+    ('l', "1|7"),
+    ('o', "0"),
+    ('s', "$5"),
+    ('t', "+7"),
+    ('x', "%"),
+    ('z', "2"),
+];
+const L33T_MAX_SUBSTITUTIONS: usize = 64;
+const BRUTEFORCE_CARDINALITY: f64 = 10.0;
+const MIN_GUESSES_BEFORE_GROWING_SEQUENCE: f64 = 10000.0;
+
+// log10 of the guess product, log10 of the total guesses and the index of the last match
+type Candidate = Option<(f64, f64, usize)>;
+
+lazy_static! {
+    static ref DICTIONARIES: Vec<(Dictionary, HashMap<String, usize>)> = vec![
+        (Dictionary::Passwords, ranked(COMMON_PASSWORDS.lines())),
+        (Dictionary::English, ranked(ENGLISH_WORDS.lines().chain(WORDS.iter().copied()))),
+        (Dictionary::Names, ranked(COMMON_NAMES.lines())),
+    ];
+    static ref KEYBOARD: HashMap<char, (isize, isize, bool)> = {
+        let mut keys = HashMap::new();
+        for (shifted, rows) in [(false, KEYBOARD_ROWS), (true, KEYBOARD_SHIFTED_ROWS)] {
+            for (row, keys_in_row) in rows.iter().enumerate() {
+                for (col, key) in keys_in_row.chars().enumerate() {
+                    keys.insert(key, (row as isize, col as isize, shifted));
+                }
+            }
+        }
+        keys
+    };
+    static ref KEYBOARD_AVERAGE_DEGREE: f64 = {
+        let positions: Vec<(isize, isize)> = KEYBOARD.values().map(|(row, col, _)| (*row, *col)).collect();
+        let neighbours: usize = positions.iter()
+            .map(|(row, col)| KEYBOARD_DIRECTIONS.iter()
+                .filter(|(dr, dc)| positions.contains(&(row + dr, col + dc)))
+                .count())
+            .sum();
+        neighbours as f64 / positions.len() as f64
+    };
+    static ref YEAR_REGEX: Regex = Regex::new(r"19\d\d|20\d\d").unwrap();
+    static ref DATE_WITH_SEPARATOR_REGEX: Regex = Regex::new(r"^(\d{1,4})([\s/\\_.-])(\d{1,2})([\s/\\_.-])(\d{1,4})$").unwrap();
+}
+
+fn ranked<'a>(words: impl Iterator<Item = &'a str>) -> HashMap<String, usize> {
+    let mut ranks = HashMap::new();
+    for word in words.map(|word| word.trim().to_lowercase()).filter(|word| !word.is_empty()) {
+        let rank = ranks.len() + 1;
+        ranks.entry(word).or_insert(rank);
+    }
+    ranks
+}
+
+fn n_choose_k(n: usize, k: usize) -> f64 {
+    if k > n {
+        return 0.0;
+    }
+    (1..=k).fold(1.0, |acc, i| acc * (n + 1 - i) as f64 / i as f64)
+}
+
+// log10(10^a + 10^b)
+fn log10_add(a: f64, b: f64) -> f64 {
+    let (high, low) = if a > b { (a, b) } else { (b, a) };
+    high + (1.0 + 10f64.powf(low - high)).log10()
+}
+
+pub struct StrengthService;
+
+impl StrengthService {
+
+    // estimates the guesses an attacker needs by splitting the password into the cheapest
+    // sequence of known patterns, in the spirit of zxcvbn
+    pub fn estimate(password: &str, user_inputs: &[&str]) -> StrengthEstimate {
+        let chars: Vec<char> = password.chars().take(STRENGTH_MAX_LEN).collect();
+        let user_inputs = ranked(user_inputs.iter().flat_map(|input| {
+            // email addresses are also tried without their domain
+            std::iter::once(*input).chain(input.split('@').next())
+        }));
+        let (guesses_log10, sequence) = Self::most_guessable(&chars, &user_inputs);
+        let score = match guesses_log10 {
+            g if g < 3.0 => 0,
+            g if g < 6.0 => 1,
+            g if g < 8.0 => 2,
+            g if g < 10.0 => 3,
+            _ => 4,
+        };
+        let (warning, suggestions) = Self::feedback(&sequence, score);
+        StrengthEstimate { guesses_log10, score, sequence, warning, suggestions }
+    }
+
+    fn most_guessable(chars: &[char], user_inputs: &HashMap<String, usize>) -> (f64, Vec<PatternMatch>) {
+        let n = chars.len();
+        if n == 0 {
+            return (0.0, Vec::new());
+        }
+        let mut matches = Self::omnimatch(chars, user_inputs);
+        let log_factorial: Vec<f64> = (0..=n + 1)
+            .scan(0.0, |acc, i| {
+                if i > 0 { *acc += (i as f64).log10(); }
+                Some(*acc)
+            })
+            .collect();
+
+        // optimal[k][l] is the best sequence of l matches covering chars 0..=k
+        let mut optimal: Vec<Vec<Candidate>> = vec![vec![None; n + 2]; n];
+        let update = |optimal: &mut [Vec<Candidate>], matches: &[PatternMatch], k: usize, l: usize, index: usize| {
+            let m = &matches[index];
+            let mut product = m.guesses_log10;
+            if m.i > 0 {
+                product += optimal[m.i - 1][l - 1].unwrap().0;
+            }
+            // longer sequences are penalized so a few long matches beat many short ones
+            let guesses = log10_add(log_factorial[l] + product, MIN_GUESSES_BEFORE_GROWING_SEQUENCE.log10() * (l - 1) as f64);
+            let dominated = optimal[k].iter().take(l + 1).flatten().any(|(_, other, _)| *other <= guesses);
+            if !dominated {
+                optimal[k][l] = Some((product, guesses, index));
+            }
+        };
+
+        for k in 0..n {
+            let ending: Vec<usize> = (0..matches.len()).filter(|&index| matches[index].j == k).collect();
+            for index in ending {
+                let i = matches[index].i;
+                if i == 0 {
+                    update(&mut optimal, &matches, k, 1, index);
+                } else {
+                    for l in 1..=n {
+                        if optimal[i - 1][l].is_some() {
+                            update(&mut optimal, &matches, k, l + 1, index);
+                        }
+                    }
+                }
+            }
+            // bruteforce fills the gaps, but never directly after another bruteforce match
+            for i in 0..=k {
+                matches.push(Self::bruteforce(chars, i, k));
+                let index = matches.len() - 1;
+                if i == 0 {
+                    update(&mut optimal, &matches, k, 1, index);
+                    continue;
+                }
+                for l in 1..=n {
+                    let Some((_, _, previous)) = optimal[i - 1][l] else { continue };
+                    if matches[previous].pattern != Pattern::Bruteforce {
+                        update(&mut optimal, &matches, k, l + 1, index);
+                    }
+                }
+            }
+        }
+
+        let (mut l, (_, guesses, _)) = optimal[n - 1].iter().enumerate()
+            .filter_map(|(l, entry)| entry.map(|entry| (l, entry)))
+            .min_by(|a, b| a.1.1.total_cmp(&b.1.1))
+            .unwrap();
+        let mut sequence = Vec::new();
+        let mut k = n - 1;
+        loop {
+            let (_, _, index) = optimal[k][l].unwrap();
+            let m = matches[index].clone();
+            let start = m.i;
+            sequence.push(m);
+            if start == 0 {
+                break;
+            }
+            k = start - 1;
+            l -= 1;
+        }
+        sequence.reverse();
+        (guesses, sequence)
+    }
+
+    fn omnimatch(chars: &[char], user_inputs: &HashMap<String, usize>) -> Vec<PatternMatch> {
+        let mut matches = Vec::new();
+        matches.extend(Self::dictionary_matches(chars, user_inputs));
+        matches.extend(Self::reversed_dictionary_matches(chars, user_inputs));
+        matches.extend(Self::l33t_matches(chars, user_inputs));
+        matches.extend(Self::spatial_matches(chars));
+        matches.extend(Self::repeat_matches(chars, user_inputs));
+        matches.extend(Self::sequence_matches(chars));
+        matches.extend(Self::year_matches(chars));
+        matches.extend(Self::date_matches(chars));
+        matches
+    }
+
+    fn new_match(chars: &[char], i: usize, j: usize, pattern: Pattern, guesses: f64) -> PatternMatch {
+        let min_guesses: f64 = if i == j { 10.0 } else { 50.0 };
+        PatternMatch {
+            i,
+            j,
+            token: chars[i..=j].iter().collect(),
+            pattern,
+            guesses_log10: guesses.max(min_guesses).log10(),
+        }
+    }
+
+    fn bruteforce(chars: &[char], i: usize, j: usize) -> PatternMatch {
+        let len = j - i + 1;
+        let min_guesses: f64 = if len == 1 { 11.0 } else { 51.0 };
+        PatternMatch {
+            i,
+            j,
+            token: chars[i..=j].iter().collect(),
+            pattern: Pattern::Bruteforce,
+            guesses_log10: (len as f64 * BRUTEFORCE_CARDINALITY.log10()).max(min_guesses.log10()),
+        }
+    }
+
+    fn dictionary_matches(chars: &[char], user_inputs: &HashMap<String, usize>) -> Vec<PatternMatch> {
+        let lower: Vec<char> = chars.iter().flat_map(|c| c.to_lowercase()).collect();
+        if lower.len() != chars.len() {
+            return Vec::new();
+        }
+        let dictionaries = DICTIONARIES.iter()
+            .map(|(dictionary, words)| (*dictionary, words))
+            .chain(std::iter::once((Dictionary::UserInputs, user_inputs)));
+        let mut matches = Vec::new();
+        for (dictionary, words) in dictionaries {
+            for i in 0..chars.len() {
+                for j in i..chars.len() {
+                    let word: String = lower[i..=j].iter().collect();
+                    let Some(&rank) = words.get(&word) else { continue };
+                    let token: String = chars[i..=j].iter().collect();
+                    let guesses = rank as f64 * Self::uppercase_variations(&token);
+                    let pattern = Pattern::Dictionary { dictionary, word, rank, reversed: false, l33t: false };
+                    matches.push(Self::new_match(chars, i, j, pattern, guesses));
+                }
+            }
+        }
+        matches
+    }
+
+    fn reversed_dictionary_matches(chars: &[char], user_inputs: &HashMap<String, usize>) -> Vec<PatternMatch> {
+        let reversed: Vec<char> = chars.iter().rev().copied().collect();
+        let n = chars.len();
+        Self::dictionary_matches(&reversed, user_inputs).into_iter()
+            .filter(|m| m.token.chars().count() > 1)
+            .filter_map(|m| {
+                let Pattern::Dictionary { dictionary, word, rank, .. } = m.pattern else { return None };
+                let (i, j) = (n - 1 - m.j, n - 1 - m.i);
+                let guesses = 10f64.powf(m.guesses_log10) * 2.0;
+                let pattern = Pattern::Dictionary { dictionary, word, rank, reversed: true, l33t: false };
+                Some(Self::new_match(chars, i, j, pattern, guesses))
+            })
+            .collect()
+    }
+
+    fn l33t_matches(chars: &[char], user_inputs: &HashMap<String, usize>) -> Vec<PatternMatch> {
+        // every combination of letters the substituted characters could stand for
+        let mut substitutions: Vec<HashMap<char, char>> = vec![HashMap::new()];
+        for c in chars.iter().copied() {
+            let letters: Vec<char> = L33T_TABLE.iter()
+                .filter(|(_, subs)| subs.contains(c))
+                .map(|(letter, _)| *letter)
+                .collect();
+            if letters.is_empty() || substitutions[0].contains_key(&c) {
+                continue;
+            }
+            substitutions = substitutions.into_iter()
+                .flat_map(|map| letters.iter().map(move |letter| {
+                    let mut map = map.clone();
+                    map.insert(c, *letter);
+                    map
+                }))
+                .take(L33T_MAX_SUBSTITUTIONS)
+                .collect();
+        }
+        if substitutions[0].is_empty() {
+            return Vec::new();
+        }
+
+        let mut matches = Vec::new();
+        for map in substitutions {
+            let translated: Vec<char> = chars.iter().map(|c| *map.get(c).unwrap_or(c)).collect();
+            for m in Self::dictionary_matches(&translated, user_inputs) {
+                let token = &chars[m.i..=m.j];
+                let used: Vec<(char, char)> = map.iter()
+                    .filter(|(sub, _)| token.contains(sub))
+                    .map(|(sub, letter)| (*sub, *letter))
+                    .collect();
+                if used.is_empty() || token.len() < 2 {
+                    continue;
+                }
+                let Pattern::Dictionary { dictionary, word, rank, .. } = m.pattern else { continue };
+                let mut variations = 1.0;
+                for (sub, letter) in used {
+                    let subbed = token.iter().filter(|c| **c == sub).count();
+                    let unsubbed = token.iter().filter(|c| c.to_ascii_lowercase() == letter).count();
+                    variations *= if subbed == 0 || unsubbed == 0 {
+                        2.0
+                    } else {
+                        (1..=subbed.min(unsubbed)).map(|i| n_choose_k(subbed + unsubbed, i)).sum()
+                    };
+                }
+                let guesses = 10f64.powf(m.guesses_log10) * variations;
+                let pattern = Pattern::Dictionary { dictionary, word, rank, reversed: false, l33t: true };
+                matches.push(Self::new_match(chars, m.i, m.j, pattern, guesses));
+            }
+        }
+        matches
+    }
+
+    fn uppercase_variations(token: &str) -> f64 {
+        let upper = token.chars().filter(|c| c.is_uppercase()).count();
+        let lower = token.chars().filter(|c| c.is_lowercase()).count();
+        if upper == 0 {
+            return 1.0;
+        }
+        let first_upper = token.chars().next().is_some_and(|c| c.is_uppercase());
+        let last_upper = token.chars().last().is_some_and(|c| c.is_uppercase());
+        // the common capitalizations only double the guesses
+        if lower == 0 || (upper == 1 && (first_upper || last_upper)) {
+            return 2.0;
+        }
+        (1..=upper.min(lower)).map(|i| n_choose_k(upper + lower, i)).sum()
+    }
+
+    fn keyboard_direction(from: char, to: char) -> Option<usize> {
+        let (from_row, from_col, _) = KEYBOARD.get(&from)?;
+        let (to_row, to_col, _) = KEYBOARD.get(&to)?;
+        KEYBOARD_DIRECTIONS.iter().position(|(dr, dc)| (from_row + dr, from_col + dc) == (*to_row, *to_col))
+    }
+
+    fn spatial_matches(chars: &[char]) -> Vec<PatternMatch> {
+        let mut matches = Vec::new();
+        let mut i = 0;
+        while i + 2 < chars.len() {
+            let mut j = i;
+            let mut turns = 0;
+            let mut previous_direction = None;
+            while j + 1 < chars.len() {
+                let Some(direction) = Self::keyboard_direction(chars[j], chars[j + 1]) else { break };
+                if previous_direction != Some(direction) {
+                    turns += 1;
+                    previous_direction = Some(direction);
+                }
+                j += 1;
+            }
+            if j - i >= 2 {
+                let shifted = chars[i..=j].iter().filter(|c| KEYBOARD.get(c).is_some_and(|key| key.2)).count();
+                let guesses = Self::spatial_guesses(j - i + 1, turns, shifted);
+                matches.push(Self::new_match(chars, i, j, Pattern::Spatial { turns, shifted }, guesses));
+                i = j;
+            } else {
+                i += 1;
+            }
+        }
+        matches
+    }
+
+    fn spatial_guesses(len: usize, turns: usize, shifted: usize) -> f64 {
+        let starts = KEYBOARD.len() as f64;
+        let degree = *KEYBOARD_AVERAGE_DEGREE;
+        let mut guesses = 0.0;
+        for i in 2..=len {
+            for j in 1..=turns.min(i - 1) {
+                guesses += n_choose_k(i - 1, j - 1) * starts * degree.powi(j as i32);
+            }
+        }
+        let unshifted = len - shifted;
+        if shifted > 0 {
+            guesses *= if unshifted == 0 {
+                2.0
+            } else {
+                (1..=shifted.min(unshifted)).map(|i| n_choose_k(shifted + unshifted, i)).sum()
+            };
+        }
+        guesses
+    }
+
+    fn repeat_matches(chars: &[char], user_inputs: &HashMap<String, usize>) -> Vec<PatternMatch> {
+        let mut matches = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            // longest repeated span starting at i, preferring the shortest base
+            let mut best: Option<(usize, usize)> = None;
+            for base_len in 1..=(chars.len() - i) / 2 {
+                let base = &chars[i..i + base_len];
+                let count = chars[i..].chunks(base_len).take_while(|chunk| *chunk == base).count();
+                if count >= 2 && best.is_none_or(|(len, reps)| base_len * count > len * reps) {
+                    best = Some((base_len, count));
+                }
+            }
+            let Some((base_len, count)) = best else {
+                i += 1;
+                continue;
+            };
+            let j = i + base_len * count - 1;
+            let base: String = chars[i..i + base_len].iter().collect();
+            let (base_guesses, _) = Self::most_guessable(&chars[i..i + base_len], user_inputs);
+            let guesses = 10f64.powf(base_guesses) * count as f64;
+            matches.push(Self::new_match(chars, i, j, Pattern::Repeat { base, count }, guesses));
+            i = j + 1;
+        }
+        matches
+    }
+
+    fn sequence_matches(chars: &[char]) -> Vec<PatternMatch> {
+        let mut matches = Vec::new();
+        let mut i = 0;
+        while i + 1 < chars.len() {
+            let delta = chars[i + 1] as i64 - chars[i] as i64;
+            let mut j = i + 1;
+            while j + 1 < chars.len() && chars[j + 1] as i64 - chars[j] as i64 == delta {
+                j += 1;
+            }
+            if j - i >= 2 && delta != 0 && delta.abs() <= 5 {
+                let first = chars[i];
+                let mut base = if "aAzZ019".contains(first) {
+                    4.0
+                } else if first.is_ascii_digit() {
+                    10.0
+                } else {
+                    26.0
+                };
+                if delta < 0 {
+                    base *= 2.0;
+                }
+                let pattern = Pattern::Sequence { ascending: delta > 0 };
+                matches.push(Self::new_match(chars, i, j, pattern, base * (j - i + 1) as f64));
+            }
+            i = j;
+        }
+        matches
+    }
+
+    fn reference_year() -> i32 {
+        chrono::Utc::now().year()
+    }
+
+    fn year_guesses(year: i32) -> f64 {
+        (year - Self::reference_year()).abs().max(MIN_YEAR_SPACE) as f64
+    }
+
+    fn year_matches(chars: &[char]) -> Vec<PatternMatch> {
+        let text: String = chars.iter().collect();
+        YEAR_REGEX.find_iter(&text)
+            .map(|found| {
+                let i = text[..found.start()].chars().count();
+                let year = found.as_str().parse::<i32>().unwrap();
+                Self::new_match(chars, i, i + 3, Pattern::Year, Self::year_guesses(year))
+            })
+            .collect()
+    }
+
+    fn date_matches(chars: &[char]) -> Vec<PatternMatch> {
+        let mut matches = Vec::new();
+        for i in 0..chars.len() {
+            for j in i + 3..chars.len().min(i + 10) {
+                let token: String = chars[i..=j].iter().collect();
+                let parsed = if token.chars().all(|c| c.is_ascii_digit()) {
+                    if token.len() > 8 {
+                        continue;
+                    }
+                    Self::split_date(&token).map(|year| (year, false))
+                } else {
+                    let Some(captures) = DATE_WITH_SEPARATOR_REGEX.captures(&token) else { continue };
+                    if captures[2] != captures[4] {
+                        continue;
+                    }
+                    Self::parse_date([&captures[1], &captures[3], &captures[5]]).map(|year| (year, true))
+                };
+                if let Some((year, separator)) = parsed {
+                    let mut guesses = 365.0 * Self::year_guesses(year);
+                    if separator {
+                        guesses *= 4.0;
+                    }
+                    matches.push(Self::new_match(chars, i, j, Pattern::Date { year, separator }, guesses));
+                }
+            }
+        }
+        matches
+    }
+
+    // tries every way of splitting a run of digits into three date parts
+    fn split_date(digits: &str) -> Option<i32> {
+        let len = digits.len();
+        let mut best: Option<i32> = None;
+        for first in 1..len.min(5) {
+            for second in 1..(len - first).min(3) {
+                let parts = [&digits[..first], &digits[first..first + second], &digits[first + second..]];
+                if parts[2].len() > 4 {
+                    continue;
+                }
+                if let Some(year) = Self::parse_date(parts) {
+                    let distance = |year: i32| (year - Self::reference_year()).abs();
+                    if best.is_none_or(|best| distance(year) < distance(best)) {
+                        best = Some(year);
+                    }
+                }
+            }
+        }
+        best
+    }
+
+    // the year either leads or trails, day and month may come in either order
+    fn parse_date(parts: [&str; 3]) -> Option<i32> {
+        let numbers: Vec<i32> = parts.iter().map(|part| part.parse::<i32>()).collect::<Result<_, _>>().ok()?;
+        let candidates = [
+            (parts[0].len(), numbers[0], numbers[1], numbers[2]),
+            (parts[2].len(), numbers[2], numbers[0], numbers[1]),
+        ];
+        candidates.into_iter()
+            .filter(|(year_len, _, a, b)| {
+                (*year_len == 2 || *year_len == 4)
+                    && ((1..=12).contains(a) && (1..=31).contains(b) || (1..=12).contains(b) && (1..=31).contains(a))
+            })
+            .map(|(year_len, year, _, _)| match year_len {
+                2 if year > 50 => 1900 + year,
+                2 => 2000 + year,
+                _ => year,
+            })
+            .find(|year| (1000..=2050).contains(year))
+    }
+
+    fn feedback(sequence: &[PatternMatch], score: u8) -> (Option<String>, Vec<String>) {
+        if sequence.is_empty() {
+            return (None, vec![
+                "Use a few words, avoid common phrases".to_string(),
+                "No need for symbols, digits, or uppercase letters".to_string(),
+            ]);
+        }
+        if score > 2 {
+            return (None, Vec::new());
+        }
+        let longest = sequence.iter().max_by_key(|m| m.token.chars().count()).unwrap();
+        let single = sequence.len() == 1;
+        let mut suggestions = vec!["Add another word or two, uncommon words are better".to_string()];
+        let warning = match &longest.pattern {
+            Pattern::Dictionary { dictionary, rank, reversed, l33t, .. } => {
+                let token = &longest.token;
+                let first_upper = token.chars().next().is_some_and(|c| c.is_uppercase());
+                if first_upper && token.chars().skip(1).all(|c| !c.is_uppercase()) {
+                    suggestions.push("Capitalization doesn't help very much".to_string());
+                } else if token.chars().any(|c| c.is_alphabetic()) && !token.chars().any(|c| c.is_lowercase()) {
+                    suggestions.push("All-uppercase is almost as easy to guess as all-lowercase".to_string());
+                }
+                if *reversed {
+                    suggestions.push("Reversed words aren't much harder to guess".to_string());
+                }
+                if *l33t {
+                    suggestions.push("Predictable substitutions like '@' instead of 'a' don't help very much".to_string());
+                }
+                match dictionary {
+                    Dictionary::Passwords if single && *rank <= 10 => Some("This is a top-10 common password"),
+                    Dictionary::Passwords if single && *rank <= 100 => Some("This is a top-100 common password"),
+                    Dictionary::Passwords if single => Some("This is a very common password"),
+                    Dictionary::Passwords => Some("This is similar to a commonly used password"),
+                    Dictionary::English if single => Some("A word by itself is easy to guess"),
+                    Dictionary::English => None,
+                    Dictionary::Names if single => Some("Names and surnames by themselves are easy to guess"),
+                    Dictionary::Names => Some("Common names and surnames are easy to guess"),
+                    Dictionary::UserInputs => Some("This is similar to the entry name or username"),
+                }
+            }
+            Pattern::Spatial { turns, .. } => {
+                suggestions.push("Use a longer keyboard pattern with more turns".to_string());
+                Some(if *turns == 1 { "Straight rows of keys are easy to guess" } else { "Short keyboard patterns are easy to guess" })
+            }
+            Pattern::Repeat { base, .. } => {
+                suggestions.push("Avoid repeated words and characters".to_string());
+                Some(if base.chars().count() == 1 {
+                    "Repeats like \"aaa\" are easy to guess"
+                } else {
+                    "Repeats like \"abcabcabc\" are only slightly harder to guess than \"abc\""
+                })
+            }
+            Pattern::Sequence { .. } => {
+                suggestions.push("Avoid sequences".to_string());
+                Some("Sequences like abc or 6543 are easy to guess")
+            }
+            Pattern::Date { .. } | Pattern::Year => {
+                suggestions.push("Avoid dates and years that are associated with you".to_string());
+                Some(if longest.pattern == Pattern::Year { "Recent years are easy to guess" } else { "Dates are often easy to guess" })
+            }
+            Pattern::Bruteforce => None,
+        };
+        (warning.map(String::from), suggestions)
+    }
+}
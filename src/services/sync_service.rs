@@ -0,0 +1,216 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use chrono::{DateTime, Utc};
+use secrecy::SecretBox;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+use crate::domain::app::error::AppError;
+use crate::domain::vault::password_entry::PasswordEntry;
+use crate::domain::vault::sync_report::{MergeConflict, MergeReport, Resolution, SyncReport};
+use crate::domain::vault::vault::Vault;
+use crate::repository::vault::vault_crypto::VaultCrypto;
+use crate::repository::vault::vault_git::VaultGit;
+use crate::repository::vault::vault_manager::VaultManager;
+use crate::repository::vault::vault_storage::VaultStorage;
+use crate::utils::constants::SYNC_DEFAULT_REMOTE;
+
+pub struct SyncService;
+
+impl SyncService {
+
+    // commits the vault file, then pulls and pushes through the branch's remote,
+    // merging diverged histories entry by entry
+    pub fn sync(
+        path: &Path,
+        password: &SecretBox<String>,
+        resolve: impl FnMut(&MergeConflict) -> Result<Resolution, AppError>,
+    ) -> Result<SyncReport, AppError> {
+        let path = fs::canonicalize(path)
+            .map_err(|e| AppError::Other(format!("Failed to find vault file: {}", e)))?;
+        let root = VaultGit::root(&path).map_err(AppError::from)?;
+        let root = fs::canonicalize(&root).unwrap_or(root);
+        let file = path.strip_prefix(&root)
+            .map_err(|_| AppError::Other("The vault is not inside the git repository".to_string()))?
+            .to_string_lossy()
+            .replace('\\', "/");
+        let git = |args: &[&str]| VaultGit::run(&root, args).map_err(AppError::from);
+        let mut report = SyncReport::default();
+
+        if !git(&["status", "--porcelain", "--", &file])?.is_empty() {
+            git(&["add", "--", &file])?;
+            git(&["commit", "-m", &format!("Update {}", file), "--", &file])?;
+            report.committed = true;
+        }
+
+        let branch = git(&["symbolic-ref", "--short", "HEAD"])?;
+        let remote = VaultGit::run(&root, &["config", &format!("branch.{}.remote", branch)])
+            .unwrap_or_else(|_| SYNC_DEFAULT_REMOTE.to_string());
+        git(&["fetch", &remote])?;
+        let upstream = format!("refs/remotes/{}/{}", remote, branch);
+        let tracked = VaultGit::succeeds(&root, &["rev-parse", "--verify", "--quiet", &upstream]);
+        let is_ancestor = |a: &str, b: &str| VaultGit::succeeds(&root, &["merge-base", "--is-ancestor", a, b]);
+
+        if tracked && !is_ancestor(&upstream, "HEAD") {
+            if is_ancestor("HEAD", &upstream) {
+                git(&["merge", "--ff-only", &upstream])?;
+                report.fast_forwarded = true;
+            } else {
+                report.merge = Some(Self::merge_diverged(&root, &file, &path, &upstream, password, resolve)?);
+            }
+        }
+        if !tracked || !is_ancestor("HEAD", &upstream) {
+            git(&["push", "-u", &remote, &branch])?;
+            report.pushed = true;
+        }
+        Ok(report)
+    }
+
+    fn merge_diverged(
+        root: &Path,
+        file: &str,
+        path: &Path,
+        upstream: &str,
+        password: &SecretBox<String>,
+        resolve: impl FnMut(&MergeConflict) -> Result<Resolution, AppError>,
+    ) -> Result<MergeReport, AppError> {
+        let git = |args: &[&str]| VaultGit::run(root, args).map_err(AppError::from);
+        let decode = |data: Option<Vec<u8>>, side: &str| -> Result<Vault, AppError> {
+            let Some(data) = data else {
+                return Ok(Vault::new(Vec::new()));
+            };
+            let mut vault = VaultManager::decode(&data, password)
+                .and_then(|mut vault| VaultCrypto::unseal_all(&mut vault).map(|_| vault))
+                .map_err(|e| AppError::Other(format!("Failed to decrypt the {} vault: {}", side, e)))?;
+            vault.assign_ids();
+            Ok(vault)
+        };
+        let base_rev = git(&["merge-base", "HEAD", upstream])?;
+        let remote = VaultGit::show(root, upstream, file).map_err(AppError::from)?
+            .ok_or(AppError::Other("The vault was deleted on the remote".to_string()))?;
+        let local = fs::read(path).map_err(|e| AppError::Other(format!("Failed to read vault file: {}", e)))?;
+        let base = decode(VaultGit::show(root, &base_rev, file).map_err(AppError::from)?, "common")?;
+        let local = decode(Some(local), "local")?;
+        let remote = decode(Some(remote), "remote")?;
+        // conflicts are resolved before git is touched, so cancelling leaves the repository as it was
+        let (merged, report) = Self::merge(base, local, remote, resolve)?;
+
+        // git merges the rest of the tree, the vault file is replaced by the entry-level merge
+        let merge_error = VaultGit::run(root, &["merge", "--no-ff", "--no-commit", upstream]).err();
+        if !VaultGit::succeeds(root, &["rev-parse", "--verify", "--quiet", "MERGE_HEAD"]) {
+            return Err(merge_error.map(AppError::from).unwrap_or(AppError::Other("Failed to start merge".to_string())));
+        }
+        // git keeps the previous version, a backup file would only end up in the working tree
+        let result = VaultManager::encode(&merged, password)
+            .and_then(|data| VaultStorage::write(path, &data, 0))
+            .map_err(AppError::from)
+            .and_then(|_| git(&["add", "--", file]))
+            .and_then(|_| match git(&["diff", "--name-only", "--diff-filter=U"])?.as_str() {
+                "" => Ok(()),
+                files => Err(AppError::Other(format!("Resolve these files with git first: {}", files.replace('\n', ", ")))),
+            })
+            .and_then(|_| git(&["commit", "-m", &format!("Merge {}", file)]));
+        if let Err(err) = result {
+            let _ = VaultGit::run(root, &["merge", "--abort"]);
+            return Err(err);
+        }
+        Ok(report)
+    }
+
+    pub fn merge(
+        mut base: Vault,
+        mut local: Vault,
+        mut remote: Vault,
+        mut resolve: impl FnMut(&MergeConflict) -> Result<Resolution, AppError>,
+    ) -> Result<(Vault, MergeReport), AppError> {
+        // entries sharing an id would overwrite each other below
+        for vault in [&mut base, &mut local, &mut remote] {
+            vault.assign_ids();
+        }
+        // copying is bookkeeping rather than an edit, so it merges as the latest copy
+        let mut copied: HashMap<String, DateTime<Utc>> = HashMap::new();
+        for entry in base.entries.iter_mut().chain(local.entries.iter_mut()).chain(remote.entries.iter_mut()) {
+            if let Some(date) = entry.last_copied_at.take() {
+                let latest = copied.entry(entry.id.clone()).or_insert(date);
+                *latest = (*latest).max(date);
+            }
+        }
+        // local order first, then entries that only exist elsewhere
+        let mut order: Vec<String> = Vec::new();
+        for entry in local.entries.iter().chain(remote.entries.iter()).chain(base.entries.iter()) {
+            if !order.contains(&entry.id) {
+                order.push(entry.id.clone());
+            }
+        }
+        let by_id = |vault: &mut Vault| -> HashMap<String, PasswordEntry> {
+            std::mem::take(&mut vault.entries).into_iter().map(|e| (e.id.clone(), e)).collect()
+        };
+        let (mut base, mut local, mut remote) = (by_id(&mut base), by_id(&mut local), by_id(&mut remote));
+
+        let mut report = MergeReport::default();
+        let mut merged = Vec::new();
+        for id in order {
+            let same = |a: &PasswordEntry, b: &PasswordEntry| Self::fingerprint(a) == Self::fingerprint(b);
+            match (base.remove(&id), local.remove(&id), remote.remove(&id)) {
+                (_, Some(l), Some(r)) if same(&l, &r) => merged.push(l),
+                (Some(b), Some(l), Some(r)) if same(&b, &r) => merged.push(l),
+                (Some(b), Some(l), Some(r)) if same(&b, &l) => {
+                    report.updated += 1;
+                    merged.push(r);
+                }
+                (Some(b), Some(l), None) if same(&b, &l) => report.deleted += 1,
+                (Some(b), None, Some(r)) if same(&b, &r) => {}
+                (None, Some(l), None) => merged.push(l),
+                (None, None, Some(r)) => {
+                    report.added += 1;
+                    merged.push(r);
+                }
+                (_, None, None) => {}
+                (_, l, r) => {
+                    report.conflicts += 1;
+                    match resolve(&MergeConflict { local: l.as_ref(), remote: r.as_ref() })? {
+                        Resolution::Local => merged.extend(l),
+                        Resolution::Remote => merged.extend(r),
+                        Resolution::Both => {
+                            let both = l.is_some();
+                            merged.extend(l);
+                            merged.extend(r.map(|mut r| {
+                                if both {
+                                    r.id = PasswordEntry::new_id();
+                                }
+                                r
+                            }));
+                        }
+                    }
+                }
+            }
+        }
+
+        for entry in merged.iter_mut() {
+            entry.last_copied_at = copied.get(&entry.id).copied();
+        }
+        // independently added entries can share a name
+        let mut seen: HashSet<String> = HashSet::new();
+        let names: HashSet<String> = merged.iter().map(|e| e.name.clone()).collect();
+        for entry in merged.iter_mut() {
+            if seen.insert(entry.name.clone()) {
+                continue;
+            }
+            let mut suffix = 2;
+            while names.contains(&format!("{}_{}", entry.name, suffix)) || seen.contains(&format!("{}_{}", entry.name, suffix)) {
+                suffix += 1;
+            }
+            entry.name = format!("{}_{}", entry.name, suffix);
+            seen.insert(entry.name.clone());
+            report.renamed.push(entry.name.clone());
+        }
+        Ok((Vault::new(merged), report))
+    }
+
+    fn fingerprint(entry: &PasswordEntry) -> [u8; 32] {
+        let mut data = serde_json::to_vec(entry).unwrap_or_default();
+        let digest = Sha256::digest(&data).into();
+        data.zeroize();
+        digest
+    }
+}
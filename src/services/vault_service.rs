@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+use std::mem;
 use chrono::{Utc};
 use secrecy::{ExposeSecret, SecretBox};
 use zeroize::Zeroize;
@@ -5,10 +7,26 @@ use crate::domain::app::error::AppError;
 use crate::domain::app::session::AppSession;
 use crate::domain::app::state::AppState;
 use crate::domain::cli::field::Field;
-use crate::utils::constants::SESSION_TTL;
+use crate::domain::cli::audit_params::AuditParams;
+use crate::domain::cli::export_params::ExportFormat;
+use crate::domain::cli::import_params::DuplicateStrategy;
+use crate::services::config_service::ConfigService;
+use crate::domain::vault::custom_field::CustomField;
+use crate::domain::vault::import_report::ImportReport;
 use crate::domain::vault::password_entry::{PasswordEntry, PasswordEntryDebug};
 use crate::domain::vault::vault::Vault;
+use crate::domain::vault::vault_error::VaultError;
+use crate::repository::vault::vault_crypto::VaultCrypto;
 use crate::repository::vault::vault_manager_trait::VaultManagerTrait;
+use crate::domain::vault::audit_report::AuditReport;
+use crate::domain::vault::sync_report::{MergeConflict, Resolution, SyncReport};
+use crate::services::audit_service::AuditService;
+use crate::services::breach_service::{BreachList, BreachService};
+use crate::services::export_service::ExportService;
+use crate::services::otp_service::OtpService;
+use crate::services::search_service::SearchService;
+use crate::services::sync_service::SyncService;
+use crate::utils::validation::{validate_arg, validate_password};
 
 pub struct VaultService<V: VaultManagerTrait> {
     vault: V,
@@ -20,26 +38,25 @@ impl<V: VaultManagerTrait> VaultService<V> {
         Self { vault }
     }
 
-    pub fn create(&self, name: &str, secret: &SecretBox<String>) {
-        self.vault.create(name, secret).expect("Failed to create vault");
+    pub fn create(&self, name: &str, secret: &SecretBox<String>) -> Result<(), AppError> {
+        Ok(self.vault.create(name, secret)?)
     }
 
     pub fn open(&self, name: &str, secret: &SecretBox<String>, state: &mut AppState) -> Result<(), AppError>{
-        let result = self.vault.load(name, secret);
-        match result {
-            Ok(vault) => {
-                state.session = Some(
-                    AppSession {
-                        vault,
-                        name: String::from(name),
-                        secret: SecretBox::new(Box::from(secret.expose_secret().clone())),
-                        expires_at: Utc::now() + SESSION_TTL
-                    }
-                );
-                Ok(())
+        let mut vault = self.vault.load(name, secret)?;
+        vault.assign_ids();
+        let config = ConfigService::current();
+        let max_expires_at = Utc::now() + config.session_lifetime();
+        state.session = Some(
+            AppSession {
+                vault,
+                name: String::from(name),
+                secret: SecretBox::new(Box::from(secret.expose_secret().clone())),
+                expires_at: (Utc::now() + config.session_ttl()).min(max_expires_at),
+                max_expires_at,
             }
-            Err(e) => Err(AppError::Other(format!("Failed to load vault: {}", e)))
-        }
+        );
+        Ok(())
     }
 
     pub fn close(&self, state: &mut AppState) {
@@ -49,11 +66,19 @@ impl<V: VaultManagerTrait> VaultService<V> {
         }
     }
 
+    // exposed secrets are decrypted into copies, the session keeps them sealed
     pub fn show(&self, entry: Option<String>, expose: bool, state: &mut AppState) -> Result<String, AppError> {
-        let entries = state.session.as_mut().unwrap().vault.entries.iter();
-        let filtered: Vec<&PasswordEntry> = match entry.clone() {
-            Some(s) => entries.filter(|entry| entry.name == s).collect(),
-            None => entries.collect(),
+        let vault = &state.session.as_ref().unwrap().vault;
+        let indexes: Vec<usize> = (0..vault.entries.len())
+            .filter(|index| entry.as_ref().is_none_or(|name| vault.entries[*index].name == *name))
+            .collect();
+        let exposed = match expose {
+            true => indexes.iter().map(|index| VaultCrypto::unsealed(vault, *index)).collect::<Result<Vec<_>, _>>()?,
+            false => Vec::new(),
+        };
+        let filtered: Vec<&PasswordEntry> = match expose {
+            true => exposed.iter().collect(),
+            false => indexes.iter().map(|index| &vault.entries[*index]).collect(),
         };
         if entry.is_some() && filtered.is_empty() {
             return Err(AppError::Other("Service not found".to_string()));
@@ -66,45 +91,431 @@ impl<V: VaultManagerTrait> VaultService<V> {
         )
     }
 
-    pub fn delete(&self, state: &mut AppState) {
+    pub fn search(&self, query: &str, state: &mut AppState) -> String {
+        let entries = &state.session.as_ref().unwrap().vault.entries;
+        let ranked = SearchService::rank(query, entries);
+        if ranked.is_empty() {
+            return "No matching entries".to_string();
+        }
+        ranked.iter()
+            .map(|(entry, _)| match &entry.url {
+                Some(url) => format!("{} ({}) {}", entry.name, entry.username, url),
+                None => format!("{} ({})", entry.name, entry.username),
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    // more than one name when the query is ambiguous
+    pub fn find_entries(&self, query: &str, state: &mut AppState) -> Vec<String> {
+        let entries = &state.session.as_ref().unwrap().vault.entries;
+        SearchService::resolve(query, entries)
+            .iter()
+            .map(|entry| entry.name.clone())
+            .collect()
+    }
+
+    pub fn delete(&self, state: &mut AppState) -> Result<(), AppError> {
         let name = state.session.as_mut().unwrap().name.clone();
-        self.vault.delete(&name).expect("Failed to delete vault");
+        Ok(self.vault.delete(&name)?)
+    }
+
+    pub fn list(&self) -> Result<String, AppError> {
+        let vaults = self.vault.list()?;
+        if vaults.is_empty() {
+            return Ok("No vaults found".to_string());
+        }
+        let width = vaults.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        Ok(
+            vaults.iter()
+                .map(|(name, location)| format!("{:<width$}  {}", name, location, width = width))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+
+    // remembers a vault stored outside the default directory under its name
+    pub fn register(&self, name: &str, location: &str) -> Result<(), AppError> {
+        Ok(self.vault.register(name, location)?)
+    }
+
+    pub fn unregister(&self, name: &str) -> Result<(), AppError> {
+        Ok(self.vault.unregister(name)?)
+    }
+
+    pub fn relocate(&self, name: &str, location: &str) -> Result<String, AppError> {
+        Ok(self.vault.relocate(name, location)?)
+    }
+
+    pub fn rename(&self, name: &str, new_name: &str, state: &mut AppState) -> Result<(), AppError> {
+        self.vault.rename(name, new_name)?;
+        if let Some(session) = state.session.as_mut().filter(|s| s.name == name) {
+            session.name = new_name.to_string();
+        }
+        Ok(())
     }
 
-    pub fn list(&self) -> String {
-        let vaults = self.vault.list().expect("Failed to list vaults");
-        vaults.join("\n")
+    pub fn add_entry(&self, entry: &str, username: &str, password: &str, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        let mut new_entry = PasswordEntry::new(entry, username, password);
+        let now = Utc::now();
+        new_entry.created_at = Some(now);
+        new_entry.updated_at = Some(now);
+        new_entry.password_changed_at = Some(now);
+        session.vault.entries.push(new_entry);
+        // the session keeps matching the stored vault when the save fails
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries.pop();
+            return Err(err.into());
+        }
+        Ok(())
     }
 
-    pub fn add_entry(&self, entry: &str, username: &str, password: &str, state: &mut AppState) {
+    // new credentials for an existing entry, which keeps its id, other fields and history;
+    // they are checked before anything changes, so a bad value leaves the entry as it was
+    pub fn replace_entry(&self, entry: &str, username: &str, password: &str, state: &mut AppState) -> Result<(), AppError> {
+        validate_arg(username, "username")?;
+        validate_password(password)?;
         let session = state.session.as_mut().unwrap();
-        let new_entry = PasswordEntry {
-            name: String::from(entry),
-            username: String::from(username),
-            password: SecretBox::new(Box::from(String::from(password))),
-        };
-        session.vault.entries.append(&mut vec![new_entry]);
-        self.vault.save(&session.name, &session.secret, &session.vault).expect("Failed to save vault");
+        let index = Self::get_entry_index(entry, &session.vault)?;
+        let mut replacement = VaultCrypto::unsealed(&session.vault, index)?;
+        replacement.record_version();
+        replacement.username = String::from(username);
+        replacement.password = SecretBox::new(Box::from(String::from(password)));
+        let now = Utc::now();
+        replacement.updated_at = Some(now);
+        replacement.password_changed_at = Some(now);
+        let previous = mem::replace(&mut session.vault.entries[index], replacement);
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries[index] = previous;
+            return Err(err.into());
+        }
+        Ok(())
     }
 
     pub fn update_entry(&self, entry: &str, field: &Field, value: &str, state: &mut AppState) -> Result<(), AppError> {
         let session = state.session.as_mut().unwrap();
-        let entry = Self::get_vault_entry(entry, &mut session.vault)?;
+        // put back when the save fails, so the session keeps matching the stored vault
+        let index = Self::get_entry_index(entry, &session.vault)?;
+        let previous = VaultCrypto::copy(&session.vault.entries[index]);
+        // changing credentials records them in the history, which is sealed with them
+        let entry = match field {
+            Field::Url | Field::Notes | Field::Tags => Self::get_vault_entry(entry, &mut session.vault)?,
+            _ => Self::get_unsealed_entry(entry, &mut session.vault)?,
+        };
         match field {
-            Field::Username => entry.username = String::from(value),
-            Field::Password => entry.password = SecretBox::new(Box::from(String::from(value))),
+            Field::Username if entry.username != value => {
+                entry.record_version();
+                entry.username = String::from(value);
+            }
+            Field::Password if entry.password.expose_secret() != value => {
+                entry.record_version();
+                entry.password = SecretBox::new(Box::from(String::from(value)));
+                entry.password_changed_at = Some(Utc::now());
+            }
+            Field::Username | Field::Password => {}
+            Field::Url => entry.url = Some(String::from(value)).filter(|v| !v.is_empty()),
+            Field::Notes => entry.notes = Some(String::from(value)).filter(|v| !v.is_empty()),
+            Field::Tags => {
+                entry.tags = value.split(',')
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect();
+            }
+            Field::Otp => {
+                if value.is_empty() {
+                    entry.otp = None;
+                } else {
+                    OtpService::parse(value)?;
+                    entry.otp = Some(SecretBox::new(Box::from(String::from(value))));
+                }
+            }
+            Field::Custom { name, secret } => {
+                // an empty value removes the field
+                match entry.fields.iter().position(|f| f.name == *name) {
+                    Some(i) if value.is_empty() => {
+                        entry.fields.remove(i);
+                    }
+                    Some(i) => {
+                        entry.fields[i].value = SecretBox::new(Box::from(String::from(value)));
+                        entry.fields[i].secret = *secret;
+                    }
+                    None if value.is_empty() => return Err(AppError::Other("Field not found".to_string())),
+                    None => entry.fields.push(CustomField {
+                        name: name.clone(),
+                        value: SecretBox::new(Box::from(String::from(value))),
+                        secret: *secret,
+                    }),
+                }
+            }
+        }
+        entry.updated_at = Some(Utc::now());
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries[index] = previous;
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    // the otp field yields the current code rather than the secret
+    pub fn field(&self, entry: &str, field: &Field, state: &mut AppState) -> Result<String, AppError> {
+        if *field == Field::Otp {
+            return Ok(self.otp(entry, state)?.0);
+        }
+        let vault = &mut state.session.as_mut().unwrap().vault;
+        let value = match field {
+            Field::Username | Field::Url | Field::Notes | Field::Tags => Self::get_vault_entry(entry, vault)?.get(field),
+            _ => Self::get_unsealed_copy(entry, vault)?.get(field),
+        };
+        value.ok_or(AppError::Other(format!("Entry has no {}", field)))
+    }
+
+    pub fn mark_copied(&self, entry: &str, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        let entry = Self::get_vault_entry(entry, &mut session.vault)?;
+        entry.last_copied_at = Some(Utc::now());
+        // a copy is no edit worth a backup slot
+        self.vault.touch(&session.name, &session.secret, &session.vault)?;
+        Ok(())
+    }
+
+    // for sessions kept open next to other writers, like the agent's: the copy time goes
+    // into a fresh load of the vault, so edits saved elsewhere since unlocking are kept
+    pub fn mark_copied_in_store(&self, entry: &str, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        let entry = Self::get_vault_entry(entry, &mut session.vault)?;
+        let now = Utc::now();
+        entry.last_copied_at = Some(now);
+        let mut stored = self.vault.load(&session.name, &session.secret)?;
+        stored.assign_ids();
+        // an entry deleted in the meantime stays deleted
+        if let Some(stored_entry) = stored.entries.iter_mut().find(|e| e.id == entry.id) {
+            stored_entry.last_copied_at = Some(now);
+            self.vault.touch(&session.name, &session.secret, &stored)?;
+        }
+        Ok(())
+    }
+
+    pub fn stale(&self, days: i64, state: &mut AppState) -> String {
+        let session = state.session.as_ref().unwrap();
+        let mut stale: Vec<(&PasswordEntry, Option<i64>)> = session.vault.entries.iter()
+            .map(|entry| (entry, entry.password_age().map(|age| age.num_days())))
+            .filter(|(_, age)| age.is_none_or(|age| age >= days))
+            .collect();
+        // unknown ages first, then oldest first
+        stale.sort_by_key(|(_, age)| std::cmp::Reverse(age.unwrap_or(i64::MAX)));
+        if stale.is_empty() {
+            return format!("No passwords older than {} days", days);
+        }
+        stale.iter()
+            .map(|(entry, age)| match age {
+                Some(age) => format!("{} ({} days)", entry.name, age),
+                None => format!("{} (unknown age)", entry.name),
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    pub fn audit(&self, params: &AuditParams, state: &mut AppState) -> Result<AuditReport, AppError> {
+        let vault = VaultCrypto::unsealed_all(&state.session.as_ref().unwrap().vault)?;
+        Ok(AuditService::audit(&vault.entries, params))
+    }
+
+    pub fn breach_check(&self, list: &mut BreachList, state: &mut AppState) -> Result<Vec<(String, u64)>, AppError> {
+        let vault = VaultCrypto::unsealed_all(&state.session.as_ref().unwrap().vault)?;
+        BreachService::check(&vault.entries, list)
+    }
+
+    pub fn otp(&self, entry: &str, state: &mut AppState) -> Result<(String, u64), AppError> {
+        let entry = Self::get_unsealed_copy(entry, &state.session.as_ref().unwrap().vault)?;
+        let secret = entry.otp.as_ref().ok_or(AppError::Other("Entry has no OTP secret".to_string()))?;
+        let totp = OtpService::parse(secret.expose_secret())?;
+        Ok(OtpService::current(&totp))
+    }
+
+    pub fn history(&self, entry: &str, expose: bool, state: &mut AppState) -> Result<String, AppError> {
+        let entry = Self::get_unsealed_copy(entry, &state.session.as_ref().unwrap().vault)?;
+        if entry.history.is_empty() {
+            return Ok("No previous versions".to_string());
+        }
+        Ok(
+            entry.history.iter()
+                .enumerate()
+                .map(|(i, version)| {
+                    let password = if expose { version.password.expose_secret().as_str() } else { "<hidden>" };
+                    format!(
+                        "{}. {}\n  Username: {}\n  Password: {}",
+                        i + 1,
+                        version.replaced_at.format("%Y-%m-%d %H:%M:%S UTC"),
+                        version.username,
+                        password
+                    )
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+
+    pub fn revert(&self, entry: &str, version: usize, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        let index = Self::get_entry_index(entry, &session.vault)?;
+        let previous = VaultCrypto::copy(&session.vault.entries[index]);
+        let entry = Self::get_unsealed_entry(entry, &mut session.vault)?;
+        version.checked_sub(1)
+            .and_then(|index| entry.revert(index))
+            .ok_or(AppError::Other("Version not found".to_string()))?;
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries[index] = previous;
+            return Err(err.into());
         }
-        self.vault.save(&session.name, &session.secret, &session.vault).expect("Failed to save vault");
         Ok(())
     }
 
+    pub fn import_entries(&self, entries: Vec<PasswordEntry>, duplicates: &DuplicateStrategy, state: &mut AppState) -> Result<ImportReport, AppError> {
+        let session = state.session.as_mut().unwrap();
+        let mut report = ImportReport::default();
+        let now = Utc::now();
+        let mut added: Vec<PasswordEntry> = Vec::new();
+        let mut overwritten: HashSet<String> = HashSet::new();
+        for mut entry in entries {
+            // ids from a bundle belong to the vault it came from
+            entry.id = PasswordEntry::new_id();
+            entry.created_at = Some(now);
+            entry.updated_at = Some(now);
+            entry.password_changed_at = Some(now);
+            let exists = |name: &str| added.iter().any(|e| e.name == name)
+                || session.vault.entries.iter().any(|e| e.name == name && !overwritten.contains(name));
+            if !exists(&entry.name) {
+                report.added += 1;
+            } else {
+                match duplicates {
+                    DuplicateStrategy::Skip => {
+                        report.skipped += 1;
+                        continue;
+                    }
+                    DuplicateStrategy::Overwrite => {
+                        added.retain(|e| e.name != entry.name);
+                        overwritten.insert(entry.name.clone());
+                        report.overwritten += 1;
+                    }
+                    DuplicateStrategy::Rename => {
+                        let base = entry.name.clone();
+                        let mut suffix = 2;
+                        while exists(&format!("{}_{}", base, suffix)) {
+                            suffix += 1;
+                        }
+                        entry.name = format!("{}_{}", base, suffix);
+                        report.renamed += 1;
+                    }
+                }
+            }
+            added.push(entry);
+        }
+        // everything is written at once, so a failed import leaves the vault file untouched,
+        // and the overwritten entries go back in place so the session matches it too
+        let (kept, replaced): (Vec<_>, Vec<_>) = mem::take(&mut session.vault.entries)
+            .into_iter()
+            .enumerate()
+            .partition(|(_, e)| !overwritten.contains(&e.name));
+        let kept_len = kept.len();
+        session.vault.entries = kept.into_iter().map(|(_, e)| e).chain(added).collect();
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries.truncate(kept_len);
+            for (index, entry) in replaced {
+                session.vault.entries.insert(index, entry);
+            }
+            return Err(err.into());
+        }
+        Ok(report)
+    }
+
+    // the export with a note on anything the format leaves out
+    pub fn export(&self, format: &ExportFormat, passphrase: Option<&SecretBox<String>>, state: &mut AppState) -> Result<(String, Option<String>), AppError> {
+        let vault = VaultCrypto::unsealed_all(&state.session.as_ref().unwrap().vault)?;
+        let data = ExportService::export(format, &vault, passphrase)?;
+        Ok((data, ExportService::omitted(format, &vault)))
+    }
+
     pub fn delete_entry(&self, entry: &str, state: &mut AppState) -> Result<(), AppError> {
         let session = state.session.as_mut().unwrap();
-        let entry = Self::get_vault_entry(entry, &mut session.vault)?.name.clone();
-        session.vault.entries.retain(|e| e.name != entry);
+        let index = Self::get_entry_index(entry, &session.vault)?;
+        let removed = session.vault.entries.remove(index);
+        if let Err(err) = self.vault.save(&session.name, &session.secret, &session.vault) {
+            session.vault.entries.insert(index, removed);
+            return Err(err.into());
+        }
+        Ok(())
+    }
+
+    pub fn change_password(&self, current: &SecretBox<String>, new: SecretBox<String>, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        if current.expose_secret() != session.secret.expose_secret() {
+            return Err(VaultError::WrongPassword.into());
+        }
+        // a new data key too, so the old password and a copy of the old file reveal nothing saved from now on
+        let mut vault = VaultCrypto::unsealed_all(&session.vault)?;
+        self.vault.save(&session.name, &new, &vault)?;
+        vault.zeroize();
+        // reloaded so the session holds the new key and sealed entries again
+        let mut reloaded = self.vault.load(&session.name, &new)?;
+        reloaded.assign_ids();
+        session.vault.zeroize();
+        session.vault = reloaded;
+        session.secret = new;
         Ok(())
     }
 
+    pub fn backups(&self, state: &mut AppState) -> Result<String, AppError> {
+        let name = &state.session.as_ref().unwrap().name;
+        let backups = self.vault.backups(name)?;
+        if backups.is_empty() {
+            return Ok("No backups available".to_string());
+        }
+        Ok(
+            backups.iter()
+                .enumerate()
+                .map(|(i, date)| format!("{}. {}", i + 1, date.format("%Y-%m-%d %H:%M:%S UTC")))
+                .collect::<Vec<String>>()
+                .join("\n")
+        )
+    }
+
+    pub fn restore(&self, backup: usize, state: &mut AppState) -> Result<(), AppError> {
+        let session = state.session.as_mut().unwrap();
+        if backup == 0 {
+            return Err(AppError::InvalidArgument("backup".to_string()));
+        }
+        self.vault.restore(&session.name, backup - 1)?;
+        match self.vault.load(&session.name, &session.secret) {
+            Ok(mut vault) => {
+                vault.assign_ids();
+                session.vault = vault;
+                Ok(())
+            }
+            Err(VaultError::WrongPassword) => {
+                // the backup predates a master password change
+                self.close(state);
+                Err(AppError::Other("Backup restored with a different master password, reopen the vault".to_string()))
+            }
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // locks the vault as soon as it expires rather than on the next command,
+    // returning the notice to show in its place
+    pub fn expire(&self, state: &mut AppState) -> Option<String> {
+        let session = state.session.as_ref()?;
+        if session.expires_at >= Utc::now() {
+            return None;
+        }
+        let notice = match session.expires_at >= session.max_expires_at {
+            true => format!("Vault {} locked, sessions last at most {} minutes", session.name, ConfigService::current().session_lifetime),
+            false => format!("Vault {} locked after {} minutes of inactivity", session.name, ConfigService::current().session_ttl),
+        };
+        self.close(state);
+        Some(notice)
+    }
+
     pub fn is_accessible(&self, state: &mut AppState) -> Result<(), AppError> {
         if let Some(session) = &state.session {
             // check session
@@ -115,7 +526,8 @@ impl<V: VaultManagerTrait> VaultService<V> {
                 Err(AppError::Other("Session expired".to_string()))
             } else {
                 // extend session
-                state.session.as_mut().unwrap().expires_at = now + SESSION_TTL;
+                let session = state.session.as_mut().unwrap();
+                session.expires_at = (now + ConfigService::current().session_ttl()).min(session.max_expires_at);
                 Ok(())
             }
         } else {
@@ -123,11 +535,28 @@ impl<V: VaultManagerTrait> VaultService<V> {
         }
     }
 
+    pub fn sync(
+        &self,
+        state: &mut AppState,
+        resolve: impl FnMut(&MergeConflict) -> Result<Resolution, AppError>,
+    ) -> Result<SyncReport, AppError> {
+        let session = state.session.as_mut().unwrap();
+        let path = self.vault.file_path(&session.name)?;
+        // ids assigned since the last save must be on disk before the file is committed,
+        // without a backup, which would land in the git working tree
+        self.vault.touch(&session.name, &session.secret, &session.vault)?;
+        let report = SyncService::sync(&path, &session.secret, resolve)?;
+        let mut vault = self.vault.load(&session.name, &session.secret)?;
+        vault.assign_ids();
+        session.vault = vault;
+        Ok(report)
+    }
+
     pub fn exists(&self, name: &str) -> Result<(), AppError> {
-        if self.vault.exists(name).unwrap() {
+        if self.vault.exists(name)? {
             Ok(())
         } else {
-            Err(AppError::Other("Vault not found".to_string()))
+            Err(VaultError::NotFound("Vault".to_string()).into())
         }
     }
 
@@ -137,4 +566,23 @@ impl<V: VaultManagerTrait> VaultService<V> {
             .find(|e| e.name == entry)
             .ok_or(AppError::Other("Service not found".to_string()))
     }
+
+    fn get_entry_index(entry: &str, vault: &Vault) -> Result<usize, AppError> {
+        vault.entries.iter()
+            .position(|e| e.name == entry)
+            .ok_or(AppError::Other("Service not found".to_string()))
+    }
+
+    // a decrypted copy of the entry, for commands that only read its secrets
+    fn get_unsealed_copy(entry: &str, vault: &Vault) -> Result<PasswordEntry, AppError> {
+        let index = Self::get_entry_index(entry, vault)?;
+        Ok(VaultCrypto::unsealed(vault, index)?)
+    }
+
+    // the entry with its secrets decrypted, for commands that change them
+    fn get_unsealed_entry<'a>(entry: &str, vault: &'a mut Vault) -> Result<&'a mut PasswordEntry, AppError> {
+        let index = Self::get_entry_index(entry, vault)?;
+        VaultCrypto::unseal(vault, index)?;
+        Ok(&mut vault.entries[index])
+    }
 }
\ No newline at end of file
@@ -4,21 +4,90 @@ use regex::Regex;
 
 pub const SALT_LENGTH: usize = 16;
 pub const NONCE_LENGTH: usize = 12;
+pub const LEGACY_VAULT_FORMAT_VERSION: u32 = 0;
+// format 1 encrypts the whole vault as one blob, format 2 seals each entry under a wrapped data key
+pub const BLOB_VAULT_FORMAT_VERSION: u32 = 1;
+pub const VAULT_FORMAT_VERSION: u32 = 2;
+pub const VAULT_KEY_LENGTH: usize = 32;
+pub const CIPHER_AES_256_GCM_SIV: &str = "aes-256-gcm-siv";
+pub const KDF_ARGON2ID: &str = "argon2id";
+pub const ARGON2_M_COST: u32 = 19 * 1024;
+pub const ARGON2_T_COST: u32 = 2;
+pub const ARGON2_P_COST: u32 = 1;
+// the header is only authenticated after the key is derived, so its costs are capped first
+pub const ARGON2_M_COST_MAX: u32 = 128 * 1024;
+pub const ARGON2_T_COST_MAX: u32 = 10;
+pub const ARGON2_P_COST_MAX: u32 = 8;
 pub const SESSION_TTL: TimeDelta = Duration::minutes(10);
 pub const CLIPBOARD_TTL: TimeDelta = Duration::seconds(10);
+pub const SESSION_TTL_MAX_MINUTES: i64 = 24 * 60;
+pub const SESSION_LIFETIME: TimeDelta = Duration::hours(8);
+pub const SESSION_LIFETIME_MAX_MINUTES: i64 = 7 * 24 * 60;
+pub const SESSION_CHECK_INTERVAL: TimeDelta = Duration::seconds(1);
+pub const CLIPBOARD_TTL_MAX_SECONDS: i64 = 10 * 60;
+pub const AGENT_SOCKET_ENV: &str = "PASSMAN_AGENT_SOCK";
+pub const AGENT_SOCKET_NAME: &str = "agent.sock";
+pub const AGENT_IO_TIMEOUT: TimeDelta = Duration::seconds(5);
+pub const AGENT_POLL_INTERVAL: TimeDelta = Duration::milliseconds(100);
+pub const AGENT_START_TIMEOUT: TimeDelta = Duration::seconds(5);
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+pub const CONFIG_PATH_ENV: &str = "PASSMAN_CONFIG";
+pub const CONFIG_ENV_PREFIX: &str = "PASSMAN_";
+pub const VAULT_REGISTRY_FILE_NAME: &str = "vaults.toml";
+pub const VAULT_EXTENSION: &str = ".vault";
+pub const VAULT_LOCATOR_SQLITE: &str = "sqlite:";
+pub const VAULT_STORE_TIMEOUT: TimeDelta = Duration::seconds(30);
+pub const SYNC_DEFAULT_REMOTE: &str = "origin";
+pub const VAULT_BACKUP_COUNT: usize = 5;
+pub const VAULT_BACKUP_COUNT_MAX: usize = 100;
+// csv column prefixes for custom fields, followed by the field name
+pub const CSV_FIELD_PREFIX: &str = "field:";
+pub const CSV_SECRET_FIELD_PREFIX: &str = "secret:";
+pub const ENTRY_HISTORY_LEN: usize = 10;
+pub const TOTP_DEFAULT_DIGITS: u32 = 6;
+pub const TOTP_DEFAULT_PERIOD: u64 = 30;
 pub const ARG_MAX_LEN: usize = 64;
 pub const PASSWORD_MAX_LEN: usize = 128;
 pub const PASSWORD_MIN_LEN: usize = 8;
+pub const TEXT_MAX_LEN: usize = 4096;
+pub const AUDIT_MIN_STRENGTH: u8 = 3;
+pub const AUDIT_MAX_AGE_DAYS: u32 = 365;
+pub const PASSPHRASE_DEFAULT_WORDS: usize = 6;
+pub const PASSPHRASE_MIN_WORDS: usize = 3;
+pub const PASSPHRASE_MAX_WORDS: usize = 20;
+pub const PASSPHRASE_DEFAULT_SEPARATOR: &str = "-";
+pub const PASSPHRASE_SEPARATOR_MAX_LEN: usize = 4;
+pub const PASSPHRASE_SYMBOL_CHARS: &str = "!@#$%^&*+=?~";
+pub const WORDLIST: &str = include_str!("wordlist.txt");
+pub const COMMON_PASSWORDS: &str = include_str!("passwords.txt");
+pub const ENGLISH_WORDS: &str = include_str!("english.txt");
+pub const COMMON_NAMES: &str = include_str!("names.txt");
+pub const STRENGTH_MAX_LEN: usize = 100;
+pub const MASTER_PASSWORD_MIN_STRENGTH: u8 = 3;
+pub const MIN_YEAR_SPACE: i32 = 20;
+// guesses per second
+pub const ATTACK_MODELS: [(&str, f64); 4] = [
+    ("online, throttled", 100.0 / 3600.0),
+    ("online, unthrottled", 10.0),
+    ("offline, slow hash", 1e4),
+    ("offline, fast hash", 1e10),
+];
 pub const UPPERCASE_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
 pub const LOWERCASE_CHARS: &str = "abcdefghijklmnopqrstuvwxyz";
 pub const DIGIT_CHARS: &str = "0123456789";
 pub const SYMBOL_CHARS: &str = "!@#$%^&*()_+[]{};:'\",.<>/?\\|`~- ";
 pub const AMBIGUOUS_CHARS: &str = "B8G6I1l0OQDS5Z2";
+pub const EXIT_SUCCESS: i32 = 0;
+pub const EXIT_FAILURE: i32 = 1;
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_VAULT_ACCESS: i32 = 3;
 
 lazy_static! {
+    pub static ref WORDS: Vec<&'static str> = WORDLIST.lines().collect();
+    pub static ref DAV_HREF_REGEX: Regex = Regex::new(r"(?i)<(?:[a-z0-9]+:)?href>([^<]+)</").unwrap();
     pub static ref ARG_REGEX: Regex = Regex::new(r"^[a-zA-Z0-9_.@+\-]+$").unwrap();
     pub static ref UPPERCASE_REGEX: Regex = Regex::new(r"[A-Z]").unwrap();
     pub static ref LOWERCASE_REGEX: Regex = Regex::new(r"[a-z]").unwrap();
     pub static ref DIGIT_REGEX: Regex = Regex::new(r"\d").unwrap();
     pub static ref SPECIAL_CHAR_REGEX: Regex = Regex::new(r#"[!@#$%^&*()_+=\[\]{};:'",.<>?/\\|`~\-\s]"#).unwrap();
-}
\ No newline at end of file
+}
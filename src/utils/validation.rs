@@ -1,4 +1,5 @@
 use crate::domain::app::error::AppError;
+use crate::services::strength_service::StrengthService;
 use crate::utils::constants::*;
 
 pub fn validate_arg(input: &str, name: &str) -> Result<(), AppError> {
@@ -17,6 +18,24 @@ pub fn validate_arg(input: &str, name: &str) -> Result<(), AppError> {
     }
 }
 
+pub fn validate_text(input: &str, name: &str) -> Result<(), AppError> {
+    if input.len() > TEXT_MAX_LEN {
+        return Err(AppError::InvalidArgument(name.to_string()));
+    }
+    // blacklisting validation, newlines are allowed for multiline notes
+    if input.chars().any(|c| c.is_control() && c != '\n') {
+        return Err(AppError::InvalidArgument(name.to_string()));
+    }
+    Ok(())
+}
+
+pub fn validate_path(input: &str) -> Result<(), AppError> {
+    if input.is_empty() || input.len() > TEXT_MAX_LEN || input.chars().any(|c| c.is_control()) {
+        return Err(AppError::InvalidArgument("path".to_string()));
+    }
+    Ok(())
+}
+
 pub fn validate_password(password: &str) -> Result<(), AppError> {
     if password.len() > PASSWORD_MAX_LEN {
         return Err(AppError::Other("Password too long".to_string()));
@@ -35,12 +54,14 @@ pub fn validate_password_strength(password: &str) -> Result<(), AppError> {
     if password.len() < PASSWORD_MIN_LEN {
         return Err(AppError::Other("Password too short".to_string()));
     }
-    if !(UPPERCASE_REGEX.is_match(password)
-        && LOWERCASE_REGEX.is_match(password)
-        && DIGIT_REGEX.is_match(password)
-        && SPECIAL_CHAR_REGEX.is_match(password)
-    ) {
-        return Err(AppError::Other("Password too weak".to_string()));
+    // pattern-aware, so long passphrases pass while Password123! does not
+    let estimate = StrengthService::estimate(password, &[]);
+    if estimate.score < MASTER_PASSWORD_MIN_STRENGTH {
+        let reason = estimate.warning.or(estimate.suggestions.into_iter().next());
+        return Err(AppError::Other(match reason {
+            Some(reason) => format!("Password too weak: {}", reason),
+            None => "Password too weak".to_string(),
+        }));
     }
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,27 @@
+use std::env;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tempfile::TempDir;
+use passman::domain::app::config::CONFIG_KEYS;
+use passman::services::config_service::ConfigService;
+use passman::utils::constants::{CONFIG_FILE_NAME, CONFIG_PATH_ENV};
+
+#[cfg(test)]
+static CONFIG_DIR: OnceLock<TempDir> = OnceLock::new();
+
+// points the config, the vault registry and the vault directory at a temporary directory
+// and drops PASSMAN_<KEY> overrides, so tests never see what the user configured.
+// the config is read once per process, so every test calls this before touching it
+#[cfg(test)]
+pub fn isolate() -> PathBuf {
+    let dir = CONFIG_DIR.get_or_init(|| {
+        let dir = tempfile::tempdir().unwrap();
+        for key in CONFIG_KEYS {
+            env::remove_var(ConfigService::env_var(key));
+        }
+        env::set_var(CONFIG_PATH_ENV, dir.path().join(CONFIG_FILE_NAME));
+        env::set_var(ConfigService::env_var("vault_dir"), dir.path().join("vaults"));
+        dir
+    });
+    dir.path().to_path_buf()
+}
@@ -1,74 +1,174 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
-use secrecy::{ExposeSecret, SecretBox};
+use std::sync::atomic::{AtomicBool, Ordering};
+use chrono::{DateTime, Utc};
+use secrecy::SecretBox;
 use passman::domain::vault::vault::Vault;
-use passman::domain::vault::vault_file::VaultFile;
-use passman::repository::vault::vault_crypto::VaultCrypto;
+use passman::domain::vault::vault_error::VaultError;
+use passman::repository::vault::vault_manager::VaultManager;
 use passman::repository::vault::vault_manager_trait::VaultManagerTrait;
 
 #[cfg(test)]
+type Storage = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+#[cfg(test)]
+type Backups = Arc<Mutex<HashMap<String, Vec<(DateTime<Utc>, Vec<u8>)>>>>;
+
+#[cfg(test)]
+type Locations = Arc<Mutex<HashMap<String, String>>>;
+
+// clones share their storage, like two processes opening the same files
+#[cfg(test)]
+#[derive(Clone)]
 pub struct MockVaultManager {
-    storage: Arc<Mutex<HashMap<String, (VaultFile, SecretBox<String>)>>>
+    storage: Storage,
+    backups: Backups,
+    locations: Locations,
+    failing: Arc<AtomicBool>,
 }
 
-#[cfg(test)]
 #[cfg(test)]
 impl MockVaultManager {
+    fn store(&self, name: &str, data: Vec<u8>, backup: bool) -> Result<(), VaultError> {
+        if self.failing.load(Ordering::SeqCst) {
+            return Err(VaultError::Store("No space left on device".to_string()));
+        }
+        let mut vaults = self.storage.lock().unwrap();
+        if let Some(previous) = vaults.remove(name).filter(|_| backup) {
+            let mut backups = self.backups.lock().unwrap();
+            backups.entry(name.to_string()).or_default().insert(0, (Utc::now(), previous));
+        }
+        vaults.insert(name.to_string(), data);
+        Ok(())
+    }
+
     pub fn new() -> Self {
         Self {
             storage: Arc::new(Mutex::new(HashMap::new())),
+            backups: Arc::new(Mutex::new(HashMap::new())),
+            locations: Arc::new(Mutex::new(HashMap::new())),
+            failing: Arc::new(AtomicBool::new(false)),
         }
     }
+
+    // puts raw file contents in as the newest backup, like one left by an older version
+    #[allow(dead_code)]
+    pub fn add_backup(&self, name: &str, data: Vec<u8>) {
+        let mut backups = self.backups.lock().unwrap();
+        backups.entry(name.to_string()).or_default().insert(0, (Utc::now(), data));
+    }
+
+    // makes every write fail, like a full disk; not every test crate needs it
+    #[allow(dead_code)]
+    pub fn fail_writes(&self, failing: bool) {
+        self.failing.store(failing, Ordering::SeqCst);
+    }
 }
 
 #[cfg(test)]
 impl VaultManagerTrait for MockVaultManager {
-    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), String> {
-        let vault = Vault { entries: vec![] };
+    fn create(&self, name: &str, password: &SecretBox<String>) -> Result<(), VaultError> {
+        let vault = Vault::new(vec![]);
         self.save(name, password, &vault)
     }
 
-    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), String> {
-        let (salt, nonce, ciphertext) = VaultCrypto::encrypt(vault, password);
+    fn save(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        self.store(name, VaultManager::encode(vault, password)?, true)
+    }
+
+    fn touch(&self, name: &str, password: &SecretBox<String>, vault: &Vault) -> Result<(), VaultError> {
+        self.store(name, VaultManager::encode(vault, password)?, false)
+    }
+
+    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, VaultError> {
+        let vaults = self.storage.lock().unwrap();
+        let data = vaults.get(name).ok_or(VaultError::NotFound("Vault".to_string()))?;
+        VaultManager::decode(data, password)
+    }
+
+    fn list(&self) -> Result<Vec<(String, String)>, VaultError> {
+        let vaults = self.storage.lock().unwrap();
+        let locations = self.locations.lock().unwrap();
+        let mut names: Vec<String> = vaults.keys().cloned().collect();
+        names.sort();
+        Ok(names.into_iter()
+            .map(|name| {
+                let location = locations.get(&name).cloned().unwrap_or(format!("memory:{}", name));
+                (name, location)
+            })
+            .collect())
+    }
+
+    fn delete(&self, name: &str) -> Result<(), VaultError> {
         let mut vaults = self.storage.lock().unwrap();
-        let secret = SecretBox::new(Box::new(password.expose_secret().to_string()));
-        vaults.insert(
-            name.to_string(),
-            (VaultFile { salt, nonce, ciphertext }, secret)
-        );
+        vaults.remove(name);
+        self.locations.lock().unwrap().remove(name);
         Ok(())
     }
 
-    fn load(&self, name: &str, password: &SecretBox<String>) -> Result<Vault, String> {
-        let vaults = self.storage.lock().unwrap();
-        let (vault_file, stored_pass) = vaults.get(name)
-            .ok_or("Vault not found")?;
+    fn file_path(&self, _name: &str) -> Result<PathBuf, VaultError> {
+        Err(VaultError::InvalidPath("Vault is not stored in a file".to_string()))
+    }
 
-        if password.expose_secret() != stored_pass.expose_secret() {
-            return Err("Invalid password".into());
+    fn register(&self, name: &str, location: &str) -> Result<(), VaultError> {
+        let mut locations = self.locations.lock().unwrap();
+        if locations.get(name).is_some_and(|current| current != location) {
+            return Err(VaultError::AlreadyExists("Vault".to_string()));
         }
+        locations.insert(name.to_string(), location.to_string());
+        Ok(())
+    }
 
-        VaultCrypto::decrypt(
-            password,
-            &vault_file.ciphertext,
-            &vault_file.salt,
-            &vault_file.nonce
-        )
+    fn unregister(&self, name: &str) -> Result<(), VaultError> {
+        self.locations.lock().unwrap().remove(name);
+        Ok(())
     }
 
-    fn list(&self) -> Result<Vec<String>, String> {
-        let vaults = self.storage.lock().unwrap();
-        Ok(vaults.keys().cloned().collect())
+    fn relocate(&self, name: &str, location: &str) -> Result<String, VaultError> {
+        if !self.exists(name)? {
+            return Err(VaultError::NotFound("Vault".to_string()));
+        }
+        self.locations.lock().unwrap().insert(name.to_string(), location.to_string());
+        Ok(location.to_string())
     }
 
-    fn delete(&self, name: &str) -> Result<(), String> {
+    fn rename(&self, name: &str, new_name: &str) -> Result<(), VaultError> {
         let mut vaults = self.storage.lock().unwrap();
-        vaults.remove(name);
+        if vaults.contains_key(new_name) {
+            return Err(VaultError::AlreadyExists("Vault".to_string()));
+        }
+        let vault = vaults.remove(name).ok_or(VaultError::NotFound("Vault".to_string()))?;
+        vaults.insert(new_name.to_string(), vault);
+        let mut backups = self.backups.lock().unwrap();
+        if let Some(previous) = backups.remove(name) {
+            backups.insert(new_name.to_string(), previous);
+        }
+        let mut locations = self.locations.lock().unwrap();
+        if let Some(location) = locations.remove(name) {
+            locations.insert(new_name.to_string(), location);
+        }
         Ok(())
     }
 
-    fn exists(&self, name: &str) -> Result<bool, String> {
+    fn exists(&self, name: &str) -> Result<bool, VaultError> {
         let vaults = self.storage.lock().unwrap();
         Ok(vaults.contains_key(name))
     }
-}
\ No newline at end of file
+
+    fn backups(&self, name: &str) -> Result<Vec<DateTime<Utc>>, VaultError> {
+        let backups = self.backups.lock().unwrap();
+        Ok(backups.get(name).map(|b| b.iter().map(|(date, _)| *date).collect()).unwrap_or_default())
+    }
+
+    fn restore(&self, name: &str, index: usize) -> Result<(), VaultError> {
+        let data = {
+            let backups = self.backups.lock().unwrap();
+            let (_, data) = backups.get(name)
+                .and_then(|b| b.get(index))
+                .ok_or(VaultError::NotFound("Backup".to_string()))?;
+            data.clone()
+        };
+        self.store(name, data, true)
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod mocks {
+    pub mod mock_config;
+    pub mod mock_vault_manager;
+}
+
+#[cfg(test)]
+mod test_agent_service {
+    use secrecy::SecretBox;
+    use passman::domain::app::state::AppState;
+    use passman::domain::cli::field::Field;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::vault::Vault;
+    use passman::repository::vault::vault_manager_trait::VaultManagerTrait;
+    use passman::services::agent_service::AgentService;
+    use passman::services::vault_service::VaultService;
+    use crate::mocks::mock_config;
+    use crate::mocks::mock_vault_manager::MockVaultManager;
+
+    fn setup() -> (AgentService<MockVaultManager>, SecretBox<String>) {
+        mock_config::isolate();
+        let manager = MockVaultManager::new();
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        let entries = vec![
+            PasswordEntry::new("github", "alice", "hunter22"),
+            PasswordEntry::new("gitlab", "bob", "swordfish"),
+        ];
+        manager.save("work", &secret, &Vault::new(entries)).unwrap();
+        manager.create("home", &secret).unwrap();
+        (AgentService::new(manager), secret)
+    }
+
+    #[test]
+    fn test_unlock_and_get_field() {
+        let (mut agent, secret) = setup();
+
+        // locked vaults are not served
+        assert!(!agent.is_unlocked("work"));
+        assert!(agent.field("work", "github", &Field::Password, false).is_err());
+
+        // unlock vault
+        agent.unlock("work", &secret).unwrap();
+
+        // check result
+        assert!(agent.is_unlocked("work"));
+        assert_eq!(agent.field("work", "github", &Field::Password, false).unwrap(), "hunter22");
+        assert_eq!(agent.field("work", "hub", &Field::Username, true).unwrap(), "alice");
+        assert!(agent.field("work", "git", &Field::Password, false).is_err());
+        assert!(agent.field("work", "mail", &Field::Password, false).is_err());
+    }
+
+    #[test]
+    fn test_copy_keeps_edits_made_elsewhere() {
+        // setup
+        mock_config::isolate();
+        let manager = MockVaultManager::new();
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        manager.save("work", &secret, &Vault::new(vec![PasswordEntry::new("github", "alice", "hunter22")])).unwrap();
+        let mut agent = AgentService::new(manager.clone());
+        agent.unlock("work", &secret).unwrap();
+
+        // edit the vault outside the agent, then copy through it
+        let service = VaultService::new(manager.clone());
+        let mut state = AppState { session: None };
+        service.open("work", &secret, &mut state).unwrap();
+        service.add_entry("mail", "bob", "swordfish", &mut state).unwrap();
+        service.update_entry("github", &Field::Username, "carol", &mut state).unwrap();
+        agent.field("work", "github", &Field::Password, true).unwrap();
+
+        // check result
+        let vault = manager.load("work", &secret).unwrap();
+        let names: Vec<(&str, &str)> = vault.entries.iter().map(|e| (e.name.as_str(), e.username.as_str())).collect();
+        assert_eq!(names, vec![("github", "carol"), ("mail", "bob")]);
+        assert!(vault.entries[0].last_copied_at.is_some());
+    }
+
+    #[test]
+    fn test_unlock_rejects_wrong_password() {
+        let (mut agent, _) = setup();
+        let wrong = SecretBox::new(Box::new("wrong_password".to_string()));
+
+        assert!(agent.unlock("work", &wrong).is_err());
+        assert!(agent.unlock("missing", &wrong).is_err());
+        assert!(!agent.is_unlocked("work"));
+    }
+
+    #[test]
+    fn test_list_and_lock() {
+        let (mut agent, secret) = setup();
+        assert_eq!(agent.list(), "No vaults unlocked");
+
+        // unlock both vaults
+        agent.unlock("work", &secret).unwrap();
+        agent.unlock("home", &secret).unwrap();
+        let list = agent.list();
+        assert!(list.starts_with("home (locks in "));
+        assert!(list.contains("\nwork (locks in "));
+
+        // lock one, then the rest
+        assert_eq!(agent.lock(Some("work")), 1);
+        assert!(!agent.is_unlocked("work"));
+        assert!(agent.is_unlocked("home"));
+        assert_eq!(agent.lock(None), 1);
+        assert_eq!(agent.list(), "No vaults unlocked");
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test_agent_socket {
+    use std::{env, thread};
+    use std::time::Duration;
+    use tempfile::tempdir;
+    use passman::cli::agent;
+    use passman::domain::app::agent_protocol::{AgentRequest, AgentResponse};
+    use passman::domain::cli::commands::{Command, VaultCommand};
+    use passman::domain::cli::field::Field;
+    use passman::utils::constants::AGENT_SOCKET_ENV;
+    use crate::mocks::mock_config;
+
+    #[test]
+    fn test_agent_round_trip() {
+        mock_config::isolate();
+        // the agent creates the socket directory private to the user
+        let dir = tempdir().unwrap();
+        let socket = dir.path().join("agent").join("agent.sock");
+        env::set_var(AGENT_SOCKET_ENV, &socket);
+        assert!(agent::request(&AgentRequest::List).is_err());
+
+        // serve in the background
+        let server = thread::spawn(agent::run);
+        let mut response = agent::request(&AgentRequest::List);
+        for _ in 0..50 {
+            if response.is_ok() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+            response = agent::request(&AgentRequest::List);
+        }
+        assert_eq!(response.unwrap(), AgentResponse::Ok(Some("No vaults unlocked".to_string())));
+
+        // vaults the agent does not hold fall back to the caller
+        let get = AgentRequest::Get { vault: "work".to_string(), entry: "github".to_string(), field: Field::Password };
+        assert_eq!(agent::request(&get).unwrap(), AgentResponse::Locked);
+        let cmd = Command::Vault(VaultCommand::Get("github".to_string(), Field::Password));
+        assert!(agent::forward("work", &cmd).is_none());
+
+        // stop the agent
+        assert_eq!(agent::request(&AgentRequest::Stop).unwrap(), AgentResponse::Ok(Some("Agent stopped".to_string())));
+        server.join().unwrap().unwrap();
+        assert!(!socket.exists());
+    }
+}
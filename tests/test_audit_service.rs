@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test_audit_service {
+    use chrono::{Duration, Utc};
+    use passman::domain::cli::audit_params::{AuditCheck, AuditParams, AuditSort};
+    use passman::domain::vault::audit_report::AuditIssue;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::services::audit_service::AuditService;
+
+    fn params(checks: Vec<AuditCheck>, sort: AuditSort) -> AuditParams {
+        AuditParams { checks, sort, max_age_days: 365 }
+    }
+
+    fn entries() -> Vec<PasswordEntry> {
+        let strong = PasswordEntry::new("bank", "me", "vR7#qL!2zX@9mK$wP4^t");
+        let weak = PasswordEntry::new("forum", "me", "password");
+        let reused_a = PasswordEntry::new("mail", "me", "Shared-Secret#2024!xyz");
+        let reused_b = PasswordEntry::new("shop", "me", "Shared-Secret#2024!xyz");
+        let username = PasswordEntry::new("git", "octocat@example.com", "Octocat!77#Zq&Wm%Lp");
+        let mut old = PasswordEntry::new("legacy", "me", "Hk8$Pw2!Qz7@Lm4#Xv9^");
+        old.password_changed_at = Some(Utc::now() - Duration::days(400));
+        vec![strong, weak, reused_a, reused_b, username, old]
+    }
+
+    #[test]
+    fn audit_finds_issues() {
+        let report = AuditService::audit(&entries(), &params(Vec::new(), AuditSort::Name));
+        let issues = |name: &str| report.findings.iter().find(|f| f.name == name).map(|f| f.issues.clone());
+
+        assert_eq!(report.audited, 6);
+        assert_eq!(report.healthy, 1);
+        assert!(issues("bank").is_none());
+        assert!(matches!(issues("forum").unwrap()[0], AuditIssue::Weak(_)));
+        assert_eq!(issues("mail").unwrap(), vec![AuditIssue::Reused(2)]);
+        assert_eq!(issues("git").unwrap(), vec![AuditIssue::UsernameMatch]);
+        assert_eq!(issues("legacy").unwrap(), vec![AuditIssue::Old(400)]);
+        assert!((report.health() - 100.0 / 6.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn audit_filter_and_sort() {
+        let report = AuditService::audit(&entries(), &params(vec![AuditCheck::Reused], AuditSort::Name));
+        let names: Vec<&str> = report.findings.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["mail", "shop"]);
+        // health still covers the whole vault
+        assert_eq!(report.audited, 6);
+
+        let report = AuditService::audit(&entries(), &params(Vec::new(), AuditSort::Score));
+        assert_eq!(report.findings[0].name, "forum");
+        assert!(report.to_string().ends_with("Vault health: 17% (1 of 6 entries without issues)"));
+    }
+}
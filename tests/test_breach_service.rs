@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod test_breach_service {
+    use std::fs;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::services::breach_service::{BreachService, HashKind};
+    use tempfile::tempdir;
+
+    // a sorted hash list with the given passwords mixed into filler hashes
+    fn hash_list(kind: HashKind, breached: &[(&str, u64)], line_ending: &str) -> String {
+        let mut lines: Vec<String> = (0..500)
+            .map(|i| format!("{}:{}", String::from_utf8(BreachService::hash(kind, &format!("filler{}", i))).unwrap(), i + 1))
+            .collect();
+        for (password, count) in breached {
+            lines.push(format!("{}:{}", String::from_utf8(BreachService::hash(kind, password)).unwrap(), count));
+        }
+        lines.sort();
+        lines.join(line_ending) + line_ending
+    }
+
+    #[test]
+    fn known_hashes() {
+        assert_eq!(BreachService::hash(HashKind::Sha1, "password"), b"5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8");
+        assert_eq!(BreachService::hash(HashKind::Ntlm, "password"), b"8846F7EAEE8FB117AD06BDD830B7586C");
+    }
+
+    #[test]
+    fn lookup_sha1_and_ntlm() {
+        let dir = tempdir().unwrap();
+        for (kind, line_ending) in [(HashKind::Sha1, "\n"), (HashKind::Ntlm, "\r\n")] {
+            let path = dir.path().join("hashes.txt");
+            fs::write(&path, hash_list(kind, &[("password", 9545824), ("letmein", 42)], line_ending)).unwrap();
+
+            let mut list = BreachService::open(path.to_str().unwrap()).unwrap();
+            assert_eq!(list.kind, kind);
+            assert_eq!(BreachService::occurrences(&mut list, "password").unwrap(), 9545824);
+            assert_eq!(BreachService::occurrences(&mut list, "letmein").unwrap(), 42);
+            assert_eq!(BreachService::occurrences(&mut list, "filler0").unwrap(), 1);
+            assert_eq!(BreachService::occurrences(&mut list, "filler499").unwrap(), 500);
+            assert_eq!(BreachService::occurrences(&mut list, "vR7#qL!2zX@9mK$w").unwrap(), 0);
+        }
+    }
+
+    #[test]
+    fn check_entries() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("hashes.txt");
+        fs::write(&path, hash_list(HashKind::Sha1, &[("password", 100), ("letmein", 5000)], "\n")).unwrap();
+        let entries = vec![
+            PasswordEntry::new("forum", "me", "password"),
+            PasswordEntry::new("bank", "me", "vR7#qL!2zX@9mK$w"),
+            PasswordEntry::new("blog", "me", "letmein"),
+        ];
+
+        let mut list = BreachService::open(path.to_str().unwrap()).unwrap();
+        let breached = BreachService::check(&entries, &mut list).unwrap();
+        assert_eq!(breached, vec![("blog".to_string(), 5000), ("forum".to_string(), 100)]);
+
+        fs::write(&path, "not a hash list\n").unwrap();
+        assert!(BreachService::open(path.to_str().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod test_config {
+    use std::fs;
+    use chrono::Duration;
+    use colored::Color;
+    use tempfile::tempdir;
+    use passman::domain::app::config::Config;
+    use passman::services::config_service::ConfigService;
+
+    #[test]
+    fn missing_file_uses_defaults() {
+        let dir = tempdir().unwrap();
+        let config = ConfigService::load_from(&dir.path().join("config.toml"), |_| None).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.session_ttl(), Duration::minutes(10));
+        assert_eq!(config.session_lifetime(), Duration::hours(8));
+        assert_eq!(config.clipboard_ttl(), Duration::seconds(10));
+        assert_eq!(config.vault_dir(), None);
+    }
+
+    #[test]
+    fn file_values_and_env_overrides() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "session_ttl = 30\nclipboard_ttl = 5\nprompt_color = \"green\"\ngenerator_policy = \"-no-symbols\"\n").unwrap();
+
+        let config = ConfigService::load_from(&path, |_| None).unwrap();
+        assert_eq!(config.session_ttl, 30);
+        assert_eq!(config.clipboard_ttl, 5);
+        assert_eq!(config.prompt_color(), Color::Green);
+        assert_eq!(config.generator_policy, "-no-symbols");
+
+        let env = |name: &str| match name {
+            "PASSMAN_SESSION_TTL" => Some("2".to_string()),
+            "PASSMAN_VAULT_DIR" => Some("/srv/vaults".to_string()),
+            _ => None,
+        };
+        let config = ConfigService::load_from(&path, env).unwrap();
+        assert_eq!(config.session_ttl, 2);
+        assert_eq!(config.clipboard_ttl, 5);
+        assert_eq!(config.vault_dir, Some("/srv/vaults".to_string()));
+
+        let env = |name: &str| (name == "PASSMAN_CLIPBOARD_TTL").then(|| "soon".to_string());
+        assert!(ConfigService::load_from(&path, env).is_err());
+    }
+
+    #[test]
+    fn invalid_files_are_rejected() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(&path, "session_ttl = 0\n").unwrap();
+        assert!(ConfigService::read(&path).is_err());
+        fs::write(&path, "session_lifetime = 0\n").unwrap();
+        assert!(ConfigService::read(&path).is_err());
+        fs::write(&path, "session_tll = 5\n").unwrap();
+        assert!(ConfigService::read(&path).is_err());
+        fs::write(&path, "vault_dir = \"relative/dir\"\n").unwrap();
+        assert!(ConfigService::read(&path).is_err());
+    }
+
+    #[test]
+    fn set_validates_and_round_trips() {
+        let mut config = Config::default();
+        config.set("generator_length", "24").unwrap();
+        config.set("vault_color", "bright_magenta").unwrap();
+        config.set("generator_policy", "  -avoid-ambiguous   -min-digits:2 ").unwrap();
+        assert_eq!(config.get("vault_color").unwrap(), "bright magenta");
+        assert_eq!(config.get("generator_policy").unwrap(), "-avoid-ambiguous -min-digits:2");
+
+        assert!(config.set("generator_length", "4").is_err());
+        config.set("generator_length", "128").unwrap();
+        assert!(config.set("generator_length", "129").is_err());
+        assert!(config.set("prompt_color", "teal").is_err());
+        assert!(config.set("generator_policy", "digits").is_err());
+        assert!(config.set("unknown", "1").is_err());
+
+        // store passwords stay out of config show and config get
+        config.set("store_user", "alice").unwrap();
+        config.set("store_password", "hunter2").unwrap();
+        assert!(config.set("store_user", "alice:bob").is_err());
+        assert!(!config.to_string().contains("hunter2"));
+        assert_eq!(config.get("store_password").unwrap(), "********");
+        assert_eq!(config.store_password, "hunter2");
+
+        config.set("vault_backend", "sqlite:/srv/passman/vaults.db").unwrap();
+        config.set("vault_backend", "https://dav.example.com/vaults/").unwrap();
+        assert!(config.set("vault_backend", "sqlite:vaults.db").is_err());
+        assert!(config.set("vault_backend", "sqlite:/srv/passman/vaults.db#work").is_err());
+        assert!(config.set("vault_backend", "https://dav.example.com/vaults/work.vault").is_err());
+        assert!(config.set("vault_backend", "/srv/vaults").is_err());
+        config.set("vault_backend", "").unwrap();
+
+        config.set("backup_count", "0").unwrap();
+        assert_eq!(config.backup_count, 0);
+        assert!(config.set("backup_count", "1000").is_err());
+        assert!(config.set("backup_count", "-1").is_err());
+
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("nested").join("config.toml");
+        ConfigService::write(&path, &config).unwrap();
+        assert_eq!(ConfigService::read(&path).unwrap(), config);
+
+        config.set("generator_length", "").unwrap();
+        assert_eq!(config.generator_length, Config::default().generator_length);
+    }
+}
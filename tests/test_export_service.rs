@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod test_export_service {
+    use passman::domain::cli::export_params::ExportFormat;
+    use passman::domain::cli::import_params::ImportFormat;
+    use passman::domain::vault::custom_field::CustomField;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::vault::Vault;
+    use passman::services::export_service::ExportService;
+    use passman::services::import_service::ImportService;
+    use secrecy::{ExposeSecret, SecretBox};
+
+    fn vault() -> Vault {
+        let mut entry = PasswordEntry::new("github", "dev", "pa,ss\"word");
+        entry.url = Some("https://github.com".to_string());
+        entry.notes = Some("line one\nline two".to_string());
+        entry.tags = vec!["work".to_string()];
+        for (name, value, secret) in [("pin", "1234", true), ("pin", "5678", true), ("Team", "ops", false)] {
+            entry.fields.push(CustomField { name: name.to_string(), value: SecretBox::new(Box::from(value.to_string())), secret });
+        }
+        Vault::new(vec![entry, PasswordEntry::new("mail", "me", "hunter2")])
+    }
+
+    #[test]
+    fn export_csv_round_trip() {
+        let data = ExportService::export(&ExportFormat::Csv, &vault(), None).unwrap();
+        let entries = ImportService::parse(&ImportFormat::Chrome, &data).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name, "github");
+        assert_eq!(entries[0].password.expose_secret(), "pa,ss\"word");
+        assert_eq!(entries[0].notes.as_deref(), Some("line one\nline two"));
+
+        // custom fields come back, repeated names included
+        let fields: Vec<(&str, &str, bool)> = entries[0].fields.iter()
+            .map(|field| (field.name.as_str(), field.value.expose_secret().as_str(), field.secret))
+            .collect();
+        assert_eq!(fields, vec![("pin", "1234", true), ("pin", "5678", true), ("Team", "ops", false)]);
+        assert!(entries[1].fields.is_empty());
+    }
+
+    #[test]
+    fn export_notes_what_it_leaves_out() {
+        let mut vault = vault();
+        assert_eq!(ExportService::omitted(&ExportFormat::Csv, &vault), None);
+        vault.entries[0].record_version();
+        assert!(ExportService::omitted(&ExportFormat::Csv, &vault).is_some());
+        assert_eq!(ExportService::omitted(&ExportFormat::Json, &vault), None);
+    }
+
+    #[test]
+    fn export_bitwarden_round_trip() {
+        let data = ExportService::export(&ExportFormat::Bitwarden, &vault(), None).unwrap();
+        let entries = ImportService::parse(&ImportFormat::Bitwarden, &data).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].url.as_deref(), Some("https://github.com"));
+        assert_eq!(entries[0].tags, vec!["work"]);
+        assert_eq!(entries[1].username, "me");
+    }
+
+    #[test]
+    fn export_encrypted_bundle() {
+        let passphrase = SecretBox::new(Box::from("export-Passphrase-42".to_string()));
+        assert!(ExportService::export(&ExportFormat::Encrypted, &vault(), None).is_err());
+
+        let data = ExportService::export(&ExportFormat::Encrypted, &vault(), Some(&passphrase)).unwrap();
+        assert!(!data.contains("hunter2"));
+        let entries = ImportService::parse_bundle(&data, &passphrase).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].password.expose_secret(), "hunter2");
+
+        let wrong = SecretBox::new(Box::from("wrong-passphrase".to_string()));
+        assert!(ImportService::parse_bundle(&data, &wrong).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod test_import_service {
+    use passman::domain::cli::import_params::ImportFormat;
+    use passman::services::import_service::ImportService;
+    use secrecy::ExposeSecret;
+
+    #[test]
+    fn import_bitwarden() {
+        let data = r#"{
+            "encrypted": false,
+            "folders": [{ "id": "f1", "name": "Work" }],
+            "items": [
+                {
+                    "type": 1, "name": "GitHub", "notes": "recovery in safe", "folderId": "f1",
+                    "login": {
+                        "username": "dev", "password": "s3cret", "totp": "JBSWY3DPEHPK3PXP",
+                        "uris": [{ "uri": "https://github.com" }]
+                    },
+                    "fields": [{ "name": "PIN", "value": "1234", "type": 1 }]
+                },
+                { "type": 3, "name": "Card" }
+            ]
+        }"#;
+
+        let entries = ImportService::parse(&ImportFormat::Bitwarden, data).unwrap();
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[0];
+        assert_eq!(entry.name, "GitHub");
+        assert_eq!(entry.username, "dev");
+        assert_eq!(entry.password.expose_secret(), "s3cret");
+        assert_eq!(entry.url.as_deref(), Some("https://github.com"));
+        assert_eq!(entry.tags, vec!["Work"]);
+        assert!(entry.otp.is_some());
+        assert!(entry.field("PIN").unwrap().secret);
+
+        assert!(ImportService::parse(&ImportFormat::Bitwarden, r#"{"encrypted": true}"#).is_err());
+    }
+
+    #[test]
+    fn import_csv_formats() {
+        let keepass = "\"Group\",\"Title\",\"Username\",\"Password\",\"URL\",\"Notes\"\n\
+                       \"Root\",\"My Bank\",\"me\",\"p,w\",\"https://bank.com\",\"line1\nline2\"\n";
+        let entries = ImportService::parse(&ImportFormat::KeePass, keepass).unwrap();
+        assert_eq!(entries[0].name, "My_Bank");
+        assert_eq!(entries[0].password.expose_secret(), "p,w");
+        assert_eq!(entries[0].notes.as_deref(), Some("line1\nline2"));
+        assert_eq!(entries[0].tags, vec!["Root"]);
+
+        let onepassword = "Title,Url,Username,Password,OTPAuth,Favorite,Archived,Tags,Notes\n\
+                           Mail,https://mail.com,me,pw,,false,false,personal;mail,\n";
+        let entries = ImportService::parse(&ImportFormat::OnePassword, onepassword).unwrap();
+        assert_eq!(entries[0].tags, vec!["personal", "mail"]);
+
+        let firefox = "url,username,password,httpRealm\nhttps://accounts.example.com/login,me,pw,\n";
+        let entries = ImportService::parse(&ImportFormat::Chrome, firefox).unwrap();
+        assert_eq!(entries[0].name, "accounts.example.com");
+
+        assert!(ImportService::parse(&ImportFormat::Chrome, "name,url\nx,y\n").is_err());
+    }
+}
@@ -34,7 +34,8 @@ mod test_input_validations {
     #[test]
     fn validate_passwords_strength() {
         // valid passwords
-        assert!(validate_password_strength("Test123!").is_ok());
+        assert!(validate_password_strength("vR7#qL!2zX@9mK$w").is_ok());
+        assert!(validate_password_strength("correct-horse-battery-staple").is_ok());
 
         // invalid passwords
         assert!(validate_password_strength("").is_err());
@@ -42,5 +43,7 @@ mod test_input_validations {
         assert!(validate_password_strength("test123").is_err());
         assert!(validate_password_strength("Test123").is_err());
         assert!(validate_password_strength("test123!").is_err());
+        assert!(validate_password_strength("Test123!").is_err());
+        assert!(validate_password_strength("Password123!").is_err());
     }
 }
\ No newline at end of file
@@ -0,0 +1,51 @@
+#[cfg(test)]
+mod test_oneshot_args {
+    use passman::cli::parser::{parse_cmd, parse_oneshot_args};
+    use passman::domain::cli::commands::{Command, VaultCommand};
+    use passman::domain::cli::field::Field;
+    use passman::domain::cli::oneshot_args::PasswordSource;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_command_with_flags() {
+        let args = parse_oneshot_args(&argv(&["vault", "copy", "github", "-password", "--vault", "work", "--password-stdin"])).unwrap();
+        assert_eq!(args.command, "vault copy github -password");
+        assert_eq!(args.vault, Some("work".to_string()));
+        assert_eq!(args.password, PasswordSource::Stdin);
+
+        let args = parse_oneshot_args(&argv(&["--password-fd", "3", "generate", "24"])).unwrap();
+        assert_eq!(args.command, "generate 24");
+        assert_eq!(args.vault, None);
+        assert_eq!(args.password, PasswordSource::Fd(3));
+    }
+
+    #[test]
+    fn copy_takes_its_field_from_the_first_option() {
+        let copy = |words: &[&str]| parse_cmd(&parse_oneshot_args(&argv(words)).unwrap().command).unwrap();
+        let expected = |field: Field| Command::Vault(VaultCommand::Copy("github".to_string(), field));
+
+        assert_eq!(copy(&["vault", "copy", "github", "-username", "--vault", "work"]), expected(Field::Username));
+        assert_eq!(copy(&["vault", "copy", "github", "--vault", "work"]), expected(Field::Password));
+    }
+
+    #[test]
+    fn parse_invalid_flags() {
+        assert!(parse_oneshot_args(&argv(&["--vault"])).is_err());
+        assert!(parse_oneshot_args(&argv(&["vault", "show", "--vault", "../etc"])).is_err());
+        assert!(parse_oneshot_args(&argv(&["generate", "--password-fd", "x"])).is_err());
+        assert!(parse_oneshot_args(&argv(&["generate", "--unknown"])).is_err());
+        assert!(parse_oneshot_args(&argv(&["--password-stdin"])).is_err());
+    }
+
+    #[test]
+    fn parse_vault_path() {
+        let args = parse_oneshot_args(&argv(&["vault", "show", "--vault", "/mnt/share/team.vault"])).unwrap();
+        assert_eq!(args.vault, Some("team".to_string()));
+        assert_eq!(args.vault_path, Some("/mnt/share/team.vault".to_string()));
+
+        assert!(parse_oneshot_args(&argv(&["vault", "show", "--vault", "/mnt/share/.vault"])).is_err());
+    }
+}
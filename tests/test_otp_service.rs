@@ -0,0 +1,56 @@
+#[cfg(test)]
+mod test_otp_service {
+    use passman::domain::vault::totp::{Totp, TotpAlgorithm};
+    use passman::services::otp_service::OtpService;
+
+    const TIMES: [u64; 6] = [59, 1111111109, 1111111111, 1234567890, 2000000000, 20000000000];
+
+    fn totp(secret: &[u8], algorithm: TotpAlgorithm) -> Totp {
+        Totp { secret: secret.to_vec(), algorithm, digits: 8, period: 30 }
+    }
+
+    fn check_vectors(totp: &Totp, expected: [&str; 6]) {
+        for (time, code) in TIMES.iter().zip(expected) {
+            assert_eq!(OtpService::generate(totp, *time), code, "T = {}", time);
+        }
+    }
+
+    // test vectors from rfc 6238, appendix b
+    #[test]
+    fn rfc6238_sha1() {
+        let totp = totp(b"12345678901234567890", TotpAlgorithm::Sha1);
+        check_vectors(&totp, ["94287082", "07081804", "14050471", "89005924", "69279037", "65353130"]);
+    }
+
+    #[test]
+    fn rfc6238_sha256() {
+        let totp = totp(b"12345678901234567890123456789012", TotpAlgorithm::Sha256);
+        check_vectors(&totp, ["46119246", "68084774", "67062674", "91819424", "90698825", "77737706"]);
+    }
+
+    #[test]
+    fn rfc6238_sha512() {
+        let secret = b"1234567890123456789012345678901234567890123456789012345678901234";
+        let totp = totp(secret, TotpAlgorithm::Sha512);
+        check_vectors(&totp, ["90693936", "25091201", "99943326", "93441116", "38618901", "47863826"]);
+    }
+
+    #[test]
+    fn parse_secrets_and_uris() {
+        // base32 of the rfc 6238 sha1 secret
+        let totp = OtpService::parse("gezd gnbv gy3t qojq gezd gnbv gy3t qojq").unwrap();
+        assert_eq!(totp.secret, b"12345678901234567890");
+        assert_eq!(totp.digits, 6);
+        assert_eq!(OtpService::generate(&totp, 59), "287082");
+
+        let uri = "otpauth://totp/ACME:john?secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=ACME&algorithm=SHA256&digits=8&period=60";
+        let totp = OtpService::parse(uri).unwrap();
+        assert_eq!(totp.algorithm, TotpAlgorithm::Sha256);
+        assert_eq!(totp.digits, 8);
+        assert_eq!(totp.period, 60);
+
+        assert!(OtpService::parse("not base32!").is_err());
+        assert!(OtpService::parse("otpauth://hotp/x?secret=GEZDGNBV").is_err());
+        assert!(OtpService::parse("otpauth://totp/x?secret=GEZDGNBV&digits=7").is_err());
+    }
+}
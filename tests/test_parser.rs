@@ -0,0 +1,171 @@
+#[cfg(test)]
+mod mocks {
+    pub mod mock_config;
+}
+
+#[cfg(test)]
+mod test_parser {
+    use passman::cli::parser::parse_cmd;
+    use passman::domain::cli::commands::{AgentCommand, Command, ConfigCommand, VaultCommand};
+    use passman::domain::cli::field::Field;
+    use passman::domain::cli::passphrase_params::PassphraseParams;
+    use passman::domain::cli::password_params::{ClassPolicy, PasswordParams};
+    use crate::mocks::mock_config;
+
+    #[test]
+    fn parse_vault_fields() {
+        mock_config::isolate();
+        let cmd = parse_cmd("vault copy github -username").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Copy("github".to_string(), Field::Username)));
+
+        let cmd = parse_cmd("vault copy github").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Copy("github".to_string(), Field::Password)));
+
+        let cmd = parse_cmd("vault update github -tags work,dev").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Update("github".to_string(), Field::Tags, Some("work,dev".to_string()))));
+
+        let cmd = parse_cmd("vault update github -field:pin -secret").unwrap();
+        let field = Field::Custom { name: "pin".to_string(), secret: true };
+        assert_eq!(cmd, Command::Vault(VaultCommand::Update("github".to_string(), field, None)));
+
+        let cmd = parse_cmd("vault update github -notes recovery codes in the safe").unwrap();
+        let notes = Some("recovery codes in the safe".to_string());
+        assert_eq!(cmd, Command::Vault(VaultCommand::Update("github".to_string(), Field::Notes, notes)));
+
+        // free text keeps words starting with a dash and its spacing
+        let cmd = parse_cmd("vault update github -notes buy  milk -urgent -c").unwrap();
+        let notes = Some("buy  milk -urgent -c".to_string());
+        assert_eq!(cmd, Command::Vault(VaultCommand::Update("github".to_string(), Field::Notes, notes)));
+
+        let cmd = parse_cmd("vault update github -field:pin -secret 12 -gen 34").unwrap();
+        let field = Field::Custom { name: "pin".to_string(), secret: true };
+        assert_eq!(cmd, Command::Vault(VaultCommand::Update("github".to_string(), field, Some("12 -gen 34".to_string()))));
+
+        assert!(parse_cmd("vault update github -username alice bob").is_err());
+        assert!(parse_cmd("vault update github -notes -generate").is_err());
+        assert!(parse_cmd("vault update github -unknown value").is_err());
+        assert!(parse_cmd("vault update github -field:../x value").is_err());
+        assert!(parse_cmd("vault update github -tags work,;x").is_err());
+    }
+
+    #[test]
+    fn parse_passphrase() {
+        mock_config::isolate();
+        let cmd = parse_cmd("generate -words 5 -sep:_ -cap -digit -copy").unwrap();
+        let params = PassphraseParams { words: 5, separator: "_".to_string(), capitalize: true, digit: true, symbol: false };
+        assert_eq!(cmd, Command::Passphrase(params, true));
+
+        assert!(parse_cmd("generate -words five").is_err());
+        assert!(parse_cmd("generate -words 5 -sep:-----").is_err());
+    }
+
+    #[test]
+    fn parse_password_policy() {
+        mock_config::isolate();
+        let cmd = parse_cmd("generate 16 -no-symbols -min-digits:2 -min-upper:1").unwrap();
+        let params = PasswordParams {
+            length: 16,
+            digits: ClassPolicy { enabled: true, min: 2 },
+            uppercase: ClassPolicy { enabled: true, min: 1 },
+            symbols: ClassPolicy { enabled: false, min: 0 },
+            ..PasswordParams::default()
+        };
+        assert_eq!(cmd, Command::Generate(params, false));
+
+        let Command::Generate(params, _) = parse_cmd("generate 12 -symbols:!#$").unwrap() else { panic!() };
+        assert_eq!(params.symbol_set.as_deref(), Some("!#$"));
+
+        assert!(parse_cmd("generate 12 -symbols:ab").is_err());
+        assert!(parse_cmd("generate 12 -min-digits:x").is_err());
+        assert!(parse_cmd("generate 12 -no-emoji").is_err());
+    }
+
+    #[test]
+    fn parse_generate_and_store() {
+        mock_config::isolate();
+        let cmd = parse_cmd("vault add github -generate 24 -no-symbols -copy").unwrap();
+        let params = PasswordParams { length: 24, symbols: ClassPolicy { enabled: false, min: 0 }, ..PasswordParams::default() };
+        assert_eq!(cmd, Command::Vault(VaultCommand::Add("github".to_string(), Some(params), true)));
+
+        let cmd = parse_cmd("vault add github").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Add("github".to_string(), None, false)));
+
+        let cmd = parse_cmd("vault update github -password -generate").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Regenerate("github".to_string(), PasswordParams::default(), false)));
+
+        let cmd = parse_cmd("vault update github -generate -no-symbols -password -copy").unwrap();
+        let params = PasswordParams { symbols: ClassPolicy { enabled: false, min: 0 }, ..PasswordParams::default() };
+        assert_eq!(cmd, Command::Vault(VaultCommand::Regenerate("github".to_string(), params, true)));
+
+        assert!(parse_cmd("vault update github -username -generate").is_err());
+        assert!(parse_cmd("vault update github -generate -username").is_err());
+        assert!(parse_cmd("vault add github -generate long").is_err());
+    }
+
+    #[test]
+    fn parse_analyze_breaches() {
+        mock_config::isolate();
+        let cmd = parse_cmd("analyze hunter2 -breaches hashes.txt").unwrap();
+        assert_eq!(cmd, Command::Analyze("hunter2".to_string(), Some("hashes.txt".to_string())));
+
+        assert!(parse_cmd("analyze hunter2 -breaches").is_err());
+        assert!(parse_cmd("analyze -breaches hashes.txt").is_err());
+    }
+
+    #[test]
+    fn parse_config() {
+        mock_config::isolate();
+        assert_eq!(parse_cmd("config").unwrap(), Command::Config(ConfigCommand::Show));
+        assert_eq!(parse_cmd("config get session_ttl").unwrap(), Command::Config(ConfigCommand::Get("session_ttl".to_string())));
+
+        let cmd = parse_cmd("config set generator_policy -no-symbols -min-digits:2").unwrap();
+        let set = ConfigCommand::Set("generator_policy".to_string(), "-no-symbols -min-digits:2".to_string());
+        assert_eq!(cmd, Command::Config(set));
+
+        let cmd = parse_cmd("config unset vault_dir").unwrap();
+        assert_eq!(cmd, Command::Config(ConfigCommand::Set("vault_dir".to_string(), String::new())));
+
+        assert!(parse_cmd("config set session_ttl").is_err());
+        assert!(parse_cmd("config drop session_ttl").is_err());
+    }
+
+    #[test]
+    fn parse_agent() {
+        mock_config::isolate();
+        assert_eq!(parse_cmd("agent").unwrap(), Command::Agent(AgentCommand::List));
+        assert_eq!(parse_cmd("agent add").unwrap(), Command::Agent(AgentCommand::Add(None)));
+        assert_eq!(parse_cmd("agent lock work").unwrap(), Command::Agent(AgentCommand::Lock(Some("work".to_string()))));
+        assert!(parse_cmd("agent kill").is_err());
+
+        let cmd = parse_cmd("vault get github -username").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Get("github".to_string(), Field::Username)));
+    }
+
+    #[test]
+    fn parse_vault_locations() {
+        mock_config::isolate();
+        let cmd = parse_cmd("vault open work").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Open("work".to_string(), None)));
+
+        let cmd = parse_cmd("vault new /mnt/share/team.vault").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::New("team".to_string(), Some("/mnt/share/team.vault".to_string()))));
+
+        let cmd = parse_cmd("vault move team ../repo").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Move("team".to_string(), "../repo".to_string())));
+
+        let cmd = parse_cmd("vault rename team ops").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Rename("team".to_string(), "ops".to_string())));
+
+        let cmd = parse_cmd("vault open sqlite:/srv/vaults.db#ops").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::Open("ops".to_string(), Some("sqlite:/srv/vaults.db#ops".to_string()))));
+
+        let cmd = parse_cmd("vault new https://dav.example.com/vaults/team.vault").unwrap();
+        assert_eq!(cmd, Command::Vault(VaultCommand::New("team".to_string(), Some("https://dav.example.com/vaults/team.vault".to_string()))));
+
+        assert!(parse_cmd("vault open ../etc/passwd").is_err());
+        assert!(parse_cmd("vault open sqlite:/srv/vaults.db").is_err());
+        assert!(parse_cmd("vault open https://dav.example.com/vaults/").is_err());
+        assert!(parse_cmd("vault new /tmp/bad name!.vault").is_err());
+        assert!(parse_cmd("vault rename team").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+#[cfg(test)]
+mod test_password_service {
+    use std::collections::HashSet;
+    use passman::domain::cli::passphrase_params::PassphraseParams;
+    use passman::domain::cli::password_params::{ClassPolicy, PasswordParams};
+    use passman::services::password_service::PasswordService;
+    use passman::utils::constants::{PASSPHRASE_SYMBOL_CHARS, PASSWORD_MAX_LEN, PASSWORD_MIN_LEN, WORDS};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn params(words: usize) -> PassphraseParams {
+        PassphraseParams {
+            words,
+            separator: "-".to_string(),
+            capitalize: false,
+            digit: false,
+            symbol: false,
+        }
+    }
+
+    // pearson's chi-squared statistic against a uniform distribution
+    fn chi_squared(counts: &[u32]) -> f64 {
+        let total: u32 = counts.iter().sum();
+        let expected = total as f64 / counts.len() as f64;
+        counts.iter().map(|&c| (c as f64 - expected).powi(2) / expected).sum()
+    }
+
+    #[test]
+    fn wordlist_is_unique() {
+        let unique: HashSet<&&str> = WORDS.iter().collect();
+        assert_eq!(unique.len(), WORDS.len());
+        assert_eq!(WORDS.len(), 2048);
+        assert!(WORDS.iter().all(|w| w.len() >= 3 && w.chars().all(|c| c.is_ascii_lowercase())));
+    }
+
+    #[test]
+    fn passphrase_shape() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (passphrase, entropy) = PasswordService::passphrase_with(&params(6), &mut rng).unwrap();
+        let words: Vec<&str> = passphrase.split('-').collect();
+        assert_eq!(words.len(), 6);
+        assert!(words.iter().all(|w| WORDS.contains(w)));
+        assert_eq!(entropy, 66.0);
+
+        let options = PassphraseParams { capitalize: true, digit: true, symbol: true, separator: ".".to_string(), ..params(4) };
+        let (passphrase, entropy) = PasswordService::passphrase_with(&options, &mut rng).unwrap();
+        assert!(passphrase.split('.').all(|w| w.starts_with(|c: char| c.is_ascii_uppercase())));
+        assert!(passphrase.chars().any(|c| c.is_ascii_digit()));
+        assert!(passphrase.chars().any(|c| PASSPHRASE_SYMBOL_CHARS.contains(c)));
+        assert!(entropy > 44.0 + 3.3 + 3.5);
+
+        assert!(PasswordService::passphrase(&params(2)).is_err());
+        assert!(PasswordService::passphrase(&params(21)).is_err());
+    }
+
+    #[test]
+    fn passphrase_word_distribution() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let index: std::collections::HashMap<&str, usize> = WORDS.iter().enumerate().map(|(i, w)| (*w, i)).collect();
+        let mut counts = vec![0u32; WORDS.len()];
+        for _ in 0..20_000 {
+            let (passphrase, _) = PasswordService::passphrase_with(&params(5), &mut rng).unwrap();
+            for word in passphrase.split('-') {
+                counts[index[word]] += 1;
+            }
+        }
+        // 2047 degrees of freedom, so the statistic stays well below 2047 + 6 standard deviations
+        assert!(chi_squared(&counts) < 2047.0 + 6.0 * (2.0 * 2047.0_f64).sqrt());
+        assert!(counts.iter().all(|&c| c > 0));
+    }
+
+    #[test]
+    fn passphrase_digit_distribution() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut counts = [0u32; 10];
+        for _ in 0..10_000 {
+            let (passphrase, _) = PasswordService::passphrase_with(&PassphraseParams { digit: true, ..params(3) }, &mut rng).unwrap();
+            let digit = passphrase.chars().find(|c| c.is_ascii_digit()).unwrap();
+            counts[digit.to_digit(10).unwrap() as usize] += 1;
+        }
+        // 9 degrees of freedom, critical value at p = 0.001
+        assert!(chi_squared(&counts) < 27.88);
+    }
+
+    #[test]
+    fn password_policy_constraints() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let params = PasswordParams {
+            length: 12,
+            digits: ClassPolicy { enabled: true, min: 3 },
+            symbols: ClassPolicy { enabled: false, min: 0 },
+            uppercase: ClassPolicy { enabled: true, min: 1 },
+            ..PasswordParams::default()
+        };
+        for _ in 0..500 {
+            let password = PasswordService::generate_with(&params, &mut rng).unwrap();
+            assert_eq!(password.len(), 12);
+            assert!(password.chars().filter(|c| c.is_ascii_digit()).count() >= 3);
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric()));
+        }
+
+        let custom = PasswordParams { symbol_set: Some("#!".to_string()), symbols: ClassPolicy { enabled: true, min: 4 }, ..PasswordParams::default() };
+        let password = PasswordService::generate_with(&custom, &mut rng).unwrap();
+        assert!(password.chars().filter(|c| !c.is_ascii_alphanumeric()).all(|c| c == '#' || c == '!'));
+
+        let impossible = PasswordParams { digits: ClassPolicy { enabled: true, min: 9 }, ..PasswordParams::default() };
+        assert!(PasswordService::generate(impossible).is_err());
+        let excluded = PasswordParams { digits: ClassPolicy { enabled: false, min: 1 }, ..PasswordParams::default() };
+        assert!(PasswordService::generate(excluded).is_err());
+    }
+
+    #[test]
+    fn password_length_bounds() {
+        let shortest = PasswordParams { length: PASSWORD_MIN_LEN, ..PasswordParams::default() };
+        assert_eq!(PasswordService::generate(shortest).unwrap().len(), PASSWORD_MIN_LEN);
+        let longest = PasswordParams { length: PASSWORD_MAX_LEN, ..PasswordParams::default() };
+        assert_eq!(PasswordService::generate(longest).unwrap().len(), PASSWORD_MAX_LEN);
+
+        let too_short = PasswordParams { length: PASSWORD_MIN_LEN - 1, ..PasswordParams::default() };
+        assert!(PasswordService::generate(too_short).is_err());
+        let too_long = PasswordParams { length: PASSWORD_MAX_LEN + 1, ..PasswordParams::default() };
+        assert!(PasswordService::generate(too_long).is_err());
+    }
+
+    #[test]
+    fn password_policy_distribution() {
+        // lowercase plus a single required symbol, so the exact distribution is known
+        let mut rng = StdRng::seed_from_u64(11);
+        let params = PasswordParams {
+            uppercase: ClassPolicy { enabled: false, min: 0 },
+            digits: ClassPolicy { enabled: false, min: 0 },
+            symbols: ClassPolicy { enabled: true, min: 1 },
+            symbol_set: Some("!".to_string()),
+            ..PasswordParams::default()
+        };
+        let samples = 20_000;
+        let mut single = 0;
+        let mut positions = [0u32; 8];
+        for _ in 0..samples {
+            let password = PasswordService::generate_with(&params, &mut rng).unwrap();
+            if password.matches('!').count() == 1 {
+                single += 1;
+                positions[password.find('!').unwrap()] += 1;
+            }
+        }
+        // among passwords with at least one symbol, 8 * 26^7 / (27^8 - 26^8) have exactly one
+        let expected = 8.0 * 26f64.powi(7) / (27f64.powi(8) - 26f64.powi(8));
+        let observed = single as f64 / samples as f64;
+        assert!((observed - expected).abs() < 0.01, "{} vs {}", observed, expected);
+        // 7 degrees of freedom, critical value at p = 0.001
+        assert!(chi_squared(&positions) < 24.32);
+    }
+}
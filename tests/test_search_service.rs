@@ -0,0 +1,76 @@
+#[cfg(test)]
+mod test_search_service {
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::services::search_service::SearchService;
+
+    #[test]
+    fn score_matches() {
+        let exact = SearchService::score("github", "github").unwrap();
+        let prefix = SearchService::score("git", "github").unwrap();
+        let substring = SearchService::score("hub", "github").unwrap();
+        let fuzzy = SearchService::score("gh", "github").unwrap();
+        assert!(exact > prefix && prefix > substring && substring > fuzzy);
+
+        // case insensitive
+        assert_eq!(SearchService::score("GitHub", "github"), Some(exact));
+
+        // no match
+        assert!(SearchService::score("xyz", "github").is_none());
+        assert!(SearchService::score("hg", "github").is_none());
+        assert!(SearchService::score("", "github").is_none());
+    }
+
+    #[test]
+    fn rank_entries() {
+        let mut gitlab = PasswordEntry::new("gitlab", "dev", "pass");
+        gitlab.tags = vec!["work".to_string()];
+        let mut github = PasswordEntry::new("github.com", "dev", "pass");
+        github.url = Some("https://github.com".to_string());
+        let mail = PasswordEntry::new("mail", "github-bot", "pass");
+        let entries = vec![gitlab, github, mail];
+
+        // name matches outrank username matches
+        let ranked = SearchService::rank("github", &entries);
+        let names: Vec<&str> = ranked.iter().map(|(e, _)| e.name.as_str()).collect();
+        assert_eq!(names, vec!["github.com", "mail"]);
+
+        let ranked = SearchService::rank("gthb", &entries);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].0.name, "github.com");
+
+        let ranked = SearchService::rank("work", &entries);
+        assert_eq!(ranked[0].0.name, "gitlab");
+    }
+
+    #[test]
+    fn resolve_entries() {
+        let mut work = PasswordEntry::new("work-mail", "dev", "pass");
+        work.url = Some("https://github.com/login".to_string());
+        let github = PasswordEntry::new("github", "dev", "pass");
+        let github_bot = PasswordEntry::new("github-bot", "dev", "pass");
+        let gitlab = PasswordEntry::new("gitlab", "github-user", "pass");
+        let mail = PasswordEntry::new("mail", "dev", "pass");
+        let entries = vec![work, github, github_bot, gitlab, mail];
+        let names = |query: &str| SearchService::resolve(query, &entries)
+            .iter()
+            .map(|e| e.name.clone())
+            .collect::<Vec<String>>();
+
+        // an exact name wins over matches on urls and usernames
+        assert_eq!(names("github"), vec!["github"]);
+
+        // a unique name match needs no choice, several always do
+        assert_eq!(names("gitl"), vec!["gitlab"]);
+        assert_eq!(names("github-b"), vec!["github-bot"]);
+        assert_eq!(names("gith"), vec!["github", "github-bot"]);
+
+        // other fields only count when no name matches
+        assert_eq!(names("login"), vec!["work-mail"]);
+
+        // close scores still offer every candidate
+        let entries = vec![PasswordEntry::new("aws-staging", "dev", "pass"), PasswordEntry::new("aws-prod", "dev", "pass")];
+        let matches: Vec<&str> = SearchService::resolve("aws", &entries).iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(matches, vec!["aws-prod", "aws-staging"]);
+        assert!(SearchService::resolve("xyz", &entries).is_empty());
+    }
+}
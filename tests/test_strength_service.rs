@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod test_strength_service {
+    use passman::domain::vault::strength_estimate::{Dictionary, Pattern};
+    use passman::services::strength_service::StrengthService;
+
+    fn patterns(password: &str) -> Vec<Pattern> {
+        StrengthService::estimate(password, &[]).sequence.into_iter().map(|m| m.pattern).collect()
+    }
+
+    #[test]
+    fn detects_patterns() {
+        assert!(matches!(&patterns("password")[0], Pattern::Dictionary { dictionary: Dictionary::Passwords, rank: 2, .. }));
+        assert!(matches!(&patterns("drowssap")[0], Pattern::Dictionary { reversed: true, .. }));
+        assert!(matches!(&patterns("p4ssw0rd")[0], Pattern::Dictionary { l33t: true, .. }));
+        assert!(matches!(&patterns("zaqwsxcde")[0], Pattern::Spatial { turns: 5, .. }));
+        assert!(matches!(&patterns("aaaaaaaa")[0], Pattern::Repeat { count: 8, .. }));
+        assert!(matches!(&patterns("abcdefg")[0], Pattern::Sequence { ascending: true }));
+        assert!(matches!(&patterns("13/05/1987")[0], Pattern::Date { year: 1987, separator: true }));
+        assert_eq!(patterns("summer1987").last(), Some(&Pattern::Year));
+    }
+
+    #[test]
+    fn scores_passwords() {
+        let score = |password: &str| StrengthService::estimate(password, &[]).score;
+        assert_eq!(score(""), 0);
+        assert_eq!(score("password"), 0);
+        assert!(score("Password123!") <= 1);
+        assert!(score("qwerty123") <= 1);
+        assert!(score("correct-horse-battery-staple") >= 3);
+        assert_eq!(score("vR7#qL!2zX@9mK$w"), 4);
+    }
+
+    #[test]
+    fn user_inputs_and_feedback() {
+        let estimate = StrengthService::estimate("octocat2024", &["octocat@example.com"]);
+        assert!(matches!(&estimate.sequence[0].pattern, Pattern::Dictionary { dictionary: Dictionary::UserInputs, .. }));
+        assert!(estimate.score <= 1);
+        assert_eq!(estimate.warning.as_deref(), Some("This is similar to the entry name or username"));
+
+        let estimate = StrengthService::estimate("password", &[]);
+        assert_eq!(estimate.warning.as_deref(), Some("This is a top-10 common password"));
+        assert!(!estimate.suggestions.is_empty());
+
+        // crack times shrink as the attacker gets faster
+        let times = StrengthService::estimate("Tr0ub4dor&3", &[]).crack_times();
+        assert!(times.windows(2).all(|pair| pair[0].1 > pair[1].1));
+    }
+}
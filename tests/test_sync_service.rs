@@ -0,0 +1,153 @@
+#[cfg(test)]
+mod test_sync_service {
+    use std::fs;
+    use std::path::Path;
+    use std::process::Command;
+    use chrono::{Duration, Utc};
+    use secrecy::{ExposeSecret, SecretBox};
+    use tempfile::tempdir;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::sync_report::Resolution;
+    use passman::domain::vault::vault::Vault;
+    use passman::repository::vault::vault_crypto::VaultCrypto;
+    use passman::repository::vault::vault_manager::VaultManager;
+    use passman::repository::vault::vault_storage::VaultStorage;
+    use passman::services::sync_service::SyncService;
+
+    fn entry(id: &str, name: &str, password: &str) -> PasswordEntry {
+        let mut entry = PasswordEntry::new(name, "alice", password);
+        entry.id = id.to_string();
+        entry
+    }
+
+    fn vault(entries: Vec<PasswordEntry>) -> Vault {
+        Vault::new(entries)
+    }
+
+    fn passwords(vault: &Vault) -> Vec<(String, String)> {
+        let mut passwords: Vec<(String, String)> = vault.entries.iter()
+            .map(|e| (e.name.clone(), e.password.expose_secret().clone()))
+            .collect();
+        passwords.sort();
+        passwords
+    }
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git").arg("-C").arg(dir).args(args).output().unwrap().status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    fn clone(remote: &Path, dir: &Path) {
+        git(remote.parent().unwrap(), &["clone", "-q", remote.to_str().unwrap(), dir.to_str().unwrap()]);
+        git(dir, &["config", "user.name", "passman"]);
+        git(dir, &["config", "user.email", "passman@example.com"]);
+        git(dir, &["config", "commit.gpgsign", "false"]);
+    }
+
+    fn write(path: &Path, vault: &Vault, secret: &SecretBox<String>) {
+        VaultStorage::write(path, &VaultManager::encode(vault, secret).unwrap(), 0).unwrap();
+    }
+
+    fn read(path: &Path, secret: &SecretBox<String>) -> Vault {
+        let mut vault = VaultManager::decode(&fs::read(path).unwrap(), secret).unwrap();
+        VaultCrypto::unseal_all(&mut vault).unwrap();
+        vault
+    }
+
+    #[test]
+    fn merge_takes_non_conflicting_changes_from_both_sides() {
+        let base = vault(vec![entry("1", "github", "a"), entry("2", "mail", "b"), entry("3", "forum", "c")]);
+        let local = vault(vec![entry("1", "github", "a"), entry("2", "mail", "local"), entry("4", "bank", "d")]);
+        let remote = vault(vec![entry("1", "github", "remote"), entry("2", "mail", "b"), entry("3", "forum", "c")]);
+
+        let (merged, report) = SyncService::merge(base, local, remote, |_| panic!("no conflicts expected")).unwrap();
+
+        // check result
+        let expected = [("bank", "d"), ("github", "remote"), ("mail", "local")];
+        assert_eq!(passwords(&merged), expected.map(|(n, p)| (n.to_string(), p.to_string())));
+        assert_eq!((report.added, report.updated, report.deleted, report.conflicts), (0, 1, 0, 0));
+    }
+
+    #[test]
+    fn merge_resolves_conflicts() {
+        let mut newer = entry("1", "github", "remote");
+        newer.updated_at = Some(Utc::now());
+        let mut older = entry("1", "github", "local");
+        older.updated_at = Some(Utc::now() - Duration::days(1));
+        let base = vault(vec![entry("1", "github", "a"), entry("2", "mail", "b")]);
+        let local = vault(vec![older, entry("2", "mail", "edited")]);
+        let remote = vault(vec![newer, entry("5", "mail", "added")]);
+
+        // edit against deletion is a conflict too, keep both copies of everything
+        let mut conflicts = Vec::new();
+        let (merged, report) = SyncService::merge(base, local, remote, |conflict| {
+            conflicts.push((conflict.name().to_string(), conflict.newer()));
+            Ok(Resolution::Both)
+        }).unwrap();
+
+        // check result
+        assert_eq!(conflicts, vec![("github".to_string(), Resolution::Remote), ("mail".to_string(), Resolution::Local)]);
+        assert_eq!(report.conflicts, 2);
+        let names: Vec<&str> = merged.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["github", "github_2", "mail", "mail_2"]);
+        assert_eq!(report.renamed, vec!["github_2".to_string(), "mail_2".to_string()]);
+        assert_ne!(merged.entries[0].id, merged.entries[1].id);
+    }
+
+    #[test]
+    fn merge_keeps_entries_sharing_an_id() {
+        let base = vault(vec![entry("1", "github", "a")]);
+        let local = vault(vec![entry("1", "github", "a"), entry("1", "github_2", "b")]);
+        let remote = vault(vec![entry("1", "github", "a"), entry("2", "mail", "c")]);
+
+        let (merged, report) = SyncService::merge(base, local, remote, |_| panic!("no conflicts expected")).unwrap();
+
+        // check result
+        let expected = [("github", "a"), ("github_2", "b"), ("mail", "c")];
+        assert_eq!(passwords(&merged), expected.map(|(n, p)| (n.to_string(), p.to_string())));
+        assert_eq!(report.added, 1);
+
+        // every copy derives the same replacement id
+        let mut again = vault(vec![entry("1", "github", "a"), entry("1", "github_2", "b")]);
+        again.assign_ids();
+        assert!(merged.entries.iter().any(|e| e.id == again.entries[1].id));
+        assert_ne!(again.entries[0].id, again.entries[1].id);
+    }
+
+    #[test]
+    fn sync_merges_diverged_clones() {
+        let dir = tempdir().unwrap();
+        let remote = dir.path().join("remote.git");
+        git(dir.path(), &["init", "-q", "--bare", "-b", "main", remote.to_str().unwrap()]);
+        let (a, b) = (dir.path().join("a"), dir.path().join("b"));
+        let secret = SecretBox::new(Box::new("correct horse battery".to_string()));
+
+        // first clone publishes the vault
+        clone(&remote, &a);
+        let vault_a = a.join("team.vault");
+        write(&vault_a, &vault(vec![entry("1", "github", "a"), entry("2", "mail", "b"), entry("3", "forum", "c")]), &secret);
+        let report = SyncService::sync(&vault_a, &secret, |_| panic!("no conflicts expected")).unwrap();
+        assert!(report.committed && report.pushed);
+
+        // both clones edit different entries
+        clone(&remote, &b);
+        let vault_b = b.join("team.vault");
+        write(&vault_a, &vault(vec![entry("1", "github", "a"), entry("2", "mail", "local"), entry("4", "bank", "d")]), &secret);
+        SyncService::sync(&vault_a, &secret, |_| panic!("no conflicts expected")).unwrap();
+        write(&vault_b, &vault(vec![entry("1", "github", "remote"), entry("2", "mail", "b"), entry("3", "forum", "c")]), &secret);
+        let report = SyncService::sync(&vault_b, &secret, |_| panic!("no conflicts expected")).unwrap();
+        assert!(report.merge.is_some() && report.pushed);
+        assert!(VaultStorage::backups(&vault_b).unwrap().is_empty());
+
+        // the first clone fast-forwards to the merge
+        let report = SyncService::sync(&vault_a, &secret, |_| panic!("no conflicts expected")).unwrap();
+        assert!(report.fast_forwarded && !report.pushed);
+        assert_eq!(passwords(&read(&vault_a, &secret)), passwords(&read(&vault_b, &secret)));
+        let expected = [("bank", "d"), ("github", "remote"), ("mail", "local")];
+        assert_eq!(passwords(&read(&vault_a, &secret)), expected.map(|(n, p)| (n.to_string(), p.to_string())));
+
+        // nothing left to do
+        let report = SyncService::sync(&vault_a, &secret, |_| panic!("no conflicts expected")).unwrap();
+        assert_eq!(report.to_string(), "Already up to date");
+    }
+}
@@ -0,0 +1,199 @@
+#[cfg(test)]
+mod mocks {
+    pub mod mock_config;
+}
+
+#[cfg(test)]
+mod test_vault_backends {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use secrecy::SecretBox;
+    use tempfile::tempdir;
+    use tiny_http::{Response, Server};
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::vault::Vault;
+    use passman::domain::vault::vault_error::VaultError;
+    use passman::domain::vault::vault_locator::VaultLocator;
+    use passman::repository::vault::vault_http::VaultHttp;
+    use passman::repository::vault::vault_manager_trait::VaultManagerTrait;
+    use passman::repository::vault::vault_sqlite::VaultSqlite;
+    use passman::services::config_service::ConfigService;
+    use crate::mocks::mock_config;
+
+    type Files = Arc<Mutex<HashMap<String, Vec<u8>>>>;
+
+    // a stand-in for a WebDAV server, keeping files in memory. without webdav it answers
+    // PROPFIND like a plain object store, with auth it wants that Authorization header
+    fn serve(webdav: bool, auth: Option<&'static str>) -> (String, Files) {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let port = server.server_addr().to_ip().unwrap().port();
+        let stored: Files = Arc::new(Mutex::new(HashMap::new()));
+        let shared = stored.clone();
+        thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let path = request.url().to_string();
+                let authorized = auth.is_none_or(|auth| request.headers().iter()
+                    .any(|header| header.field.equiv("Authorization") && header.value.as_str() == auth));
+                let mut files = shared.lock().unwrap();
+                let response = match request.method().as_str() {
+                    _ if !authorized => Response::from_data(Vec::new()).with_status_code(401),
+                    "GET" | "HEAD" => match files.get(&path) {
+                        Some(data) => Response::from_data(data.clone()),
+                        None => Response::from_data(Vec::new()).with_status_code(404),
+                    },
+                    "PUT" => {
+                        let mut data = Vec::new();
+                        request.as_reader().read_to_end(&mut data).unwrap();
+                        files.insert(path, data);
+                        Response::from_data(Vec::new()).with_status_code(201)
+                    }
+                    "DELETE" => match files.remove(&path) {
+                        Some(_) => Response::from_data(Vec::new()).with_status_code(204),
+                        None => Response::from_data(Vec::new()).with_status_code(404),
+                    },
+                    "PROPFIND" if webdav => {
+                        let hrefs: String = files.keys()
+                            .filter(|file| file.starts_with(&path))
+                            .map(|file| format!("<d:response><d:href>{}</d:href></d:response>", file))
+                            .collect();
+                        let body = format!(
+                            r#"<?xml version="1.0"?><d:multistatus xmlns:d="DAV:"><d:response><d:href>{}</d:href></d:response>{}</d:multistatus>"#,
+                            path, hrefs
+                        );
+                        Response::from_string(body).with_status_code(207)
+                    }
+                    _ => Response::from_data(Vec::new()).with_status_code(405),
+                };
+                drop(files);
+                let _ = request.respond(response);
+            }
+        });
+        (format!("http://127.0.0.1:{}/dav/", port), stored)
+    }
+
+    // the same lifecycle against any store
+    fn exercise(store: &dyn VaultManagerTrait) {
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        let wrong = SecretBox::new(Box::new("wrong_password".to_string()));
+        store.create("work", &secret).unwrap();
+        assert!(store.exists("work").unwrap());
+        assert!(!store.exists("home").unwrap());
+
+        // save over the empty vault
+        let vault = Vault::new(vec![PasswordEntry::new("github", "alice", "hunter22")]);
+        store.save("work", &secret, &vault).unwrap();
+        assert_eq!(store.load("work", &secret).unwrap().entries[0].name, "github");
+        assert!(matches!(store.load("work", &wrong), Err(VaultError::WrongPassword)));
+
+        // restore the empty vault, keeping the current one as a backup
+        assert_eq!(store.backups("work").unwrap().len(), 1);
+        store.restore("work", 0).unwrap();
+        assert!(store.load("work", &secret).unwrap().entries.is_empty());
+        assert_eq!(store.backups("work").unwrap().len(), 2);
+
+        // rename with backups
+        store.rename("work", "ops").unwrap();
+        assert!(!store.exists("work").unwrap());
+        assert_eq!(store.backups("ops").unwrap().len(), 2);
+        assert_eq!(store.list().unwrap().into_iter().map(|(name, _)| name).collect::<Vec<_>>(), vec!["ops"]);
+        assert!(store.file_path("ops").is_err());
+
+        // delete
+        store.delete("ops").unwrap();
+        assert!(matches!(store.load("ops", &secret), Err(VaultError::NotFound(_))));
+        assert!(store.backups("ops").unwrap().is_empty());
+        assert!(store.list().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_sqlite_store() {
+        mock_config::isolate();
+        let dir = tempdir().unwrap();
+        let database = dir.path().join("vaults.db");
+        let store = VaultSqlite::open(&database).unwrap();
+        exercise(&store);
+
+        // check result
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        store.create("home", &secret).unwrap();
+        assert_eq!(store.list().unwrap()[0].1, format!("sqlite:{}#home", database.display()));
+    }
+
+    #[test]
+    fn test_http_store() {
+        mock_config::isolate();
+        let (base, _) = serve(true, None);
+        let store = VaultHttp::new(&base);
+        exercise(&store);
+
+        // check result
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        store.create("home", &secret).unwrap();
+        assert_eq!(store.list().unwrap()[0].1, format!("{}home.vault", base));
+    }
+
+    #[test]
+    fn test_http_store_without_listing() {
+        mock_config::isolate();
+        let (base, files) = serve(false, None);
+        let store = VaultHttp::new(&base);
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+
+        // backups could not be found again, so none are made
+        store.create("work", &secret).unwrap();
+        for _ in 0..3 {
+            store.save("work", &secret, &Vault::new(Vec::new())).unwrap();
+        }
+        assert!(store.backups("work").unwrap().is_empty());
+        assert_eq!(files.lock().unwrap().len(), 1);
+
+        // check result
+        store.delete("work").unwrap();
+        assert!(files.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_http_store_credentials() {
+        mock_config::isolate();
+        // alice:secret
+        let (base, _) = serve(true, Some("Basic YWxpY2U6c2VjcmV0"));
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        assert!(matches!(VaultHttp::new(&base).create("work", &secret), Err(VaultError::Store(_))));
+
+        ConfigService::set("store_user", "alice").unwrap();
+        ConfigService::set("store_password", "secret").unwrap();
+
+        // check result
+        let store = VaultHttp::new(&base);
+        store.create("work", &secret).unwrap();
+        assert!(store.load("work", &secret).is_ok());
+        assert_eq!(store.list().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_locators() {
+        let locator = VaultLocator::parse("sqlite:/srv/vaults.db#work", "other").unwrap();
+        assert_eq!(locator, VaultLocator::Sqlite { database: PathBuf::from("/srv/vaults.db"), name: "work".to_string() });
+        assert_eq!(locator.to_string(), "sqlite:/srv/vaults.db#work");
+
+        // a store gets the vault name appended
+        let locator = VaultLocator::parse("https://dav.example.com/vaults/", "team").unwrap();
+        assert_eq!(locator.to_string(), "https://dav.example.com/vaults/team.vault");
+        assert_eq!(VaultLocator::parse(&locator.to_string(), "other").unwrap(), locator);
+        assert_eq!(locator.renamed("ops").to_string(), "https://dav.example.com/vaults/ops.vault");
+        assert!(VaultLocator::parse("https://dav.example.com/vaults/", "").unwrap().is_store());
+
+        assert!(matches!(VaultLocator::parse("/srv/team.vault", "team").unwrap(), VaultLocator::File(_)));
+        assert!(VaultLocator::parse("https://dav.example.com/vaults/team.txt", "team").is_err());
+        assert!(VaultLocator::parse("https://", "team").is_err());
+
+        // plain http only to this machine
+        assert!(matches!(VaultLocator::parse("http://dav.example.com/vaults/", "team"), Err(VaultError::Unsupported(_))));
+        assert!(VaultLocator::parse("http://user@dav.example.com:8080/vaults/", "team").is_err());
+        assert!(VaultLocator::parse("http://localhost:8080/vaults/", "team").is_ok());
+        assert!(VaultLocator::parse("http://127.0.0.1/vaults/team.vault", "").is_ok());
+        assert!(VaultLocator::parse("http://[::1]:8080/vaults/", "team").is_ok());
+    }
+}
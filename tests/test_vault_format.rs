@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod test_vault_format {
+    use passman::domain::vault::custom_field::CustomField;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::vault::Vault;
+    use passman::domain::vault::vault_error::VaultError;
+    use passman::domain::vault::vault_file::{VaultFile, VaultHeader};
+    use passman::repository::vault::vault_crypto::VaultCrypto;
+    use passman::repository::vault::vault_manager::VaultManager;
+    use secrecy::{ExposeSecret, SecretBox};
+
+    fn setup() -> (Vault, SecretBox<String>) {
+        let entry = PasswordEntry::new("github", "user", "pass");
+        let vault = Vault::new(vec![entry]);
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        (vault, secret)
+    }
+
+    fn encode(vault: &Vault, secret: &SecretBox<String>, header: VaultHeader, store_header: bool) -> Vec<u8> {
+        let (salt, nonce, ciphertext) = VaultCrypto::encrypt(vault, secret, &header).unwrap();
+        let header = if store_header { Some(header) } else { None };
+        serde_json::to_vec(&VaultFile { header, salt, nonce, ciphertext, entries: Vec::new() }).unwrap()
+    }
+
+    #[test]
+    fn test_blob_format_loads() {
+        let (vault, secret) = setup();
+        let data = encode(&vault, &secret, VaultHeader::blob(), true);
+
+        let loaded = VaultManager::decode(&data, &secret).unwrap();
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].password.expose_secret(), "pass");
+    }
+
+    #[test]
+    fn test_legacy_format_loads() {
+        let (vault, secret) = setup();
+        let data = encode(&vault, &secret, VaultHeader::legacy(), false);
+
+        let loaded = VaultManager::decode(&data, &secret).unwrap();
+        assert_eq!(loaded.entries[0].name, "github");
+    }
+
+    #[test]
+    fn test_entries_are_sealed_separately() {
+        let (mut vault, secret) = setup();
+        let entry = &mut vault.entries[0];
+        entry.otp = Some(SecretBox::new(Box::new("JBSWY3DPEHPK3PXP".to_string())));
+        entry.fields.push(CustomField { name: "pin".to_string(), value: SecretBox::new(Box::new("1234".to_string())), secret: true });
+        entry.fields.push(CustomField { name: "team".to_string(), value: SecretBox::new(Box::new("ops".to_string())), secret: false });
+        entry.record_version();
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+
+        // metadata loads on its own, secrets only once unsealed
+        let mut loaded = VaultManager::decode(&data, &secret).unwrap();
+        let entry = &loaded.entries[0];
+        assert!(entry.sealed.is_some());
+        assert_eq!((entry.name.as_str(), entry.username.as_str()), ("github", "user"));
+        assert_eq!(entry.password.expose_secret(), "");
+        assert_eq!(entry.field("team").unwrap().value.expose_secret(), "ops");
+        assert_eq!(entry.field("pin").unwrap().value.expose_secret(), "");
+        assert!(entry.history.is_empty());
+
+        // an untouched entry keeps its sealed secrets when saved again
+        loaded.entries[0].url = Some("https://github.com".to_string());
+        let resaved = VaultManager::encode(&loaded, &secret).unwrap();
+        let secrets = |data: &[u8]| serde_json::from_slice::<VaultFile>(data).unwrap().entries[0].secrets.clone();
+        assert_eq!(secrets(&resaved), secrets(&data));
+
+        // check result
+        let mut loaded = VaultManager::decode(&resaved, &secret).unwrap();
+        VaultCrypto::unseal(&mut loaded, 0).unwrap();
+        let entry = &loaded.entries[0];
+        assert!(entry.sealed.is_none());
+        assert_eq!(entry.url.as_deref(), Some("https://github.com"));
+        assert_eq!(entry.password.expose_secret(), "pass");
+        assert_eq!(entry.otp.as_ref().unwrap().expose_secret(), "JBSWY3DPEHPK3PXP");
+        assert_eq!(entry.field("pin").unwrap().value.expose_secret(), "1234");
+        assert_eq!(entry.history.len(), 1);
+    }
+
+    #[test]
+    fn test_secret_fields_keep_their_place() {
+        let (mut vault, secret) = setup();
+        for (name, value, secret) in [("pin", "1234", true), ("note", "plain", false), ("pin", "5678", true), ("", "nameless", true)] {
+            vault.entries[0].fields.push(CustomField { name: name.to_string(), value: SecretBox::new(Box::new(value.to_string())), secret });
+        }
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+
+        // check result
+        let mut loaded = VaultManager::decode(&data, &secret).unwrap();
+        VaultCrypto::unseal(&mut loaded, 0).unwrap();
+        let values: Vec<&str> = loaded.entries[0].fields.iter().map(|field| field.value.expose_secret().as_str()).collect();
+        assert_eq!(values, vec!["1234", "plain", "5678", "nameless"]);
+    }
+
+    #[test]
+    fn test_swapped_secrets_are_rejected() {
+        let (mut vault, secret) = setup();
+        vault.entries.push(PasswordEntry::new("mail", "user", "other"));
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+        let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+        let first = file.entries[0].secrets.clone();
+        file.entries[0].secrets = file.entries[1].secrets.clone();
+        file.entries[1].secrets = first;
+
+        assert!(VaultManager::decode(&serde_json::to_vec(&file).unwrap(), &secret).is_err());
+    }
+
+    #[test]
+    fn test_dropped_entry_is_rejected() {
+        let (mut vault, secret) = setup();
+        vault.entries.push(PasswordEntry::new("mail", "user", "other"));
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+        let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+        file.entries.remove(1);
+        assert!(VaultManager::decode(&serde_json::to_vec(&file).unwrap(), &secret).is_err());
+
+        // nor can one be duplicated
+        let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+        file.entries.push(file.entries[0].clone());
+        assert!(VaultManager::decode(&serde_json::to_vec(&file).unwrap(), &secret).is_err());
+    }
+
+    #[test]
+    fn test_entry_from_a_backup_is_rejected() {
+        let (mut vault, secret) = setup();
+        vault.entries.push(PasswordEntry::new("mail", "user", "old"));
+        let backup = VaultManager::encode(&vault, &secret).unwrap();
+
+        // the next save keeps the data key and changes one entry
+        let mut loaded = VaultManager::decode(&backup, &secret).unwrap();
+        VaultCrypto::unseal(&mut loaded, 1).unwrap();
+        loaded.entries[1].password = SecretBox::new(Box::new("new".to_string()));
+        let current = VaultManager::encode(&loaded, &secret).unwrap();
+        assert!(VaultManager::decode(&current, &secret).is_ok());
+
+        // check result
+        let old: VaultFile = serde_json::from_slice(&backup).unwrap();
+        let mut file: VaultFile = serde_json::from_slice(&current).unwrap();
+        file.entries[1] = old.entries[1].clone();
+        assert!(VaultManager::decode(&serde_json::to_vec(&file).unwrap(), &secret).is_err());
+    }
+
+    #[test]
+    fn test_tampered_header_is_rejected() {
+        let (vault, secret) = setup();
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+        let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+        file.header.as_mut().unwrap().t_cost += 1;
+        let tampered = serde_json::to_vec(&file).unwrap();
+
+        assert!(VaultManager::decode(&tampered, &secret).is_err());
+    }
+
+    #[test]
+    fn test_failures_are_typed() {
+        let (vault, secret) = setup();
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+        let wrong = SecretBox::new(Box::new("wrong_password".to_string()));
+
+        // check result
+        assert!(matches!(VaultManager::decode(&data, &wrong), Err(VaultError::WrongPassword)));
+        assert!(matches!(VaultManager::decode(b"not json", &secret), Err(VaultError::Corrupt(_))));
+        let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+        file.header.as_mut().unwrap().cipher = "rot13".to_string();
+        let data = serde_json::to_vec(&file).unwrap();
+        assert!(matches!(VaultManager::decode(&data, &secret), Err(VaultError::Unsupported(_))));
+    }
+
+    #[test]
+    fn test_excessive_kdf_costs_are_rejected() {
+        let (vault, secret) = setup();
+        let data = VaultManager::encode(&vault, &secret).unwrap();
+        for cost in [|h: &mut VaultHeader| h.m_cost = 1 << 24, |h: &mut VaultHeader| h.t_cost = 1000, |h: &mut VaultHeader| h.p_cost = 64] {
+            let mut file: VaultFile = serde_json::from_slice(&data).unwrap();
+            cost(file.header.as_mut().unwrap());
+            let data = serde_json::to_vec(&file).unwrap();
+            assert!(matches!(VaultManager::decode(&data, &secret), Err(VaultError::Unsupported(_))));
+        }
+    }
+
+    #[test]
+    fn test_unsupported_version_is_rejected() {
+        let (vault, secret) = setup();
+        let mut header = VaultHeader::current();
+        header.version = 99;
+        let data = encode(&vault, &secret, header, true);
+
+        let result = VaultManager::decode(&data, &secret);
+        assert!(matches!(result, Err(VaultError::UnsupportedVersion(99))));
+    }
+
+    #[test]
+    fn test_old_entry_shape_loads() {
+        let data = r#"{"entries":[{"name":"github","username":"user","password":"pass"}]}"#;
+
+        let vault: Vault = serde_json::from_str(data).unwrap();
+        let entry = &vault.entries[0];
+        assert_eq!(entry.password.expose_secret(), "pass");
+        assert!(entry.url.is_none() && entry.notes.is_none());
+        assert!(entry.tags.is_empty() && entry.fields.is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod mocks {
+    pub mod mock_config;
+}
+
+#[cfg(test)]
+mod test_vault_manager {
+    use secrecy::SecretBox;
+    use passman::domain::vault::vault_error::VaultError;
+    use passman::repository::vault::vault_manager::VaultManager;
+    use passman::repository::vault::vault_manager_trait::VaultManagerTrait;
+    use passman::repository::vault::vault_registry::VaultRegistry;
+    use passman::repository::vault::vault_storage::VaultStorage;
+    use passman::services::config_service::ConfigService;
+    use crate::mocks::mock_config;
+
+    // one test, the registry is shared by everything in this process
+    #[test]
+    fn test_register_relocate_rename() {
+        // setup
+        let dir = mock_config::isolate();
+        let secret = SecretBox::new(Box::new("test_password".to_string()));
+        let default = VaultManager.file_path("team").unwrap();
+        assert_eq!(default, dir.join("vaults").join("team.vault"));
+
+        // a vault created at a registered location
+        let shared = dir.join("shared").join("team.vault");
+        VaultManager.register("team", shared.to_str().unwrap()).unwrap();
+        VaultManager.create("team", &secret).unwrap();
+        VaultManager.save("team", &secret, &VaultManager.load("team", &secret).unwrap()).unwrap();
+        assert!(shared.exists());
+        assert_eq!(VaultManager.list().unwrap(), vec![("team".to_string(), shared.display().to_string())]);
+        let registry = VaultRegistry::load(&ConfigService::registry_path()).unwrap();
+        assert_eq!(registry.get("team"), Some(shared.to_str().unwrap()));
+
+        // another location for a name already in use is refused
+        let other = dir.join("other.vault");
+        assert!(matches!(VaultManager.register("team", other.to_str().unwrap()), Err(VaultError::AlreadyExists(_))));
+
+        // moving into the default directory drops the registry entry, backups move along
+        let moved = VaultManager.relocate("team", default.parent().unwrap().to_str().unwrap()).unwrap();
+        assert_eq!(moved, default.display().to_string());
+        assert!(!shared.exists());
+        assert_eq!(VaultStorage::backups(&default).unwrap().len(), 1);
+        let registry = VaultRegistry::load(&ConfigService::registry_path()).unwrap();
+        assert_eq!(registry.get("team"), None);
+        assert!(VaultManager.load("team", &secret).is_ok());
+
+        // a rename keeps the vault next to where it was
+        VaultManager.relocate("team", shared.to_str().unwrap()).unwrap();
+        VaultManager.rename("team", "ops").unwrap();
+        let renamed = shared.with_file_name("ops.vault");
+        assert!(!VaultManager.exists("team").unwrap());
+        assert_eq!(VaultManager.file_path("ops").unwrap(), renamed);
+        assert!(VaultManager.load("ops", &secret).is_ok());
+        assert!(!shared.exists());
+
+        // unregistering forgets the location but keeps the file
+        VaultManager.unregister("ops").unwrap();
+        assert_eq!(VaultManager.file_path("ops").unwrap(), dir.join("vaults").join("ops.vault"));
+        assert!(renamed.exists());
+    }
+}
@@ -1,17 +1,30 @@
 #[cfg(test)]
 mod mocks {
+    pub mod mock_config;
     pub mod mock_vault_manager;
 }
 
 #[cfg(test)]
 mod test_vault_service {
+    use std::collections::HashSet;
+    use passman::domain::app::error::AppError;
     use passman::domain::app::state::AppState;
     use passman::services::vault_service::VaultService;
-    use secrecy::SecretBox;
+    use secrecy::{ExposeSecret, SecretBox};
+    use passman::domain::cli::audit_params::{AuditCheck, AuditParams, AuditSort};
     use passman::domain::cli::field::Field;
+    use passman::domain::cli::import_params::DuplicateStrategy;
+    use passman::domain::vault::password_entry::PasswordEntry;
+    use passman::domain::vault::vault::Vault;
+    use passman::domain::vault::vault_file::{VaultFile, VaultHeader};
+    use passman::repository::vault::vault_crypto::VaultCrypto;
+    use passman::domain::vault::vault_error::VaultError;
+    use chrono::{Duration, Utc};
+    use crate::mocks::mock_config;
     use crate::mocks::mock_vault_manager::MockVaultManager;
 
     fn setup() -> (AppState, VaultService<MockVaultManager>, SecretBox<String>) {
+        mock_config::isolate();
         let state = AppState { session: None };
         let service = VaultService::new(MockVaultManager::new());
         let secret = SecretBox::new(Box::new("test_password".to_string()));
@@ -24,7 +37,7 @@ mod test_vault_service {
         let vault = "test_vault";
 
         // create vault
-        service.create(vault, &secret);
+        service.create(vault, &secret).unwrap();
 
         // open vault
         service.open(vault, &secret, &mut state).unwrap();
@@ -40,7 +53,7 @@ mod test_vault_service {
         let vault = "test_vault";
 
         // setup
-        service.create(vault, &secret);
+        service.create(vault, &secret).unwrap();
         service.open(vault, &secret, &mut state).unwrap();
 
         // close vault
@@ -48,6 +61,30 @@ mod test_vault_service {
         assert!(state.session.is_none());
     }
 
+    #[test]
+    fn test_session_expiry() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        assert!(service.expire(&mut state).is_none());
+
+        // activity cannot extend the session past its lifetime
+        let limit = Utc::now() + Duration::seconds(30);
+        state.session.as_mut().unwrap().max_expires_at = limit;
+        service.is_accessible(&mut state).unwrap();
+        assert_eq!(state.session.as_ref().unwrap().expires_at, limit);
+
+        // an idle session locks without waiting for a command
+        state.session.as_mut().unwrap().expires_at = Utc::now() - Duration::seconds(1);
+        let notice = service.expire(&mut state).unwrap();
+        assert!(notice.contains("inactivity"));
+        assert!(state.session.is_none());
+        assert!(service.expire(&mut state).is_none());
+    }
+
     #[test]
     fn test_add_entry() {
         let (mut state, service, secret) = setup();
@@ -55,11 +92,11 @@ mod test_vault_service {
         let entry = "test_entry";
 
         // setup
-        service.create(vault, &secret);
+        service.create(vault, &secret).unwrap();
         service.open(vault, &secret, &mut state).unwrap();
 
         // add entry
-        service.add_entry(entry, "user", "pass", &mut state);
+        service.add_entry(entry, "user", "pass", &mut state).unwrap();
 
         // check result
         let result = service.show(Some(entry.to_string()), false, &mut state);
@@ -74,9 +111,9 @@ mod test_vault_service {
         let entry = "test_entry";
 
         // setup
-        service.create(vault, &secret);
+        service.create(vault, &secret).unwrap();
         service.open(vault, &secret, &mut state).unwrap();
-        service.add_entry(entry, "user", "pass", &mut state);
+        service.add_entry(entry, "user", "pass", &mut state).unwrap();
 
         // update entry
         let _ = service.update_entry(entry, &Field::Username, "new_user", &mut state);
@@ -96,11 +133,11 @@ mod test_vault_service {
         let entry = "test_entry";
 
         // setup
-        service.create(vault, &secret);
+        service.create(vault, &secret).unwrap();
         service.open(vault, &secret, &mut state).unwrap();
 
         // delete vault
-        service.delete(&mut state);
+        service.delete(&mut state).unwrap();
 
         // check result
         let result = service.show(Some(entry.to_string()), false, &mut state);
@@ -114,11 +151,11 @@ mod test_vault_service {
         let vault2 = "test_vault2";
 
         // setup
-        service.create(vault1, &secret);
-        service.create(vault2, &secret);
+        service.create(vault1, &secret).unwrap();
+        service.create(vault2, &secret).unwrap();
 
         // list vaults
-        let result = service.list();
+        let result = service.list().unwrap();
 
         // check result
         assert!(result.contains(vault1));
@@ -132,13 +169,372 @@ mod test_vault_service {
         let wrong_secret = SecretBox::new(Box::new("wrong_pass".to_string()));
 
         // setup
-        service.create(vault1, &secret);
+        service.create(vault1, &secret).unwrap();
 
         // attempt to unlock with wrong password
         let result = service.open(vault1, &wrong_secret, &mut state);
 
         // check result
-        assert!(result.is_err());
+        assert!(matches!(result, Err(AppError::Vault(VaultError::WrongPassword))));
         assert!(state.session.is_none());
+        let result = service.open("missing", &secret, &mut state);
+        assert!(matches!(result, Err(AppError::Vault(VaultError::NotFound(_)))));
+    }
+
+    #[test]
+    fn test_change_password() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let new_secret = SecretBox::new(Box::new("N3w_p@ssword".to_string()));
+        let wrong_secret = SecretBox::new(Box::new("wrong_pass".to_string()));
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("test_entry", "user", "pass", &mut state).unwrap();
+
+        // attempt with wrong current password
+        let new_copy = SecretBox::new(Box::new(new_secret.expose_secret().clone()));
+        assert!(service.change_password(&wrong_secret, new_copy, &mut state).is_err());
+
+        // change password
+        let new_copy = SecretBox::new(Box::new(new_secret.expose_secret().clone()));
+        service.change_password(&secret, new_copy, &mut state).unwrap();
+        service.close(&mut state);
+
+        // check result
+        assert!(service.open(vault, &secret, &mut state).is_err());
+        service.open(vault, &new_secret, &mut state).unwrap();
+        assert_eq!(state.session.as_ref().unwrap().vault.entries.len(), 1);
+    }
+
+    #[test]
+    fn test_change_password_rotates_key() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let new_secret = SecretBox::new(Box::new("N3w_p@ssword".to_string()));
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("test_entry", "user", "pass", &mut state).unwrap();
+        let key = |state: &AppState| *state.session.as_ref().unwrap().vault.key.as_ref().unwrap().expose_secret();
+        let old_key = key(&state);
+
+        // change password
+        service.change_password(&secret, new_secret, &mut state).unwrap();
+
+        // check result
+        assert_ne!(key(&state), old_key);
+        assert!(state.session.as_ref().unwrap().vault.entries.iter().all(|e| e.sealed.is_some()));
+        assert_eq!(service.field("test_entry", &Field::Password, &mut state).unwrap(), "pass");
+    }
+
+    #[test]
+    fn test_read_commands_keep_entries_sealed() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let sealed = |state: &AppState| state.session.as_ref().unwrap().vault.entries.iter().all(|e| e.sealed.is_some());
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("github", "alice", "hunter22", &mut state).unwrap();
+        service.add_entry("gitlab", "bob", "swordfish", &mut state).unwrap();
+        service.close(&mut state);
+        service.open(vault, &secret, &mut state).unwrap();
+        assert!(sealed(&state));
+
+        // read and update plain fields
+        service.show(None, false, &mut state).unwrap();
+        service.search("git", &mut state);
+        service.update_entry("github", &Field::Url, "https://github.com", &mut state).unwrap();
+        service.update_entry("github", &Field::Notes, "work account", &mut state).unwrap();
+        assert!(sealed(&state));
+
+        // read secrets
+        assert!(service.show(None, true, &mut state).unwrap().contains("swordfish"));
+        assert!(service.show(Some("github".to_string()), true, &mut state).unwrap().contains("hunter22"));
+        assert_eq!(service.field("gitlab", &Field::Password, &mut state).unwrap(), "swordfish");
+        service.history("github", true, &mut state).unwrap();
+        let params = AuditParams { checks: vec![AuditCheck::Weak], sort: AuditSort::Name, max_age_days: 365 };
+        service.audit(&params, &mut state).unwrap();
+
+        // check result
+        assert!(sealed(&state));
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("entry1", "user", "pass", &mut state).unwrap();
+        service.add_entry("entry2", "user", "pass", &mut state).unwrap();
+
+        // check backups
+        assert!(service.backups(&mut state).unwrap().starts_with("1. "));
+
+        // restore the generation before the last save
+        service.restore(1, &mut state).unwrap();
+
+        // check result
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.vault.entries.len(), 1);
+        assert_eq!(session.vault.entries[0].name, "entry1");
+        assert!(service.restore(10, &mut state).is_err());
+    }
+
+    #[test]
+    fn test_restore_legacy_backup() {
+        let (mut state, _, secret) = setup();
+        let storage = MockVaultManager::new();
+        let service = VaultService::new(storage.clone());
+        let vault = "test_vault";
+
+        // setup, with a backup written before entries had ids
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        let mut legacy = PasswordEntry::new("entry1", "user", "pass");
+        legacy.id = String::new();
+        let header = VaultHeader::legacy();
+        let (salt, nonce, ciphertext) = VaultCrypto::encrypt(&Vault::new(vec![legacy]), &secret, &header).unwrap();
+        let file = VaultFile { header: None, salt, nonce, ciphertext, entries: Vec::new() };
+        storage.add_backup(vault, serde_json::to_vec(&file).unwrap());
+
+        // restored entries get their ids right away
+        service.restore(1, &mut state).unwrap();
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.vault.entries[0].name, "entry1");
+        assert!(!session.vault.entries[0].id.is_empty());
+    }
+
+    #[test]
+    fn test_update_rich_fields() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let entry = "test_entry";
+        let recovery = Field::Custom { name: "recovery".to_string(), secret: true };
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry(entry, "user", "pass", &mut state).unwrap();
+
+        // update fields
+        service.update_entry(entry, &Field::Url, "https://github.com", &mut state).unwrap();
+        service.update_entry(entry, &Field::Notes, "line 1\nline 2", &mut state).unwrap();
+        service.update_entry(entry, &Field::Tags, "work,dev", &mut state).unwrap();
+        service.update_entry(entry, &recovery, "abcd-efgh", &mut state).unwrap();
+
+        // check result
+        let shown = service.show(Some(entry.to_string()), false, &mut state).unwrap();
+        assert!(shown.contains("https://github.com"));
+        assert!(!shown.contains("abcd-efgh"));
+        let session = state.session.as_ref().unwrap();
+        let stored = &session.vault.entries[0];
+        assert_eq!(stored.tags, vec!["work", "dev"]);
+        assert_eq!(stored.get(&recovery), Some("abcd-efgh".to_string()));
+
+        // empty value removes a custom field
+        service.update_entry(entry, &recovery, "", &mut state).unwrap();
+        assert!(state.session.as_ref().unwrap().vault.entries[0].fields.is_empty());
+    }
+
+    #[test]
+    fn test_entry_history() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let entry = "test_entry";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry(entry, "user", "pass1", &mut state).unwrap();
+
+        // replace the password more times than the history keeps
+        for i in 2..=12 {
+            service.update_entry(entry, &Field::Password, &format!("pass{}", i), &mut state).unwrap();
+        }
+
+        // check history
+        let history = service.history(entry, true, &mut state).unwrap();
+        assert!(history.starts_with("1. ") && history.contains("pass11"));
+        let stored = &state.session.as_ref().unwrap().vault.entries[0];
+        assert_eq!(stored.history.len(), 10);
+        assert_eq!(stored.history[9].password.expose_secret(), "pass2");
+
+        // revert to the previous password
+        service.revert(entry, 1, &mut state).unwrap();
+        let stored = &state.session.as_ref().unwrap().vault.entries[0];
+        assert_eq!(stored.password.expose_secret(), "pass11");
+        assert_eq!(stored.history[0].password.expose_secret(), "pass12");
+        assert!(service.revert(entry, 0, &mut state).is_err());
+        assert!(service.revert(entry, 42, &mut state).is_err());
+    }
+
+    #[test]
+    fn test_replace_entry() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let entry = "test_entry";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry(entry, "user", "pass1", &mut state).unwrap();
+        service.update_entry(entry, &Field::Password, "pass2", &mut state).unwrap();
+        let id = state.session.as_ref().unwrap().vault.entries[0].id.clone();
+
+        // invalid new credentials leave the stored entry untouched
+        assert!(service.replace_entry(entry, "", "pass3", &mut state).is_err());
+        assert!(service.replace_entry(entry, "new_user", "", &mut state).is_err());
+        service.close(&mut state);
+        service.open(vault, &secret, &mut state).unwrap();
+        assert_eq!(service.field(entry, &Field::Username, &mut state).unwrap(), "user");
+        assert_eq!(service.field(entry, &Field::Password, &mut state).unwrap(), "pass2");
+
+        // a valid overwrite keeps the id and the history
+        service.replace_entry(entry, "new_user", "pass3", &mut state).unwrap();
+        service.close(&mut state);
+        service.open(vault, &secret, &mut state).unwrap();
+        assert_eq!(state.session.as_ref().unwrap().vault.entries.len(), 1);
+        assert_eq!(state.session.as_ref().unwrap().vault.entries[0].id, id);
+        assert_eq!(service.field(entry, &Field::Password, &mut state).unwrap(), "pass3");
+        let history = service.history(entry, true, &mut state).unwrap();
+        assert!(history.contains("pass2") && history.contains("pass1"));
+    }
+
+    #[test]
+    fn test_failed_save_keeps_session() {
+        let (mut state, _, secret) = setup();
+        let storage = MockVaultManager::new();
+        let service = VaultService::new(storage.clone());
+        let vault = "test_vault";
+        let entry = "test_entry";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry(entry, "user", "pass1", &mut state).unwrap();
+        service.update_entry(entry, &Field::Password, "pass2", &mut state).unwrap();
+        service.close(&mut state);
+        service.open(vault, &secret, &mut state).unwrap();
+
+        // every change is undone in the session when it cannot be saved
+        storage.fail_writes(true);
+        assert!(service.update_entry(entry, &Field::Password, "pass3", &mut state).is_err());
+        assert!(service.update_entry(entry, &Field::Notes, "note", &mut state).is_err());
+        assert!(service.revert(entry, 1, &mut state).is_err());
+        assert!(service.replace_entry(entry, "new_user", "pass3", &mut state).is_err());
+        assert!(service.delete_entry(entry, &mut state).is_err());
+        assert_eq!(state.session.as_ref().unwrap().vault.entries.len(), 1);
+        assert_eq!(service.field(entry, &Field::Password, &mut state).unwrap(), "pass2");
+        assert!(service.field(entry, &Field::Notes, &mut state).is_err());
+        assert!(!service.history(entry, true, &mut state).unwrap().contains("pass2"));
+
+        // the session still saves once writes work again
+        storage.fail_writes(false);
+        service.update_entry(entry, &Field::Password, "pass3", &mut state).unwrap();
+        service.close(&mut state);
+        service.open(vault, &secret, &mut state).unwrap();
+        assert_eq!(service.field(entry, &Field::Password, &mut state).unwrap(), "pass3");
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_entry_timestamps() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("fresh", "user", "pass", &mut state).unwrap();
+        service.add_entry("old", "user", "pass", &mut state).unwrap();
+        let backups = service.backups(&mut state).unwrap();
+        service.mark_copied("fresh", &mut state).unwrap();
+        service.mark_copied("fresh", &mut state).unwrap();
+
+        // copies do not use up backup slots
+        assert_eq!(service.backups(&mut state).unwrap(), backups);
+
+        // check timestamps
+        let session = state.session.as_mut().unwrap();
+        let fresh = &session.vault.entries[0];
+        assert!(fresh.created_at.is_some() && fresh.updated_at.is_some());
+        assert!(fresh.last_copied_at.is_some());
+        assert!(session.vault.entries[1].last_copied_at.is_none());
+
+        // age one of the passwords
+        let old = &mut session.vault.entries[1];
+        old.password_changed_at = Some(Utc::now() - Duration::days(400));
+
+        // check result
+        let stale = service.stale(365, &mut state);
+        assert!(stale.contains("old (400 days)"));
+        assert!(!stale.contains("fresh"));
+    }
+
+    #[test]
+    fn test_import_duplicates() {
+        let (mut state, service, secret) = setup();
+        let vault = "test_vault";
+        let imported = || vec![
+            PasswordEntry::new("github", "new_user", "new_pass"),
+            PasswordEntry::new("gitlab", "user", "pass"),
+        ];
+
+        // setup
+        service.create(vault, &secret).unwrap();
+        service.open(vault, &secret, &mut state).unwrap();
+        service.add_entry("github", "user", "pass", &mut state).unwrap();
+
+        // skip duplicates
+        let report = service.import_entries(imported(), &DuplicateStrategy::Skip, &mut state).unwrap();
+        assert_eq!((report.added, report.skipped), (1, 1));
+        assert!(service.stale(0, &mut state).contains("gitlab (0 days)"));
+
+        // rename duplicates
+        let report = service.import_entries(imported(), &DuplicateStrategy::Rename, &mut state).unwrap();
+        assert_eq!(report.renamed, 2);
+        assert!(service.show(Some("github_2".to_string()), false, &mut state).is_ok());
+
+        // overwrite duplicates
+        let report = service.import_entries(imported(), &DuplicateStrategy::Overwrite, &mut state).unwrap();
+        assert_eq!(report.overwritten, 2);
+        let session = state.session.as_ref().unwrap();
+        assert_eq!(session.vault.entries.len(), 4);
+        let github = session.vault.entries.iter().find(|e| e.name == "github").unwrap();
+        assert_eq!(github.username, "new_user");
+
+        // imported entries get ids of their own, even when the bundle came from this vault
+        let mut own = PasswordEntry::new("github", "user", "pass");
+        own.id = github.id.clone();
+        service.import_entries(vec![own], &DuplicateStrategy::Rename, &mut state).unwrap();
+        let session = state.session.as_ref().unwrap();
+        let ids: HashSet<&str> = session.vault.entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids.len(), session.vault.entries.len());
+    }
+
+    #[test]
+    fn test_rename_and_move_vault() {
+        let (mut state, service, secret) = setup();
+        service.create("team", &secret).unwrap();
+        service.open("team", &secret, &mut state).unwrap();
+
+        // rename the open vault
+        service.rename("team", "ops", &mut state).unwrap();
+        assert_eq!(state.session.as_ref().unwrap().name, "ops");
+        assert!(service.exists("team").is_err());
+        assert!(service.rename("missing", "other", &mut state).is_err());
+
+        // move it and check the listed location
+        let location = service.relocate("ops", "/mnt/share/ops.vault").unwrap();
+        assert_eq!(location, "/mnt/share/ops.vault");
+        assert!(service.list().unwrap().contains("/mnt/share/ops.vault"));
+        assert!(service.relocate("missing", "/tmp").is_err());
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod test_vault_storage {
+    use std::fs;
+    use passman::domain::vault::vault_error::VaultError;
+    use passman::repository::vault::vault_registry::VaultRegistry;
+    use passman::repository::vault::vault_storage::VaultStorage;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_write_keeps_backup_ring() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.vault");
+
+        // write several generations
+        for i in 0..5 {
+            VaultStorage::write(&path, format!("gen{}", i).as_bytes(), 3).unwrap();
+        }
+
+        // check result
+        assert_eq!(fs::read_to_string(&path).unwrap(), "gen4");
+        let backups = VaultStorage::backups(&path).unwrap();
+        assert_eq!(backups.len(), 3);
+        assert_eq!(fs::read_to_string(&backups[0].1).unwrap(), "gen3");
+        assert_eq!(fs::read_to_string(&backups[2].1).unwrap(), "gen1");
+
+        // no temporary files left behind
+        let files = fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(files, 4);
+    }
+
+    #[test]
+    fn test_restore_backup() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("test.vault");
+        VaultStorage::write(&path, b"old", 3).unwrap();
+        VaultStorage::write(&path, b"new", 3).unwrap();
+
+        // restore previous generation
+        VaultStorage::restore(&path, 0, 3).unwrap();
+
+        // check result
+        assert_eq!(fs::read_to_string(&path).unwrap(), "old");
+        let backups = VaultStorage::backups(&path).unwrap();
+        assert_eq!(fs::read_to_string(&backups[0].1).unwrap(), "new");
+        assert!(matches!(VaultStorage::restore(&path, 5, 3), Err(VaultError::NotFound(_))));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("export.csv");
+        VaultStorage::write(&path, b"secret", 0).unwrap();
+
+        // check result
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_copy_and_remove_with_backups() {
+        let dir = tempdir().unwrap();
+        let from = dir.path().join("team.vault");
+        let to = dir.path().join("shared").join("renamed.vault");
+        for i in 0..3 {
+            VaultStorage::write(&from, format!("gen{}", i).as_bytes(), 5).unwrap();
+        }
+
+        // copy next to a new name, then drop the original
+        VaultStorage::copy(&from, &to).unwrap();
+        assert!(matches!(VaultStorage::copy(&from, &to), Err(VaultError::AlreadyExists(_))));
+        VaultStorage::remove(&from).unwrap();
+
+        // check result
+        assert!(!from.exists());
+        assert!(VaultStorage::backups(&from).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&to).unwrap(), "gen2");
+        let backups = VaultStorage::backups(&to).unwrap();
+        assert_eq!(backups.len(), 2);
+        assert_eq!(fs::read_to_string(&backups[0].1).unwrap(), "gen1");
+    }
+
+    #[test]
+    fn test_failed_copy_leaves_nothing_behind() {
+        let dir = tempdir().unwrap();
+        let from = dir.path().join("team.vault");
+        let to = dir.path().join("shared").join("team.vault");
+        for i in 0..3 {
+            VaultStorage::write(&from, format!("gen{}", i).as_bytes(), 5).unwrap();
+        }
+
+        // a directory where the oldest backup would be copied to
+        let (date, _) = VaultStorage::backups(&from).unwrap().pop().unwrap();
+        fs::create_dir_all(to.with_file_name(VaultStorage::backup_name("team", &date))).unwrap();
+        assert!(VaultStorage::copy(&from, &to).is_err());
+
+        // check result
+        assert!(!to.exists());
+        assert_eq!(fs::read_dir(to.parent().unwrap()).unwrap().count(), 1);
+        assert_eq!(VaultStorage::backups(&from).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("vaults.toml");
+        assert_eq!(VaultRegistry::load(&path).unwrap(), VaultRegistry::default());
+
+        let mut registry = VaultRegistry::default();
+        registry.insert("team", "/mnt/share/team.vault");
+        registry.insert("ops", "/srv/git/ops.vault");
+        registry.insert("shared", "sqlite:/srv/vaults.db#shared");
+        registry.save(&path).unwrap();
+
+        let loaded = VaultRegistry::load(&path).unwrap();
+        assert_eq!(loaded.get("team"), Some("/mnt/share/team.vault"));
+        assert_eq!(loaded.get("shared"), Some("sqlite:/srv/vaults.db#shared"));
+        assert_eq!(loaded.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>(), vec!["ops", "shared", "team"]);
+    }
+}